@@ -13,12 +13,12 @@ fn main() {
                 WindowEvent::Key(button, Action::Press, _) => {
                     println!("You pressed the button: {:?}", button);
                     println!("Do not try to press escape: the event is inhibited!");
-                    event.inhibited = true // override the default keyboard handler
+                    event.inhibit(); // override the default keyboard handler
                 }
                 WindowEvent::Key(button, Action::Release, _) => {
                     println!("You released the button: {:?}", button);
                     println!("Do not try to press escape: the event is inhibited!");
-                    event.inhibited = true // override the default keyboard handler
+                    event.inhibit(); // override the default keyboard handler
                 }
                 WindowEvent::MouseButton(button, Action::Press, mods) => {
                     println!("You pressed the mouse button: {:?}", button);
@@ -0,0 +1,23 @@
+extern crate kiss3d;
+extern crate nalgebra as na;
+
+use kiss3d::camera::ArcBall;
+use kiss3d::light::Light;
+use kiss3d::window::Window;
+use na::Point3;
+
+fn main() {
+    let mut front = Window::new_with_size("Kiss3d: multi_view (front)", 400, 400);
+    let mut top = Window::new_with_size("Kiss3d: multi_view (top)", 400, 400);
+    let mut front_camera = ArcBall::new(Point3::new(0.0, 0.0, 3.0), Point3::origin());
+    let mut top_camera = ArcBall::new(Point3::new(0.0, 3.0, 0.01), Point3::origin());
+
+    // Both windows share their meshes, but each one has its own scene.
+    for window in [&mut front, &mut top].iter_mut() {
+        let mut c = window.add_cone(0.5, 1.0);
+        c.set_color(1.0, 0.0, 0.0);
+        window.set_light(Light::StickToCamera);
+    }
+
+    while front.render_with_camera(&mut front_camera) && top.render_with_camera(&mut top_camera) {}
+}
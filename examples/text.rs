@@ -1,7 +1,7 @@
 extern crate kiss3d;
 extern crate nalgebra as na;
 
-use kiss3d::text::Font;
+use kiss3d::text::{Font, TextAlign};
 use kiss3d::window::Window;
 use na::{Point2, Point3};
 
@@ -27,5 +27,15 @@ fn main() {
             &font,
             &Point3::new(1.0, 1.0, 0.0),
         );
+
+        window.draw_text_ex(
+            "This paragraph is right-aligned and wrapped at word boundaries.",
+            &Point2::new(1500.0, 240.0),
+            60.0,
+            &font,
+            &Point3::new(1.0, 0.5, 1.0),
+            TextAlign::Right,
+            Some(800.0),
+        );
     }
 }
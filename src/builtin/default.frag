@@ -6,30 +6,120 @@
 #endif
 
 varying vec3 local_light_position;
+varying float light_w;
 varying vec2 tex_coord_v;
 varying vec3 normalInterp;
 varying vec3 vertPos;
+varying vec3 worldPos;
 
 uniform vec3 color;
+uniform vec3 back_color;
+uniform vec3 ambient;
 uniform sampler2D tex;
+// The sampled layer, the number of layers stacked vertically in `tex`, the height of a layer and
+// the height of the padding above and below it, in texels.
+uniform vec4 tex_layer;
+uniform samplerCube env_map;
+uniform mat3 env_rotation;
+uniform float reflectivity;
+uniform float alpha;
+uniform int shadows;
+uniform sampler2D shadow_map;
+uniform mat4 shadow_view_proj;
+uniform vec3 shadow_light_position;
+uniform float shadow_far;
+uniform float shadow_texel_size;
+// 0: regular rendering, 1: OIT accumulation, 2: OIT revealage.
+uniform int oit_pass;
+// 1 if the colors are already multiplied by their alpha, see `BlendMode::PremultipliedAlpha`.
+uniform int premultiplied;
 const vec3 specColor = vec3(0.4, 0.4, 0.4);
 
+float unpack_distance(vec4 packed) {
+  return dot(packed, vec4(1.0, 1.0 / 255.0, 1.0 / 65025.0, 1.0 / 16581375.0));
+}
+
+// The fraction of the light reaching the fragment, from 0 (fully shadowed) to 1.
+float light_visibility() {
+  vec4 light_pos = shadow_view_proj * vec4(worldPos, 1.0);
+  vec2 uv = light_pos.xy / light_pos.w * 0.5 + 0.5;
+
+  if (light_pos.w <= 0.0 || uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+    return 1.0;
+  }
+
+  float dist = length(worldPos - shadow_light_position) / shadow_far;
+  // Avoid self-shadowing artifacts.
+  float bias = 0.0005;
+  float visibility = 0.0;
+
+  // Smooth the shadow edges by averaging neighboring samples.
+  for (int i = 0; i < 2; i++) {
+    for (int j = 0; j < 2; j++) {
+      vec2 offset = (vec2(float(i), float(j)) - 0.5) * shadow_texel_size;
+      float occluder = unpack_distance(texture2D(shadow_map, uv + offset));
+      visibility += dist - bias > occluder ? 0.0 : 0.25;
+    }
+  }
+
+  return visibility;
+}
+
 void main() {
   vec3 normal = normalize(normalInterp);
-  vec3 lightDir = normalize(local_light_position - vertPos);
+  vec3 base_color = gl_FrontFacing ? color : back_color;
+  // Directional lights store the direction toward the light instead of a position.
+  vec3 lightDir = light_w < 0.5 ? normalize(local_light_position)
+                                : normalize(local_light_position - vertPos);
 
   float lambertian = max(dot(lightDir, normal), 0.0);
   float specular = 0.0;
 
+  float visibility = shadows != 0 ? light_visibility() : 1.0;
+  lambertian *= visibility;
+
   if(lambertian > 0.0) {
     vec3 viewDir = normalize(-vertPos);
     vec3 halfDir = normalize(lightDir + viewDir);
     float specAngle = max(dot(halfDir, normal), 0.0);
-    specular = pow(specAngle, 30.0);
+    specular = pow(specAngle, 30.0) * visibility;
   }
 
-  vec4 tex_color = texture2D(tex, tex_coord_v);
-  gl_FragColor = tex_color * vec4(color / 3.0 +
-                                  lambertian * color / 3.0 +
-                                  specular * specColor / 3.0, 1.0);
+  vec2 uv = tex_coord_v;
+
+  if (tex_layer.y > 1.0) {
+    // Stay half a texel away from the edges so the filtering does not reach the next layer.
+    float half_texel = 0.5 / tex_layer.z;
+    float slot = tex_layer.z + 2.0 * tex_layer.w;
+    float v = clamp(uv.y, half_texel, 1.0 - half_texel);
+    uv.y = (tex_layer.x * slot + tex_layer.w + v * tex_layer.z) / (tex_layer.y * slot);
+  }
+
+  vec4 tex_color = texture2D(tex, uv);
+  vec4 lit_color = tex_color * vec4(ambient * base_color +
+                                    lambertian * base_color / 3.0 +
+                                    specular * specColor / 3.0, 1.0);
+
+  if (reflectivity > 0.0) {
+    // Reflect the view direction in view space, then rotate it to world space.
+    vec3 reflected = env_rotation * reflect(normalize(vertPos), normal);
+    vec3 env_color = textureCube(env_map, reflected).rgb;
+    lit_color.rgb = mix(lit_color.rgb, env_color, reflectivity);
+  }
+
+  lit_color.a *= alpha;
+
+  if (oit_pass == 1) {
+    // Weight the closest fragments more, see McGuire and Bavoil, "Weighted Blended
+    // Order-Independent Transparency". The accumulation buffer has half-float channels so the
+    // weight only needs to be bounded.
+    float weight = clamp(10.0 / (1.0 + abs(vertPos.z)), 0.1, 1.0);
+    // Premultiplied colors only need to be multiplied by the opacity of the object.
+    vec3 rgb = lit_color.rgb * (premultiplied != 0 ? alpha : lit_color.a);
+    gl_FragColor = vec4(rgb, lit_color.a) * weight;
+  } else if (oit_pass == 2) {
+    gl_FragColor = vec4(lit_color.a);
+  } else {
+    gl_FragColor = lit_color;
+  }
 }
@@ -5,18 +5,24 @@ attribute vec3 normal;
 
 uniform mat3 ntransform, scale;
 uniform mat4 proj, view, transform;
-uniform vec3 light_position;
+// The homogeneous light position: `w` is 0 for directional lights.
+uniform vec4 light_position;
 
 varying vec3 local_light_position;
+varying float light_w;
 varying vec2 tex_coord_v;
 varying vec3 normalInterp;
 varying vec3 vertPos;
+varying vec3 worldPos;
 
 void main(){
     gl_Position = proj * view * transform * vec4(scale * position, 1.0);
-    vec4 vertPos4 = view * transform * vec4(scale * position, 1.0);
+    vec4 worldPos4 = transform * vec4(scale * position, 1.0);
+    worldPos = vec3(worldPos4) / worldPos4.w;
+    vec4 vertPos4 = view * worldPos4;
     vertPos = vec3(vertPos4) / vertPos4.w;
     normalInterp = mat3(view) * ntransform * normal;
     tex_coord_v = tex_coord;
-    local_light_position = (view * vec4(light_position, 1.0)).xyz;
+    local_light_position = (view * light_position).xyz;
+    light_w = light_position.w;
 }
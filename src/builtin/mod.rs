@@ -1,13 +1,22 @@
 //! Built-in geometries, shaders and effects.
 
 pub use self::normals_material::{NormalsMaterial, NORMAL_FRAGMENT_SRC, NORMAL_VERTEX_SRC};
+pub(crate) use self::object_material::set_wireframe_mode;
 pub use self::object_material::{ObjectMaterial, OBJECT_FRAGMENT_SRC, OBJECT_VERTEX_SRC};
+pub use self::point_cloud_material::{
+    PointCloudMaterial, POINT_CLOUD_FRAGMENT_SRC, POINT_CLOUD_VERTEX_SRC,
+};
 pub use self::uvs_material::{UvsMaterial, UVS_FRAGMENT_SRC, UVS_VERTEX_SRC};
+pub use self::wireframe_material::{
+    WireframeMaterial, WIREFRAME_FRAGMENT_SRC, WIREFRAME_VERTEX_SRC,
+};
 
 pub use self::planar_object_material::PlanarObjectMaterial;
 
 mod normals_material;
 mod object_material;
+mod point_cloud_material;
 mod uvs_material;
+mod wireframe_material;
 
 mod planar_object_material;
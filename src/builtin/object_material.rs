@@ -1,12 +1,22 @@
 use crate::camera::Camera;
 use crate::context::Context;
 use crate::light::Light;
+use crate::renderer::{shadow_map, transparency};
 use crate::resource::vertex_index::VERTEX_INDEX_TYPE;
 use crate::resource::Material;
-use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
-use crate::scene::ObjectData;
+use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform, TEXTURE_ARRAY_PADDING};
+use crate::scene::{apply_cull_mode, BlendMode, ObjectData};
 use crate::{ignore, verify};
-use na::{Isometry3, Matrix3, Matrix4, Point2, Point3, Vector3};
+use na::{Isometry3, Matrix3, Matrix4, Point2, Point3, Vector3, Vector4};
+use std::cell::Cell;
+
+thread_local!(static WIREFRAME_MODE: Cell<bool> = Cell::new(false));
+
+/// Sets whether the objects are drawn as wireframes, regardless of their surface rendering and
+/// lines width.
+pub(crate) fn set_wireframe_mode(enabled: bool) {
+    WIREFRAME_MODE.with(|w| w.set(enabled))
+}
 
 /// The default material used to draw objects.
 pub struct ObjectMaterial {
@@ -14,13 +24,63 @@ pub struct ObjectMaterial {
     pos: ShaderAttribute<Point3<f32>>,
     normal: ShaderAttribute<Vector3<f32>>,
     tex_coord: ShaderAttribute<Point2<f32>>,
-    light: ShaderUniform<Point3<f32>>,
+    light: ShaderUniform<Vector4<f32>>,
     color: ShaderUniform<Point3<f32>>,
+    back_color: ShaderUniform<Point3<f32>>,
+    ambient: ShaderUniform<Point3<f32>>,
+    ambient_color: Point3<f32>,
     transform: ShaderUniform<Matrix4<f32>>,
     scale: ShaderUniform<Matrix3<f32>>,
     ntransform: ShaderUniform<Matrix3<f32>>,
     proj: ShaderUniform<Matrix4<f32>>,
     view: ShaderUniform<Matrix4<f32>>,
+    env_map: ShaderUniform<i32>,
+    env_rotation: ShaderUniform<Matrix3<f32>>,
+    reflectivity: ShaderUniform<f32>,
+    alpha: ShaderUniform<f32>,
+    tex_layer: ShaderUniform<Vector4<f32>>,
+    oit_pass: ShaderUniform<i32>,
+    premultiplied: ShaderUniform<i32>,
+    shadows: ShaderUniform<i32>,
+    shadow_map: ShaderUniform<i32>,
+    shadow_view_proj: ShaderUniform<Matrix4<f32>>,
+    shadow_light_position: ShaderUniform<Point3<f32>>,
+    shadow_far: ShaderUniform<f32>,
+    shadow_texel_size: ShaderUniform<f32>,
+    outline: OutlineEffect,
+}
+
+/// The shader used to draw the outline of objects.
+struct OutlineEffect {
+    effect: Effect,
+    pos: ShaderAttribute<Point3<f32>>,
+    normal: ShaderAttribute<Vector3<f32>>,
+    color: ShaderUniform<Point3<f32>>,
+    width: ShaderUniform<f32>,
+    transform: ShaderUniform<Matrix4<f32>>,
+    scale: ShaderUniform<Matrix3<f32>>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+}
+
+impl OutlineEffect {
+    fn new() -> OutlineEffect {
+        let mut effect = Effect::new_from_str(OUTLINE_VERTEX_SRC, OUTLINE_FRAGMENT_SRC);
+
+        effect.use_program();
+
+        OutlineEffect {
+            pos: effect.get_attrib("position").unwrap(),
+            normal: effect.get_attrib("normal").unwrap(),
+            color: effect.get_uniform("color").unwrap(),
+            width: effect.get_uniform("width").unwrap(),
+            transform: effect.get_uniform("transform").unwrap(),
+            scale: effect.get_uniform("scale").unwrap(),
+            view: effect.get_uniform("view").unwrap(),
+            proj: effect.get_uniform("proj").unwrap(),
+            effect,
+        }
+    }
 }
 
 impl ObjectMaterial {
@@ -38,12 +98,29 @@ impl ObjectMaterial {
             tex_coord: effect.get_attrib("tex_coord").unwrap(),
             light: effect.get_uniform("light_position").unwrap(),
             color: effect.get_uniform("color").unwrap(),
+            back_color: effect.get_uniform("back_color").unwrap(),
+            ambient: effect.get_uniform("ambient").unwrap(),
+            ambient_color: Point3::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0),
             transform: effect.get_uniform("transform").unwrap(),
             scale: effect.get_uniform("scale").unwrap(),
             ntransform: effect.get_uniform("ntransform").unwrap(),
             view: effect.get_uniform("view").unwrap(),
             proj: effect.get_uniform("proj").unwrap(),
+            env_map: effect.get_uniform("env_map").unwrap(),
+            env_rotation: effect.get_uniform("env_rotation").unwrap(),
+            reflectivity: effect.get_uniform("reflectivity").unwrap(),
+            alpha: effect.get_uniform("alpha").unwrap(),
+            tex_layer: effect.get_uniform("tex_layer").unwrap(),
+            oit_pass: effect.get_uniform("oit_pass").unwrap(),
+            premultiplied: effect.get_uniform("premultiplied").unwrap(),
+            shadows: effect.get_uniform("shadows").unwrap(),
+            shadow_map: effect.get_uniform("shadow_map").unwrap(),
+            shadow_view_proj: effect.get_uniform("shadow_view_proj").unwrap(),
+            shadow_light_position: effect.get_uniform("shadow_light_position").unwrap(),
+            shadow_far: effect.get_uniform("shadow_far").unwrap(),
+            shadow_texel_size: effect.get_uniform("shadow_texel_size").unwrap(),
             effect,
+            outline: OutlineEffect::new(),
         }
     }
 
@@ -59,6 +136,58 @@ impl ObjectMaterial {
         self.normal.disable();
         self.tex_coord.disable();
     }
+
+    /// Draws the outline of an object on the pixels not marked by the stencil buffer.
+    fn render_outline(
+        &mut self,
+        pass: usize,
+        transform: &Matrix4<f32>,
+        scale: &Matrix3<f32>,
+        camera: &mut dyn Camera,
+        data: &ObjectData,
+        mesh: &mut Mesh,
+    ) {
+        let ctxt = Context::get();
+        let outline = &mut self.outline;
+        let color = match data.outline_color() {
+            Some(color) => color,
+            None => return,
+        };
+
+        outline.effect.use_program();
+        outline.pos.enable();
+        outline.normal.enable();
+
+        camera.upload(pass, &mut outline.proj, &mut outline.view);
+
+        unsafe {
+            outline.transform.upload(transform);
+            outline.scale.upload(scale);
+            outline.color.upload(color);
+            outline.width.upload(&data.outline_width());
+
+            mesh.bind_coords(&mut outline.pos);
+            mesh.bind_normals(&mut outline.normal);
+            mesh.bind_faces();
+
+            verify!(ctxt.stencil_func(Context::NOTEQUAL, 1, 0xFF));
+            verify!(ctxt.stencil_mask(0x00));
+            verify!(ctxt.disable(Context::CULL_FACE));
+            let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
+            verify!(ctxt.draw_elements(
+                Context::TRIANGLES,
+                mesh.num_pts() as i32,
+                VERTEX_INDEX_TYPE,
+                0
+            ));
+            verify!(ctxt.stencil_mask(0xFF));
+            verify!(ctxt.disable(Context::STENCIL_TEST));
+        }
+
+        mesh.unbind();
+        outline.pos.disable();
+        outline.normal.disable();
+    }
 }
 
 impl Material for ObjectMaterial {
@@ -83,11 +212,16 @@ impl Material for ObjectMaterial {
         camera.upload(pass, &mut self.proj, &mut self.view);
 
         let pos = match *light {
-            Light::Absolute(ref p) => *p,
-            Light::StickToCamera => camera.eye(),
+            Light::Absolute(ref p) => p.to_homogeneous(),
+            Light::StickToCamera => camera.eye().to_homogeneous(),
+            Light::StickToCameraWithOffset(ref offset) => (camera.eye()
+                + camera.view_transform().inverse_transform_vector(offset))
+            .to_homogeneous(),
+            Light::Directional(ref dir) => (-dir).push(0.0),
         };
 
         self.light.upload(&pos);
+        self.ambient.upload(&self.ambient_color);
 
         /*
          *
@@ -105,16 +239,80 @@ impl Material for ObjectMaterial {
 
             mesh.bind(&mut self.pos, &mut self.normal, &mut self.tex_coord);
 
+            // Always use a separate texture unit, samplers of different types may not share one.
+            self.env_map.upload(&1);
+
+            match data.environment_map() {
+                Some(map) => {
+                    let env_rotation = camera
+                        .view_transform()
+                        .rotation
+                        .inverse()
+                        .to_rotation_matrix()
+                        .into_inner();
+
+                    verify!(ctxt.active_texture(Context::TEXTURE1));
+                    verify!(ctxt.bind_texture(Context::TEXTURE_CUBE_MAP, Some(map.texture())));
+                    self.env_rotation.upload(&env_rotation);
+                    self.reflectivity.upload(&data.reflectivity());
+                }
+                None => self.reflectivity.upload(&0.0),
+            }
+
+            // Like the environment map, the shadow map uses its own texture unit.
+            self.shadow_map.upload(&2);
+
+            match shadow_map::shadow_map() {
+                Some(shadow_map) => {
+                    verify!(ctxt.active_texture(Context::TEXTURE2));
+                    verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*shadow_map.texture)));
+                    self.shadows.upload(&1);
+                    self.shadow_view_proj.upload(&shadow_map.view_proj);
+                    self.shadow_light_position
+                        .upload(&shadow_map.light_position);
+                    self.shadow_far.upload(&shadow_map.far);
+                    self.shadow_texel_size.upload(&shadow_map.texel_size);
+                }
+                None => self.shadows.upload(&0),
+            }
+
             verify!(ctxt.active_texture(Context::TEXTURE0));
             verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*data.texture())));
+            self.tex_layer.upload(&Vector4::new(
+                data.texture_layer() as f32,
+                data.texture_layer_count() as f32,
+                data.texture_layer_height() as f32,
+                TEXTURE_ARRAY_PADDING as f32,
+            ));
+
+            self.alpha.upload(&data.alpha());
+            self.oit_pass.upload(&(transparency::oit_pass() as i32));
+            self.premultiplied
+                .upload(&((data.blend_mode() == BlendMode::PremultipliedAlpha) as i32));
 
-            if data.surface_rendering_active() {
+            if data.outline_color().is_some() {
+                // Mark the pixels covered by the object so the outline is drawn around them.
+                verify!(ctxt.enable(Context::STENCIL_TEST));
+                verify!(ctxt.stencil_func(Context::ALWAYS, 1, 0xFF));
+                verify!(ctxt.stencil_op(Context::KEEP, Context::KEEP, Context::REPLACE));
+                verify!(ctxt.stencil_mask(0xFF));
+            }
+
+            let wireframe = WIREFRAME_MODE.with(|w| w.get());
+
+            if data.surface_rendering_active() && !wireframe {
                 self.color.upload(data.color());
+                self.back_color
+                    .upload(data.backface_color().unwrap_or(data.color()));
 
-                if data.backface_culling_enabled() {
-                    verify!(ctxt.enable(Context::CULL_FACE));
-                } else {
-                    verify!(ctxt.disable(Context::CULL_FACE));
+                apply_cull_mode(data.cull_mode());
+
+                // Push the surface behind the lines drawn on top of it to avoid z-fighting.
+                let offset_surface = data.lines_width() != 0.0;
+
+                if offset_surface {
+                    verify!(ctxt.enable(Context::POLYGON_OFFSET_FILL));
+                    verify!(ctxt.polygon_offset(1.0, 1.0));
                 }
 
                 let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
@@ -124,14 +322,24 @@ impl Material for ObjectMaterial {
                     VERTEX_INDEX_TYPE,
                     0
                 ));
+
+                if offset_surface {
+                    verify!(ctxt.disable(Context::POLYGON_OFFSET_FILL));
+                }
             }
 
-            if data.lines_width() != 0.0 {
-                self.color
-                    .upload(data.lines_color().unwrap_or(data.color()));
+            if data.lines_width() != 0.0 || wireframe {
+                let lines_color = data.lines_color().unwrap_or(data.color());
+                let lines_width = if data.lines_width() != 0.0 {
+                    data.lines_width()
+                } else {
+                    1.0
+                };
+                self.color.upload(lines_color);
+                self.back_color.upload(lines_color);
 
                 verify!(ctxt.disable(Context::CULL_FACE));
-                ignore!(ctxt.line_width(data.lines_width()));
+                ignore!(ctxt.line_width(lines_width));
 
                 if verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::LINE)) {
                     verify!(ctxt.draw_elements(
@@ -154,6 +362,7 @@ impl Material for ObjectMaterial {
 
             if data.points_size() != 0.0 {
                 self.color.upload(data.color());
+                self.back_color.upload(data.color());
 
                 verify!(ctxt.disable(Context::CULL_FACE));
                 ctxt.point_size(data.points_size());
@@ -178,6 +387,19 @@ impl Material for ObjectMaterial {
 
         mesh.unbind();
         self.deactivate();
+
+        self.render_outline(
+            pass,
+            &formated_transform,
+            &formated_scale,
+            camera,
+            data,
+            mesh,
+        );
+    }
+
+    fn set_ambient_light(&mut self, color: &Point3<f32>) {
+        self.ambient_color = *color;
     }
 }
 
@@ -191,3 +413,31 @@ const A_VERY_LONG_STRING: &str = include_str!("default.vert");
 // phong-like lighting (heavily) inspired
 // http://www.mathematik.uni-marburg.de/~thormae/lectures/graphics1/code/WebGLShaderLightMat/ShaderLightMat.html
 const ANOTHER_VERY_LONG_STRING: &str = include_str!("default.frag");
+
+/// Vertex shader used to draw the outline of objects.
+static OUTLINE_VERTEX_SRC: &str = "#version 100
+attribute vec3 position;
+attribute vec3 normal;
+
+uniform mat3 scale;
+uniform mat4 proj, view, transform;
+uniform float width;
+
+void main() {
+    vec3 extruded = scale * position + normalize(normal) * width;
+    gl_Position = proj * view * transform * vec4(extruded, 1.0);
+}";
+
+/// Fragment shader used to draw the outline of objects.
+static OUTLINE_FRAGMENT_SRC: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+uniform vec3 color;
+
+void main() {
+    gl_FragColor = vec4(color, 1.0);
+}";
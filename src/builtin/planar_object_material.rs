@@ -13,6 +13,8 @@ pub struct PlanarObjectMaterial {
     pos: ShaderAttribute<Point2<f32>>,
     tex_coord: ShaderAttribute<Point2<f32>>,
     color: ShaderUniform<Point3<f32>>,
+    tex_offset: ShaderUniform<Vector2<f32>>,
+    tex_scale: ShaderUniform<Vector2<f32>>,
     scale: ShaderUniform<Matrix2<f32>>,
     model: ShaderUniform<Matrix3<f32>>,
     view: ShaderUniform<Matrix3<f32>>,
@@ -32,6 +34,8 @@ impl PlanarObjectMaterial {
             pos: effect.get_attrib("position").unwrap(),
             tex_coord: effect.get_attrib("tex_coord").unwrap(),
             color: effect.get_uniform("color").unwrap(),
+            tex_offset: effect.get_uniform("tex_offset").unwrap(),
+            tex_scale: effect.get_uniform("tex_scale").unwrap(),
             scale: effect.get_uniform("scale").unwrap(),
             model: effect.get_uniform("model").unwrap(),
             view: effect.get_uniform("view").unwrap(),
@@ -78,10 +82,13 @@ impl PlanarMaterial for PlanarObjectMaterial {
          */
         let formated_transform = model.to_homogeneous();
         let formated_scale = Matrix2::from_diagonal(&Vector2::new(scale.x, scale.y));
+        let (tex_min, tex_max) = data.texture_rect();
 
         unsafe {
             self.model.upload(&formated_transform);
             self.scale.upload(&formated_scale);
+            self.tex_offset.upload(&tex_min.coords);
+            self.tex_scale.upload(&(tex_max - tex_min));
 
             mesh.bind(&mut self.pos, &mut self.tex_coord);
 
@@ -168,6 +175,7 @@ attribute vec2 tex_coord;
 
 uniform mat2 scale;
 uniform mat3 proj, view, model;
+uniform vec2 tex_offset, tex_scale;
 
 varying vec2 tex_coord_v;
 
@@ -176,7 +184,7 @@ void main(){
     projected_pos.z = 0.0;
 
     gl_Position = vec4(projected_pos, 1.0);
-    tex_coord_v = tex_coord;
+    tex_coord_v = tex_offset + tex_coord * tex_scale;
 }";
 
 const ANOTHER_VERY_LONG_STRING: &str = "#version 100
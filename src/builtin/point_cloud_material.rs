@@ -0,0 +1,122 @@
+use crate::camera::Camera;
+use crate::context::Context;
+use crate::light::Light;
+use crate::resource::Material;
+use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
+use crate::scene::ObjectData;
+use crate::verify;
+use na::{Isometry3, Matrix3, Matrix4, Point3, Vector3};
+
+/// A material that draws the vertices of an object as points, using the per-vertex colors of its
+/// mesh.
+///
+/// Objects whose mesh has no colors are not drawn.
+pub struct PointCloudMaterial {
+    shader: Effect,
+    position: ShaderAttribute<Point3<f32>>,
+    color: ShaderAttribute<Point3<f32>>,
+    size: ShaderUniform<f32>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+    transform: ShaderUniform<Matrix4<f32>>,
+    scale: ShaderUniform<Matrix3<f32>>,
+}
+
+impl PointCloudMaterial {
+    /// Creates a new PointCloudMaterial.
+    pub fn new() -> PointCloudMaterial {
+        let mut shader = Effect::new_from_str(POINT_CLOUD_VERTEX_SRC, POINT_CLOUD_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        PointCloudMaterial {
+            position: shader.get_attrib("position").unwrap(),
+            color: shader.get_attrib("color").unwrap(),
+            size: shader.get_uniform("size").unwrap(),
+            transform: shader.get_uniform("transform").unwrap(),
+            scale: shader.get_uniform("scale").unwrap(),
+            view: shader.get_uniform("view").unwrap(),
+            proj: shader.get_uniform("proj").unwrap(),
+            shader,
+        }
+    }
+}
+
+impl Material for PointCloudMaterial {
+    fn render(
+        &mut self,
+        pass: usize,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        camera: &mut dyn Camera,
+        _: &Light,
+        data: &ObjectData,
+        mesh: &mut Mesh,
+    ) {
+        if mesh.colors().is_none() || data.points_size() == 0.0 {
+            return;
+        }
+
+        let ctxt = Context::get();
+        self.shader.use_program();
+        self.position.enable();
+        self.color.enable();
+
+        camera.upload(pass, &mut self.proj, &mut self.view);
+
+        let formated_transform = transform.to_homogeneous();
+        let formated_scale = Matrix3::from_diagonal(&Vector3::new(scale.x, scale.y, scale.z));
+
+        self.transform.upload(&formated_transform);
+        self.scale.upload(&formated_scale);
+        self.size.upload(&data.points_size());
+
+        mesh.bind_coords(&mut self.position);
+        mesh.bind_colors(&mut self.color);
+
+        let num_points = mesh.coords().read().unwrap().len();
+        verify!(ctxt.draw_arrays(Context::POINTS, 0, num_points as i32));
+
+        mesh.unbind();
+
+        self.position.disable();
+        self.color.disable();
+    }
+}
+
+/// A vertex shader for drawing each vertex of an object as a colored point.
+pub static POINT_CLOUD_VERTEX_SRC: &str = A_VERY_LONG_STRING;
+
+/// A fragment shader for drawing each vertex of an object as a colored point.
+pub static POINT_CLOUD_FRAGMENT_SRC: &str = ANOTHER_VERY_LONG_STRING;
+
+const A_VERY_LONG_STRING: &str = "#version 100
+attribute vec3 position;
+attribute vec3 color;
+uniform mat4 proj;
+uniform mat4 view;
+uniform mat4 transform;
+uniform mat3 scale;
+uniform float size;
+varying vec3 Color;
+
+void main() {
+    Color = color;
+    gl_PointSize = size;
+    gl_Position = proj * view * transform * mat4(scale) * vec4(position, 1.0);
+}
+";
+
+const ANOTHER_VERY_LONG_STRING: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+varying vec3 Color;
+
+void main() {
+    gl_FragColor = vec4(Color, 1.0);
+}
+";
@@ -3,8 +3,7 @@ use crate::context::Context;
 use crate::light::Light;
 use crate::resource::Material;
 use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
-use crate::scene::ObjectData;
-use crate::verify;
+use crate::scene::{apply_cull_mode, ObjectData};
 use na::{Isometry3, Matrix3, Matrix4, Point2, Point3, Vector3};
 
 /// A material that draws normals of an object.
@@ -54,11 +53,7 @@ impl Material for UvsMaterial {
 
         let ctxt = Context::get();
         // enable/disable culling.
-        if data.backface_culling_enabled() {
-            verify!(ctxt.enable(Context::CULL_FACE));
-        } else {
-            verify!(ctxt.disable(Context::CULL_FACE));
-        }
+        apply_cull_mode(data.cull_mode());
 
         self.shader.use_program();
         self.position.enable();
@@ -0,0 +1,121 @@
+use crate::camera::Camera;
+use crate::context::Context;
+use crate::light::Light;
+use crate::resource::vertex_index::VERTEX_INDEX_TYPE;
+use crate::resource::Material;
+use crate::resource::{Effect, Mesh, ShaderAttribute, ShaderUniform};
+use crate::scene::ObjectData;
+use crate::{ignore, verify};
+use na::{Isometry3, Matrix3, Matrix4, Point3, Vector3};
+
+/// A material that draws the explicit edges of a mesh as lines, using the color of its object.
+///
+/// Objects whose mesh has no edges set with `Mesh::set_edges` are not drawn. The lines width is
+/// given by the lines width of the object.
+pub struct WireframeMaterial {
+    shader: Effect,
+    position: ShaderAttribute<Point3<f32>>,
+    color: ShaderUniform<Point3<f32>>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+    transform: ShaderUniform<Matrix4<f32>>,
+    scale: ShaderUniform<Matrix3<f32>>,
+}
+
+impl WireframeMaterial {
+    /// Creates a new WireframeMaterial.
+    pub fn new() -> WireframeMaterial {
+        let mut shader = Effect::new_from_str(WIREFRAME_VERTEX_SRC, WIREFRAME_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        WireframeMaterial {
+            position: shader.get_attrib("position").unwrap(),
+            color: shader.get_uniform("color").unwrap(),
+            transform: shader.get_uniform("transform").unwrap(),
+            scale: shader.get_uniform("scale").unwrap(),
+            view: shader.get_uniform("view").unwrap(),
+            proj: shader.get_uniform("proj").unwrap(),
+            shader,
+        }
+    }
+}
+
+impl Material for WireframeMaterial {
+    fn render(
+        &mut self,
+        pass: usize,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        camera: &mut dyn Camera,
+        _: &Light,
+        data: &ObjectData,
+        mesh: &mut Mesh,
+    ) {
+        let num_edges = match mesh.edges() {
+            Some(edges) => edges.read().unwrap().len(),
+            None => return,
+        };
+
+        if num_edges == 0 || data.lines_width() == 0.0 {
+            return;
+        }
+
+        let ctxt = Context::get();
+        self.shader.use_program();
+        self.position.enable();
+
+        camera.upload(pass, &mut self.proj, &mut self.view);
+
+        let formated_transform = transform.to_homogeneous();
+        let formated_scale = Matrix3::from_diagonal(&Vector3::new(scale.x, scale.y, scale.z));
+
+        self.transform.upload(&formated_transform);
+        self.scale.upload(&formated_scale);
+        self.color
+            .upload(data.lines_color().unwrap_or(data.color()));
+
+        mesh.bind_coords(&mut self.position);
+        mesh.bind_edges();
+
+        ignore!(ctxt.line_width(data.lines_width()));
+        verify!(ctxt.draw_elements(Context::LINES, num_edges as i32 * 2, VERTEX_INDEX_TYPE, 0));
+        ignore!(ctxt.line_width(1.0));
+
+        mesh.unbind();
+
+        self.position.disable();
+    }
+}
+
+/// A vertex shader for drawing the edges of an object with a uniform color.
+pub static WIREFRAME_VERTEX_SRC: &str = A_VERY_LONG_STRING;
+
+/// A fragment shader for drawing the edges of an object with a uniform color.
+pub static WIREFRAME_FRAGMENT_SRC: &str = ANOTHER_VERY_LONG_STRING;
+
+const A_VERY_LONG_STRING: &str = "#version 100
+attribute vec3 position;
+uniform mat4 proj;
+uniform mat4 view;
+uniform mat4 transform;
+uniform mat3 scale;
+
+void main() {
+    gl_Position = proj * view * transform * mat4(scale) * vec4(position, 1.0);
+}
+";
+
+const ANOTHER_VERY_LONG_STRING: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+uniform vec3 color;
+
+void main() {
+    gl_FragColor = vec4(color, 1.0);
+}
+";
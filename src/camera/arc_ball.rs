@@ -15,6 +15,9 @@ use std::f32;
 /// direction
 /// * Scroll in/out - zoom in/out
 /// * Enter key - set the focus point to the origin
+///
+/// With `ArcBall::set_inertia`, the camera keeps rotating after a rotation drag is released,
+/// and slows down over a few frames.
 #[derive(Clone, Debug)]
 pub struct ArcBall {
     /// The focus point.
@@ -45,6 +48,12 @@ pub struct ArcBall {
     drag_button: Option<MouseButton>,
     drag_modifiers: Option<Modifiers>,
     reset_key: Option<Key>,
+    /// Fraction of the angular velocity lost at each frame after a drag. 1.0 disables inertia.
+    inertia_damping: f32,
+    /// The mouse displacement accumulated by the rotation drag during the current frame.
+    drag_displacement: Vector2<f32>,
+    /// The mouse displacement applied at each frame after the rotation drag is released.
+    angular_velocity: Vector2<f32>,
 
     projection: Perspective3<f32>,
     view: Matrix4<f32>,
@@ -54,6 +63,7 @@ pub struct ArcBall {
     last_cursor_pos: Vector2<f32>,
     last_framebuffer_size: Vector2<f32>,
     coord_system: CoordSystemRh,
+    viewport: Option<(i32, i32, i32, i32)>,
 }
 
 impl ArcBall {
@@ -87,6 +97,9 @@ impl ArcBall {
             drag_button: Some(MouseButton::Button2),
             drag_modifiers: None,
             reset_key: Some(Key::Return),
+            inertia_damping: 1.0,
+            drag_displacement: na::zero(),
+            angular_velocity: na::zero(),
             projection: Perspective3::new(800.0 / 600.0, fov, znear, zfar),
             view: na::zero(),
             proj: na::zero(),
@@ -95,6 +108,7 @@ impl ArcBall {
             last_framebuffer_size: Vector2::new(800.0, 600.0),
             last_cursor_pos: na::zero(),
             coord_system: CoordSystemRh::from_up_axis(Vector3::y_axis()),
+            viewport: None,
         };
 
         res.look_at(eye, at);
@@ -102,6 +116,14 @@ impl ArcBall {
         res
     }
 
+    /// Sets the region of the framebuffer this camera renders into, as `(x, y, width, height)` in
+    /// pixels with the origin at the bottom-left corner.
+    ///
+    /// Set to `None` to render into the whole framebuffer.
+    pub fn set_viewport(&mut self, viewport: Option<(i32, i32, i32, i32)>) {
+        self.viewport = viewport;
+    }
+
     /// The point the arc-ball is looking at.
     pub fn at(&self) -> Point3<f32> {
         self.at
@@ -197,6 +219,24 @@ impl ArcBall {
         self.dist_step = dist_step;
     }
 
+    /// The fraction of the angular velocity lost at each frame after a rotation drag is released.
+    pub fn inertia(&self) -> f32 {
+        self.inertia_damping
+    }
+
+    /// Enables smooth rotations that continue after a rotation drag is released.
+    ///
+    /// Once the drag is released, the camera keeps rotating with the velocity of the last frame
+    /// of the drag, and `damping` is the fraction of that velocity lost at each frame. It is
+    /// clamped to `[0.0, 1.0]`, and `1.0` (the default) disables inertia.
+    pub fn set_inertia(&mut self, damping: f32) {
+        self.inertia_damping = damping.max(0.0).min(1.0);
+
+        if self.inertia_damping == 1.0 {
+            self.angular_velocity = na::zero();
+        }
+    }
+
     /// Move and orient the camera such that it looks at a specific point.
     pub fn look_at(&mut self, eye: Point3<f32>, at: Point3<f32>) {
         let dist = (eye - at).norm();
@@ -210,6 +250,7 @@ impl ArcBall {
         self.dist = dist;
         self.yaw = yaw;
         self.pitch = pitch;
+        self.angular_velocity = na::zero();
 
         self.update_restrictions();
         self.update_projviews();
@@ -363,6 +404,10 @@ impl ArcBall {
 }
 
 impl Camera for ArcBall {
+    fn viewport(&self) -> Option<(i32, i32, i32, i32)> {
+        self.viewport
+    }
+
     fn clip_planes(&self) -> (f32, f32) {
         (self.projection.znear(), self.projection.zfar())
     }
@@ -392,6 +437,7 @@ impl Camera for ArcBall {
                             .unwrap_or(true)
                     {
                         let dpos = curr_pos - self.last_cursor_pos;
+                        self.drag_displacement += dpos;
                         self.handle_left_button_displacement(&dpos)
                     }
                 }
@@ -441,7 +487,32 @@ impl Camera for ArcBall {
         self.inverse_proj_view
     }
 
-    fn update(&mut self, _: &Canvas) {}
+    fn update(&mut self, canvas: &Canvas) {
+        let drag_displacement = self.drag_displacement;
+        self.drag_displacement = na::zero();
+
+        if self.inertia_damping == 1.0 {
+            return;
+        }
+
+        let dragging = self
+            .rotate_button
+            .map(|button| canvas.get_mouse_button(button) == Action::Press)
+            .unwrap_or(false);
+
+        if dragging {
+            self.angular_velocity = drag_displacement;
+        } else if self.angular_velocity != na::zero::<Vector2<f32>>() {
+            let velocity = self.angular_velocity;
+            self.handle_left_button_displacement(&velocity);
+            self.angular_velocity *= 1.0 - self.inertia_damping;
+
+            // Stop once the rotation is no longer perceptible.
+            if self.angular_velocity.norm() < 1.0e-2 {
+                self.angular_velocity = na::zero();
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
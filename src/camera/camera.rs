@@ -48,6 +48,15 @@ pub trait Camera {
         1usize
     }
 
+    /// The region of the framebuffer this camera renders into, as `(x, y, width, height)` in
+    /// pixels with the origin at the bottom-left corner.
+    ///
+    /// Returns `None` if the camera renders into the whole framebuffer.
+    #[inline]
+    fn viewport(&self) -> Option<(i32, i32, i32, i32)> {
+        None
+    }
+
     /// Indicates that a pass will begin.
     #[inline]
     fn start_pass(&self, _pass: usize, _canvas: &Canvas) {}
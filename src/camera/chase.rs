@@ -0,0 +1,233 @@
+use crate::camera::Camera;
+use crate::event::WindowEvent;
+use crate::resource::ShaderUniform;
+use crate::window::Canvas;
+use instant::Instant;
+use na::{self, Isometry3, Matrix4, Perspective3, Point3, Vector3};
+use std::f32;
+
+/// A camera that smoothly follows a moving target.
+///
+/// The camera tries to stay at `target + offset` while looking at the target. Its target is
+/// usually updated once per frame, e.g., with the translation of the world transformation of the
+/// followed node:
+///
+/// ```no_run
+/// # use kiss3d::camera::ChaseCamera;
+/// # use kiss3d::scene::SceneNode;
+/// # fn follow(camera: &mut ChaseCamera, node: &SceneNode) {
+/// camera.set_target(node.data().world_transformation().translation.vector.into());
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChaseCamera {
+    target: Point3<f32>,
+    offset: Vector3<f32>,
+    damping: f32,
+    eye: Point3<f32>,
+    at: Point3<f32>,
+    last_update: Option<Instant>,
+    projection: Perspective3<f32>,
+    view: Isometry3<f32>,
+    proj_view: Matrix4<f32>,
+    inv_proj_view: Matrix4<f32>,
+    viewport: Option<(i32, i32, i32, i32)>,
+}
+
+impl ChaseCamera {
+    /// Creates a new chase camera following `target` from `target + offset`.
+    ///
+    /// # Arguments
+    /// * `target` - the initial position of the followed target.
+    /// * `offset` - the position of the camera relative to the target, in world coordinates.
+    /// * `damping` - the time, in seconds, the camera takes to cover about 63% of the distance to
+    ///   its destination. Set to `0.0` to rigidly follow the target.
+    pub fn new(target: Point3<f32>, offset: Vector3<f32>, damping: f32) -> ChaseCamera {
+        ChaseCamera::new_with_frustrum(f32::consts::PI / 4.0, 0.1, 1024.0, target, offset, damping)
+    }
+
+    /// Creates a new chase camera with the given projection parameters.
+    pub fn new_with_frustrum(
+        fov: f32,
+        znear: f32,
+        zfar: f32,
+        target: Point3<f32>,
+        offset: Vector3<f32>,
+        damping: f32,
+    ) -> ChaseCamera {
+        assert!(damping >= 0.0, "The damping must not be negative.");
+
+        let mut res = ChaseCamera {
+            target,
+            offset,
+            damping,
+            eye: target + offset,
+            at: target,
+            last_update: None,
+            projection: Perspective3::new(800.0 / 600.0, fov, znear, zfar),
+            view: Isometry3::identity(),
+            proj_view: na::one(),
+            inv_proj_view: na::one(),
+            viewport: None,
+        };
+
+        res.update_projviews();
+        res
+    }
+
+    /// The position of the followed target.
+    pub fn target(&self) -> Point3<f32> {
+        self.target
+    }
+
+    /// Sets the position of the followed target.
+    ///
+    /// The camera moves toward its new destination during the next updates.
+    pub fn set_target(&mut self, target: Point3<f32>) {
+        self.target = target;
+    }
+
+    /// The position of the camera relative to the target, in world coordinates.
+    pub fn offset(&self) -> Vector3<f32> {
+        self.offset
+    }
+
+    /// Sets the position of the camera relative to the target, in world coordinates.
+    pub fn set_offset(&mut self, offset: Vector3<f32>) {
+        self.offset = offset;
+    }
+
+    /// The time, in seconds, the camera takes to cover about 63% of the distance to its
+    /// destination.
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Sets the time, in seconds, the camera takes to cover about 63% of the distance to its
+    /// destination.
+    ///
+    /// Set to `0.0` to rigidly follow the target.
+    pub fn set_damping(&mut self, damping: f32) {
+        assert!(damping >= 0.0, "The damping must not be negative.");
+        self.damping = damping;
+    }
+
+    /// Moves the camera to its destination immediately, skipping the smoothing.
+    pub fn snap_to_target(&mut self) {
+        self.eye = self.target + self.offset;
+        self.at = self.target;
+        self.update_projviews();
+    }
+
+    /// The vertical field of view of this camera, in radians.
+    pub fn fov(&self) -> f32 {
+        self.projection.fovy()
+    }
+
+    /// Sets the vertical field of view of this camera, in radians.
+    pub fn set_fov(&mut self, fov: f32) {
+        self.projection.set_fovy(fov);
+        self.update_projviews();
+    }
+
+    /// Sets the distances to the near and far clipping planes of this camera.
+    pub fn set_clip_planes(&mut self, znear: f32, zfar: f32) {
+        self.projection.set_znear_and_zfar(znear, zfar);
+        self.update_projviews();
+    }
+
+    /// Sets the region of the framebuffer this camera renders into, as `(x, y, width, height)` in
+    /// pixels with the origin at the bottom-left corner.
+    ///
+    /// Set to `None` to render into the whole framebuffer.
+    pub fn set_viewport(&mut self, viewport: Option<(i32, i32, i32, i32)>) {
+        self.viewport = viewport;
+    }
+
+    fn update_projviews(&mut self) {
+        let dir = self.at - self.eye;
+        let up = if dir.cross(&Vector3::y()).norm_squared() < 1.0e-6 * dir.norm_squared() {
+            Vector3::z()
+        } else {
+            Vector3::y()
+        };
+
+        if dir.norm_squared() > 1.0e-12 {
+            self.view = Isometry3::look_at_rh(&self.eye, &self.at, &up);
+        }
+
+        self.proj_view = self.projection.as_matrix() * self.view.to_homogeneous();
+        let _ = self
+            .proj_view
+            .try_inverse()
+            .map(|inv| self.inv_proj_view = inv);
+    }
+}
+
+impl Camera for ChaseCamera {
+    fn viewport(&self) -> Option<(i32, i32, i32, i32)> {
+        self.viewport
+    }
+
+    fn clip_planes(&self) -> (f32, f32) {
+        (self.projection.znear(), self.projection.zfar())
+    }
+
+    fn view_transform(&self) -> Isometry3<f32> {
+        self.view
+    }
+
+    fn eye(&self) -> Point3<f32> {
+        self.eye
+    }
+
+    fn handle_event(&mut self, _: &Canvas, event: &WindowEvent) {
+        match *event {
+            WindowEvent::FramebufferSize(w, h) => {
+                self.projection.set_aspect(w as f32 / h as f32);
+                self.update_projviews();
+            }
+            _ => {}
+        }
+    }
+
+    #[inline]
+    fn upload(
+        &self,
+        _: usize,
+        proj: &mut ShaderUniform<Matrix4<f32>>,
+        view: &mut ShaderUniform<Matrix4<f32>>,
+    ) {
+        proj.upload(self.projection.as_matrix());
+        view.upload(&self.view.to_homogeneous());
+    }
+
+    fn transformation(&self) -> Matrix4<f32> {
+        self.proj_view
+    }
+
+    fn inverse_transformation(&self) -> Matrix4<f32> {
+        self.inv_proj_view
+    }
+
+    fn update(&mut self, _: &Canvas) {
+        let now = Instant::now();
+        let dt = self
+            .last_update
+            .map(|t| now.duration_since(t).as_secs_f32())
+            .unwrap_or(0.0);
+        self.last_update = Some(now);
+
+        // Exponential smoothing, independent from the frame rate.
+        let t = if self.damping > 0.0 {
+            1.0 - (-dt / self.damping).exp()
+        } else {
+            1.0
+        };
+
+        let dest_eye = self.target + self.offset;
+        self.eye += (dest_eye - self.eye) * t;
+        self.at += (self.target - self.at) * t;
+        self.update_projviews();
+    }
+}
@@ -38,6 +38,7 @@ pub struct FirstPerson {
     inverse_proj_view: Matrix4<f32>,
     last_cursor_pos: Vector2<f32>,
     coord_system: CoordSystemRh,
+    viewport: Option<(i32, i32, i32, i32)>,
 }
 
 impl FirstPerson {
@@ -74,6 +75,7 @@ impl FirstPerson {
             inverse_proj_view: na::zero(),
             last_cursor_pos: na::zero(),
             coord_system: CoordSystemRh::from_up_axis(Vector3::y_axis()),
+            viewport: None,
         };
 
         res.look_at(eye, at);
@@ -81,6 +83,14 @@ impl FirstPerson {
         res
     }
 
+    /// Sets the region of the framebuffer this camera renders into, as `(x, y, width, height)` in
+    /// pixels with the origin at the bottom-left corner.
+    ///
+    /// Set to `None` to render into the whole framebuffer.
+    pub fn set_viewport(&mut self, viewport: Option<(i32, i32, i32, i32)>) {
+        self.viewport = viewport;
+    }
+
     /// Sets the translational increment per arrow press.
     ///
     /// The default value is 0.5.
@@ -361,6 +371,10 @@ impl FirstPerson {
 }
 
 impl Camera for FirstPerson {
+    fn viewport(&self) -> Option<(i32, i32, i32, i32)> {
+        self.viewport
+    }
+
     fn clip_planes(&self) -> (f32, f32) {
         (self.projection.znear(), self.projection.zfar())
     }
@@ -376,7 +390,10 @@ impl Camera for FirstPerson {
                 let curr_pos = Vector2::new(x as f32, y as f32);
 
                 if let Some(rotate_button) = self.rotate_button {
-                    if canvas.get_mouse_button(rotate_button) == Action::Press {
+                    // In relative mouse mode, the `MouseMotion` events rotate the camera instead.
+                    if canvas.get_mouse_button(rotate_button) == Action::Press
+                        && !canvas.relative_mouse_mode()
+                    {
                         let dpos = curr_pos - self.last_cursor_pos;
                         self.handle_left_button_displacement(&dpos)
                     }
@@ -391,6 +408,9 @@ impl Camera for FirstPerson {
 
                 self.last_cursor_pos = curr_pos;
             }
+            WindowEvent::MouseMotion(dx, dy) => {
+                self.handle_left_button_displacement(&Vector2::new(dx as f32, dy as f32))
+            }
             WindowEvent::Scroll(_, off, _) => self.handle_scroll(off as f32),
             WindowEvent::FramebufferSize(w, h) => {
                 self.projection.set_aspect(w as f32 / h as f32);
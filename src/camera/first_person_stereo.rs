@@ -24,6 +24,8 @@ pub struct FirstPersonStereo {
 
     /// Inter Pupilary Distance
     ipd: f32,
+    /// Distance from the eyes to the point both eyes are oriented toward.
+    convergence: f32,
 
     /// Yaw of the camera (rotation along the y axis).
     yaw: f32,
@@ -68,6 +70,7 @@ impl FirstPersonStereo {
             eye_left: Point3::new(0.0, 0.0, 0.0),
             eye_right: Point3::new(0.0, 0.0, 0.0),
             ipd,
+            convergence: 1.0,
             yaw: 0.0,
             pitch: 0.0,
             yaw_step: 0.005,
@@ -182,14 +185,28 @@ impl FirstPersonStereo {
         }
     }
 
+    /// The point the eye at `eye` is oriented toward.
+    fn eye_target(&self, eye: &Point3<f32>) -> Point3<f32> {
+        let dir = self.at() - self.eye;
+
+        if self.convergence.is_finite() {
+            self.eye + dir * self.convergence
+        } else {
+            // Parallel eyes.
+            eye + dir
+        }
+    }
+
     /// The left eye camera view transformation
     fn view_transform_left(&self) -> Isometry3<f32> {
-        Isometry3::look_at_rh(&self.eye_left, &self.at(), &Vector3::y())
+        let target = self.eye_target(&self.eye_left);
+        Isometry3::look_at_rh(&self.eye_left, &target, &Vector3::y())
     }
 
     /// The right eye camera view transformation
     fn view_transform_right(&self) -> Isometry3<f32> {
-        Isometry3::look_at_rh(&self.eye_right, &self.at(), &Vector3::y())
+        let target = self.eye_target(&self.eye_right);
+        Isometry3::look_at_rh(&self.eye_right, &target, &Vector3::y())
     }
 
     /// return Inter Pupilary Distance
@@ -205,6 +222,25 @@ impl FirstPersonStereo {
         self.update_restrictions();
         self.update_projviews();
     }
+
+    /// The distance from the camera to the point both eyes are oriented toward.
+    pub fn convergence(&self) -> f32 {
+        self.convergence
+    }
+
+    /// Sets the distance from the camera to the point both eyes are oriented toward.
+    ///
+    /// Objects at this distance appear at the depth of the screen. Use `f32::INFINITY` for
+    /// parallel eyes. Defaults to `1.0`.
+    pub fn set_convergence(&mut self, convergence: f32) {
+        assert!(
+            convergence > 0.0,
+            "The convergence distance must be positive."
+        );
+        self.convergence = convergence;
+
+        self.update_projviews();
+    }
 }
 
 impl Camera for FirstPersonStereo {
@@ -2,15 +2,17 @@ use crate::camera::Camera;
 use crate::event::WindowEvent;
 use crate::resource::ShaderUniform;
 use crate::window::Canvas;
-use na::{self, Isometry3, Matrix4, Perspective3, Point3};
+use na::{self, Isometry3, Matrix4, Perspective3, Point3, Vector3};
 use std::f32;
 
 /// A camera that cannot move.
 #[derive(Clone, Debug)]
 pub struct FixedView {
+    view: Isometry3<f32>,
     projection: Perspective3<f32>,
     proj: Matrix4<f32>,
     inv_proj: Matrix4<f32>,
+    viewport: Option<(i32, i32, i32, i32)>,
 }
 
 impl FixedView {
@@ -19,17 +21,70 @@ impl FixedView {
         FixedView::new_with_frustrum(f32::consts::PI / 4.0, 0.1, 1024.0)
     }
 
-    /// Creates a new arc ball camera with default sensitivity values.
+    /// Creates a new static camera with the given projection parameters.
     pub fn new_with_frustrum(fov: f32, znear: f32, zfar: f32) -> FixedView {
-        FixedView {
+        FixedView::new_with_projection(fov, znear, zfar)
+    }
+
+    /// Creates a new static camera with the given projection parameters.
+    ///
+    /// # Arguments
+    /// * `fov` - the vertical field of view, in radians.
+    /// * `znear` - the distance to the near clipping plane.
+    /// * `zfar` - the distance to the far clipping plane.
+    pub fn new_with_projection(fov: f32, znear: f32, zfar: f32) -> FixedView {
+        let mut res = FixedView {
+            view: Isometry3::identity(),
             projection: Perspective3::new(800.0 / 600.0, fov, znear, zfar),
             proj: na::one(),
             inv_proj: na::one(),
-        }
+            viewport: None,
+        };
+
+        res.update_projviews();
+        res
+    }
+
+    /// The vertical field of view of this camera, in radians.
+    pub fn fov(&self) -> f32 {
+        self.projection.fovy()
+    }
+
+    /// Sets the vertical field of view of this camera, in radians.
+    pub fn set_fov(&mut self, fov: f32) {
+        self.projection.set_fovy(fov);
+        self.update_projviews();
+    }
+
+    /// Sets the distances to the near and far clipping planes of this camera.
+    pub fn set_clip_planes(&mut self, znear: f32, zfar: f32) {
+        self.projection.set_znear_and_zfar(znear, zfar);
+        self.update_projviews();
+    }
+
+    /// Sets the transformation from world space to the view space of this camera.
+    ///
+    /// The camera looks toward the `-z` axis of its view space. Defaults to the identity.
+    pub fn set_view_transform(&mut self, view: Isometry3<f32>) {
+        self.view = view;
+        self.update_projviews();
+    }
+
+    /// Places this camera at `eye`, looking toward `at`, with the `y` axis up.
+    pub fn look_at(&mut self, eye: Point3<f32>, at: Point3<f32>) {
+        self.set_view_transform(Isometry3::look_at_rh(&eye, &at, &Vector3::y()))
+    }
+
+    /// Sets the region of the framebuffer this camera renders into, as `(x, y, width, height)` in
+    /// pixels with the origin at the bottom-left corner.
+    ///
+    /// Set to `None` to render into the whole framebuffer.
+    pub fn set_viewport(&mut self, viewport: Option<(i32, i32, i32, i32)>) {
+        self.viewport = viewport;
     }
 
     fn update_projviews(&mut self) {
-        self.proj = *self.projection.as_matrix();
+        self.proj = self.projection.as_matrix() * self.view.to_homogeneous();
         let _ = self
             .proj
             .try_inverse()
@@ -38,16 +93,20 @@ impl FixedView {
 }
 
 impl Camera for FixedView {
+    fn viewport(&self) -> Option<(i32, i32, i32, i32)> {
+        self.viewport
+    }
+
     fn clip_planes(&self) -> (f32, f32) {
         (self.projection.znear(), self.projection.zfar())
     }
 
     fn view_transform(&self) -> Isometry3<f32> {
-        Isometry3::identity()
+        self.view
     }
 
     fn eye(&self) -> Point3<f32> {
-        Point3::origin()
+        self.view.inverse_transform_point(&Point3::origin())
     }
 
     fn handle_event(&mut self, _: &Canvas, event: &WindowEvent) {
@@ -67,9 +126,8 @@ impl Camera for FixedView {
         proj: &mut ShaderUniform<Matrix4<f32>>,
         view: &mut ShaderUniform<Matrix4<f32>>,
     ) {
-        let view_mat = Matrix4::identity();
-        proj.upload(&self.proj);
-        view.upload(&view_mat);
+        proj.upload(self.projection.as_matrix());
+        view.upload(&self.view.to_homogeneous());
     }
 
     fn transformation(&self) -> Matrix4<f32> {
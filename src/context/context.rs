@@ -1,5 +1,6 @@
 #![allow(missing_docs)]
 
+use std::cell::RefCell;
 use std::sync::Once;
 
 use crate::resource::GLPrimitive;
@@ -32,6 +33,40 @@ impl Drop for Buffer {
 static mut CONTEXT_SINGLETON: Option<Context> = None;
 static CONTEXT_INIT: Once = Once::new();
 
+/// The severity of a message given to the callback set with `Context::set_debug_callback`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DebugSeverity {
+    /// An OpenGL error, or a debug message about undefined behavior.
+    High,
+    /// A debug message about a major performance or portability issue.
+    Medium,
+    /// A debug message about a minor performance or portability issue.
+    Low,
+    /// A debug message that is neither an error nor a performance issue.
+    Notification,
+}
+
+type DebugCallback = Box<dyn FnMut(&str, DebugSeverity)>;
+
+thread_local!(static DEBUG_CALLBACK: RefCell<Option<DebugCallback>> = RefCell::new(None));
+
+/// Gives a message to the debug callback, if any.
+///
+/// Returns `false` if there is no debug callback.
+pub(crate) fn report_debug_message(message: &str, severity: DebugSeverity) -> bool {
+    DEBUG_CALLBACK.with(|c| match c.try_borrow_mut() {
+        Ok(mut callback) => match &mut *callback {
+            Some(callback) => {
+                callback(message, severity);
+                true
+            }
+            None => false,
+        },
+        // Messages emitted by the callback itself are dropped.
+        Err(_) => true,
+    })
+}
+
 #[derive(Clone)]
 pub struct Context {
     pub ctxt: ContextImpl,
@@ -55,8 +90,16 @@ impl Context {
     pub const DEPTH_ATTACHMENT: u32 = ContextImpl::DEPTH_ATTACHMENT;
     pub const COLOR_ATTACHMENT0: u32 = ContextImpl::COLOR_ATTACHMENT0;
     pub const TEXTURE_2D: u32 = ContextImpl::TEXTURE_2D;
+    pub const TEXTURE_CUBE_MAP: u32 = ContextImpl::TEXTURE_CUBE_MAP;
+    pub const TEXTURE_CUBE_MAP_POSITIVE_X: u32 = ContextImpl::TEXTURE_CUBE_MAP_POSITIVE_X;
     pub const DEPTH_COMPONENT: u32 = ContextImpl::DEPTH_COMPONENT;
     pub const DEPTH_COMPONENT16: u32 = ContextImpl::DEPTH_COMPONENT16;
+    pub const RGBA16F: u32 = ContextImpl::RGBA16F;
+    pub const HALF_FLOAT: u32 = ContextImpl::HALF_FLOAT;
+    pub const DEPTH_STENCIL: u32 = ContextImpl::DEPTH_STENCIL;
+    pub const DEPTH24_STENCIL8: u32 = ContextImpl::DEPTH24_STENCIL8;
+    pub const DEPTH_STENCIL_ATTACHMENT: u32 = ContextImpl::DEPTH_STENCIL_ATTACHMENT;
+    pub const UNSIGNED_INT_24_8: u32 = ContextImpl::UNSIGNED_INT_24_8;
     pub const UNSIGNED_BYTE: u32 = ContextImpl::UNSIGNED_BYTE;
     pub const TEXTURE_WRAP_S: u32 = ContextImpl::TEXTURE_WRAP_S;
     pub const TEXTURE_WRAP_T: u32 = ContextImpl::TEXTURE_WRAP_T;
@@ -69,16 +112,22 @@ impl Context {
     pub const RGBA: u32 = ContextImpl::RGBA;
     pub const TEXTURE0: u32 = ContextImpl::TEXTURE0;
     pub const TEXTURE1: u32 = ContextImpl::TEXTURE1;
+    pub const TEXTURE2: u32 = ContextImpl::TEXTURE2;
     pub const REPEAT: u32 = ContextImpl::REPEAT;
     pub const MIRRORED_REPEAT: u32 = ContextImpl::MIRRORED_REPEAT;
+    pub const TEXTURE_MAX_ANISOTROPY: u32 = ContextImpl::TEXTURE_MAX_ANISOTROPY;
+    pub const MAX_TEXTURE_MAX_ANISOTROPY: u32 = ContextImpl::MAX_TEXTURE_MAX_ANISOTROPY;
     pub const LINEAR_MIPMAP_LINEAR: u32 = ContextImpl::LINEAR_MIPMAP_LINEAR;
     pub const TRIANGLES: u32 = ContextImpl::TRIANGLES;
     pub const CULL_FACE: u32 = ContextImpl::CULL_FACE;
+    pub const POLYGON_OFFSET_FILL: u32 = ContextImpl::POLYGON_OFFSET_FILL;
     pub const FRONT_AND_BACK: u32 = ContextImpl::FRONT_AND_BACK;
     pub const FILL: u32 = ContextImpl::FILL;
     pub const LINE: u32 = ContextImpl::LINE;
     pub const POINT: u32 = ContextImpl::POINT;
     pub const LINES: u32 = ContextImpl::LINES;
+    pub const ALIASED_LINE_WIDTH_RANGE: u32 = ContextImpl::ALIASED_LINE_WIDTH_RANGE;
+    pub const VIEWPORT: u32 = ContextImpl::VIEWPORT;
     pub const POINTS: u32 = ContextImpl::POINTS;
     pub const TRIANGLE_STRIP: u32 = ContextImpl::TRIANGLE_STRIP;
     pub const COLOR_BUFFER_BIT: u32 = ContextImpl::COLOR_BUFFER_BIT;
@@ -89,16 +138,35 @@ impl Context {
     pub const PROGRAM_POINT_SIZE: u32 = ContextImpl::PROGRAM_POINT_SIZE;
     pub const LEQUAL: u32 = ContextImpl::LEQUAL;
     pub const BACK: u32 = ContextImpl::BACK;
+    pub const FRONT: u32 = ContextImpl::FRONT;
     pub const PACK_ALIGNMENT: u32 = ContextImpl::PACK_ALIGNMENT;
     pub const BLEND: u32 = ContextImpl::BLEND;
     pub const SRC_ALPHA: u32 = ContextImpl::SRC_ALPHA;
     pub const ONE_MINUS_SRC_ALPHA: u32 = ContextImpl::ONE_MINUS_SRC_ALPHA;
     pub const ONE: u32 = ContextImpl::ONE;
+    pub const ZERO: u32 = ContextImpl::ZERO;
+    pub const ONE_MINUS_SRC_COLOR: u32 = ContextImpl::ONE_MINUS_SRC_COLOR;
+    pub const DST_COLOR: u32 = ContextImpl::DST_COLOR;
     pub const UNPACK_ALIGNMENT: u32 = ContextImpl::UNPACK_ALIGNMENT;
     pub const ALPHA: u32 = ContextImpl::ALPHA;
     pub const RED: u32 = ContextImpl::RED;
-
-    pub fn init(get_ctxt: impl Fn() -> glow::Context) {
+    pub const STENCIL_TEST: u32 = ContextImpl::STENCIL_TEST;
+    pub const STENCIL_BUFFER_BIT: u32 = ContextImpl::STENCIL_BUFFER_BIT;
+    pub const ALWAYS: u32 = ContextImpl::ALWAYS;
+    pub const NOTEQUAL: u32 = ContextImpl::NOTEQUAL;
+    pub const KEEP: u32 = ContextImpl::KEEP;
+    pub const REPLACE: u32 = ContextImpl::REPLACE;
+    pub const READ_FRAMEBUFFER: u32 = ContextImpl::READ_FRAMEBUFFER;
+    pub const DRAW_FRAMEBUFFER: u32 = ContextImpl::DRAW_FRAMEBUFFER;
+    pub const SAMPLE_BUFFERS: u32 = ContextImpl::SAMPLE_BUFFERS;
+    pub const RGBA8: u32 = ContextImpl::RGBA8;
+
+    /// Initializes the context with the OpenGL context that was just made current.
+    ///
+    /// The OpenGL functions are only loaded for the first context: the contexts created
+    /// afterward, e.g., by other windows, reuse them. The debug output is enabled for each new
+    /// context if a debug callback is set.
+    pub fn init(get_ctxt: impl FnOnce() -> glow::Context) {
         unsafe {
             CONTEXT_INIT.call_once(|| {
                 let ctxt = get_ctxt();
@@ -107,6 +175,51 @@ impl Context {
                 });
             });
         }
+
+        // The debug output is a state of each OpenGL context.
+        if DEBUG_CALLBACK.with(|c| c.borrow().is_some()) {
+            Context::get().ctxt.enable_debug_output();
+        }
+    }
+
+    /// Sets the callback called with the OpenGL errors and debug messages of the current thread.
+    ///
+    /// Without a callback, an OpenGL error detected after a call to the context makes the program
+    /// panic. With a callback, the error is given to the callback with `DebugSeverity::High`
+    /// instead. If the driver supports the `KHR_debug` extension, its debug messages are given to
+    /// the callback as well. This is not supported on WebGL, where OpenGL errors are not checked.
+    ///
+    /// The debug messages are enabled for the current OpenGL context and the ones created
+    /// afterward, so this should be called before opening several windows.
+    pub fn set_debug_callback<F: FnMut(&str, DebugSeverity) + 'static>(callback: F) {
+        DEBUG_CALLBACK.with(|c| *c.borrow_mut() = Some(Box::new(callback)));
+
+        if let Some(ctxt) = unsafe { CONTEXT_SINGLETON.as_ref() } {
+            ctxt.ctxt.enable_debug_output();
+        }
+    }
+
+    /// Removes the callback set with `set_debug_callback`.
+    ///
+    /// OpenGL errors make the program panic again, and debug messages are ignored.
+    pub fn remove_debug_callback() {
+        DEBUG_CALLBACK.with(|c| *c.borrow_mut() = None);
+    }
+
+    /// Checks that no OpenGL error occurred, reporting `file` and `line` otherwise.
+    ///
+    /// This is used by the `verify!` macro.
+    #[doc(hidden)]
+    pub fn check_error(&self, file: &str, line: u32) {
+        let error = self.get_error();
+
+        if error != 0 {
+            let message = format!("OpenGL error 0x{:X} at {}:{}.", error, file, line);
+
+            if !report_debug_message(&message, DebugSeverity::High) {
+                panic!("{}", message);
+            }
+        }
     }
 
     pub fn get() -> Context {
@@ -301,6 +414,14 @@ impl Context {
             .map(UniformLocation)
     }
 
+    pub fn get_active_uniform_names(&self, program: &Program) -> Vec<String> {
+        self.ctxt.get_active_uniform_names(&program.0)
+    }
+
+    pub fn get_active_attribute_names(&self, program: &Program) -> Vec<String> {
+        self.ctxt.get_active_attribute_names(&program.0)
+    }
+
     pub fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
         self.ctxt.viewport(x, y, width, height)
     }
@@ -326,6 +447,25 @@ impl Context {
         self.ctxt.delete_framebuffer(framebuffer.map(|e| &e.0))
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn blit_framebuffer(
+        &self,
+        src_x0: i32,
+        src_y0: i32,
+        src_x1: i32,
+        src_y1: i32,
+        dst_x0: i32,
+        dst_y0: i32,
+        dst_x1: i32,
+        dst_y1: i32,
+        mask: u32,
+        filter: GLenum,
+    ) {
+        self.ctxt.blit_framebuffer(
+            src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1, mask, filter,
+        )
+    }
+
     pub fn framebuffer_texture2d(
         &self,
         target: GLenum,
@@ -395,6 +535,31 @@ impl Context {
         )
     }
 
+    pub fn tex_image2d_typed(
+        &self,
+        target: GLenum,
+        level: i32,
+        internalformat: i32,
+        width: i32,
+        height: i32,
+        border: i32,
+        format: GLenum,
+        ty: GLenum,
+        pixels: Option<&[u8]>,
+    ) {
+        self.ctxt.tex_image2d_typed(
+            target,
+            level,
+            internalformat,
+            width,
+            height,
+            border,
+            format,
+            ty,
+            pixels,
+        )
+    }
+
     pub fn tex_image2di(
         &self,
         target: GLenum,
@@ -438,6 +603,22 @@ impl Context {
         self.ctxt.tex_parameteri(target, pname, param)
     }
 
+    pub fn tex_parameterf(&self, target: GLenum, pname: GLenum, param: f32) {
+        self.ctxt.tex_parameterf(target, pname, param)
+    }
+
+    pub fn supports_anisotropic_filtering(&self) -> bool {
+        self.ctxt.supports_anisotropic_filtering()
+    }
+
+    pub fn supports_half_float_render_targets(&self) -> bool {
+        self.ctxt.supports_half_float_render_targets()
+    }
+
+    pub fn supports_depth_stencil_textures(&self) -> bool {
+        self.ctxt.supports_depth_stencil_textures()
+    }
+
     pub fn is_texture(&self, texture: Option<&Texture>) -> bool {
         self.ctxt.is_texture(texture.map(|e| &e.0))
     }
@@ -478,10 +659,22 @@ impl Context {
         self.ctxt.line_width(size)
     }
 
+    pub fn get_parameter_i32_slice(&self, parameter: GLenum, out: &mut [i32]) {
+        self.ctxt.get_parameter_i32_slice(parameter, out)
+    }
+
+    pub fn get_parameter_f32_slice(&self, parameter: GLenum, out: &mut [f32]) {
+        self.ctxt.get_parameter_f32_slice(parameter, out)
+    }
+
     pub fn clear(&self, mask: u32) {
         self.ctxt.clear(mask)
     }
 
+    pub fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool) {
+        self.ctxt.color_mask(red, green, blue, alpha)
+    }
+
     pub fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
         self.ctxt.clear_color(r, g, b, a)
     }
@@ -498,10 +691,18 @@ impl Context {
         self.ctxt.depth_func(mode)
     }
 
+    pub fn depth_mask(&self, flag: bool) {
+        self.ctxt.depth_mask(flag)
+    }
+
     pub fn cull_face(&self, mode: GLenum) {
         self.ctxt.cull_face(mode)
     }
 
+    pub fn polygon_offset(&self, factor: f32, units: f32) {
+        self.ctxt.polygon_offset(factor, units)
+    }
+
     pub fn read_pixels(
         &self,
         x: i32,
@@ -528,6 +729,18 @@ impl Context {
         self.ctxt
             .blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha)
     }
+
+    pub fn stencil_func(&self, func: GLenum, ref_: i32, mask: u32) {
+        self.ctxt.stencil_func(func, ref_, mask)
+    }
+
+    pub fn stencil_op(&self, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        self.ctxt.stencil_op(sfail, dpfail, dppass)
+    }
+
+    pub fn stencil_mask(&self, mask: u32) {
+        self.ctxt.stencil_mask(mask)
+    }
 }
 
 pub(crate) trait AbstractContextConst {
@@ -548,8 +761,16 @@ pub(crate) trait AbstractContextConst {
     const DEPTH_ATTACHMENT: u32;
     const COLOR_ATTACHMENT0: u32;
     const TEXTURE_2D: u32;
+    const TEXTURE_CUBE_MAP: u32;
+    const TEXTURE_CUBE_MAP_POSITIVE_X: u32;
     const DEPTH_COMPONENT: u32;
     const DEPTH_COMPONENT16: u32;
+    const RGBA16F: u32;
+    const HALF_FLOAT: u32;
+    const DEPTH_STENCIL: u32;
+    const DEPTH24_STENCIL8: u32;
+    const DEPTH_STENCIL_ATTACHMENT: u32;
+    const UNSIGNED_INT_24_8: u32;
     const UNSIGNED_BYTE: u32;
     const TEXTURE_WRAP_S: u32;
     const TEXTURE_WRAP_T: u32;
@@ -562,16 +783,22 @@ pub(crate) trait AbstractContextConst {
     const RGBA: u32;
     const TEXTURE0: u32;
     const TEXTURE1: u32;
+    const TEXTURE2: u32;
     const REPEAT: u32;
     const MIRRORED_REPEAT: u32;
+    const TEXTURE_MAX_ANISOTROPY: u32;
+    const MAX_TEXTURE_MAX_ANISOTROPY: u32;
     const LINEAR_MIPMAP_LINEAR: u32;
     const TRIANGLES: u32;
     const CULL_FACE: u32;
+    const POLYGON_OFFSET_FILL: u32;
     const FRONT_AND_BACK: u32;
     const FILL: u32;
     const LINE: u32;
     const POINT: u32;
     const LINES: u32;
+    const ALIASED_LINE_WIDTH_RANGE: u32;
+    const VIEWPORT: u32;
     const POINTS: u32;
     const TRIANGLE_STRIP: u32;
     const COLOR_BUFFER_BIT: u32;
@@ -582,14 +809,28 @@ pub(crate) trait AbstractContextConst {
     const PROGRAM_POINT_SIZE: u32;
     const LEQUAL: u32;
     const BACK: u32;
+    const FRONT: u32;
     const PACK_ALIGNMENT: u32;
     const BLEND: u32;
     const SRC_ALPHA: u32;
     const ONE_MINUS_SRC_ALPHA: u32;
     const ONE: u32;
+    const ZERO: u32;
+    const ONE_MINUS_SRC_COLOR: u32;
+    const DST_COLOR: u32;
     const UNPACK_ALIGNMENT: u32;
     const ALPHA: u32;
     const RED: u32;
+    const STENCIL_TEST: u32;
+    const STENCIL_BUFFER_BIT: u32;
+    const ALWAYS: u32;
+    const NOTEQUAL: u32;
+    const KEEP: u32;
+    const REPLACE: u32;
+    const READ_FRAMEBUFFER: u32;
+    const DRAW_FRAMEBUFFER: u32;
+    const SAMPLE_BUFFERS: u32;
+    const RGBA8: u32;
 }
 
 pub(crate) trait AbstractContext {
@@ -603,6 +844,7 @@ pub(crate) trait AbstractContext {
     type VertexArray;
 
     fn get_error(&self) -> GLenum;
+    fn enable_debug_output(&self);
     fn uniform_matrix2fv(
         &self,
         location: Option<&Self::UniformLocation>,
@@ -671,6 +913,8 @@ pub(crate) trait AbstractContext {
         program: &Self::Program,
         name: &str,
     ) -> Option<Self::UniformLocation>;
+    fn get_active_uniform_names(&self, program: &Self::Program) -> Vec<String>;
+    fn get_active_attribute_names(&self, program: &Self::Program) -> Vec<String>;
 
     fn viewport(&self, x: i32, y: i32, width: i32, height: i32);
     fn scissor(&self, x: i32, y: i32, width: i32, height: i32);
@@ -678,6 +922,20 @@ pub(crate) trait AbstractContext {
     fn is_framebuffer(&self, framebuffer: Option<&Self::Framebuffer>) -> bool;
     fn bind_framebuffer(&self, target: GLenum, framebuffer: Option<&Self::Framebuffer>);
     fn delete_framebuffer(&self, framebuffer: Option<&Self::Framebuffer>);
+    #[allow(clippy::too_many_arguments)]
+    fn blit_framebuffer(
+        &self,
+        src_x0: i32,
+        src_y0: i32,
+        src_x1: i32,
+        src_y1: i32,
+        dst_x0: i32,
+        dst_y0: i32,
+        dst_x1: i32,
+        dst_y1: i32,
+        mask: u32,
+        filter: GLenum,
+    );
     fn framebuffer_texture2d(
         &self,
         target: GLenum,
@@ -710,6 +968,18 @@ pub(crate) trait AbstractContext {
         format: GLenum,
         pixels: Option<&[u8]>,
     );
+    fn tex_image2d_typed(
+        &self,
+        target: GLenum,
+        level: i32,
+        internalformat: i32,
+        width: i32,
+        height: i32,
+        border: i32,
+        format: GLenum,
+        ty: GLenum,
+        pixels: Option<&[u8]>,
+    );
     fn tex_image2di(
         &self,
         target: GLenum,
@@ -733,6 +1003,10 @@ pub(crate) trait AbstractContext {
         pixels: Option<&[u8]>,
     );
     fn tex_parameteri(&self, target: GLenum, pname: GLenum, param: i32);
+    fn tex_parameterf(&self, target: GLenum, pname: GLenum, param: f32);
+    fn supports_anisotropic_filtering(&self) -> bool;
+    fn supports_half_float_render_targets(&self) -> bool;
+    fn supports_depth_stencil_textures(&self) -> bool;
     fn is_texture(&self, texture: Option<&Self::Texture>) -> bool;
     fn create_texture(&self) -> Option<Self::Texture>;
     fn delete_texture(&self, texture: Option<&Self::Texture>);
@@ -747,15 +1021,22 @@ pub(crate) trait AbstractContext {
     fn point_size(&self, size: f32);
     fn line_width(&self, size: f32);
 
+    fn get_parameter_i32_slice(&self, parameter: GLenum, out: &mut [i32]);
+    fn get_parameter_f32_slice(&self, parameter: GLenum, out: &mut [f32]);
+
     fn clear(&self, mask: u32);
+    fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool);
     fn clear_color(&self, r: f32, g: f32, b: f32, a: f32);
 
     fn polygon_mode(&self, face: GLenum, mode: GLenum) -> bool;
 
     fn front_face(&self, mode: GLenum);
     fn depth_func(&self, mode: GLenum);
+    fn depth_mask(&self, flag: bool);
     fn cull_face(&self, mode: GLenum);
 
+    fn polygon_offset(&self, factor: f32, units: f32);
+
     fn read_pixels(
         &self,
         x: i32,
@@ -774,4 +1055,8 @@ pub(crate) trait AbstractContext {
         src_alpha: GLenum,
         dst_alpha: GLenum,
     );
+
+    fn stencil_func(&self, func: GLenum, ref_: i32, mask: u32);
+    fn stencil_op(&self, sfail: GLenum, dpfail: GLenum, dppass: GLenum);
+    fn stencil_mask(&self, mask: u32);
 }
@@ -1,7 +1,9 @@
 use std::mem;
 use std::sync::Arc;
 
-use crate::context::{AbstractContext, AbstractContextConst, GLenum, GLintptr};
+use crate::context::{
+    report_debug_message, AbstractContext, AbstractContextConst, DebugSeverity, GLenum, GLintptr,
+};
 
 use crate::resource::GLPrimitive;
 use glow::{Context, HasContext};
@@ -41,8 +43,16 @@ impl AbstractContextConst for GLContext {
     const DEPTH_ATTACHMENT: u32 = glow::DEPTH_ATTACHMENT;
     const COLOR_ATTACHMENT0: u32 = glow::COLOR_ATTACHMENT0;
     const TEXTURE_2D: u32 = glow::TEXTURE_2D;
+    const TEXTURE_CUBE_MAP: u32 = glow::TEXTURE_CUBE_MAP;
+    const TEXTURE_CUBE_MAP_POSITIVE_X: u32 = glow::TEXTURE_CUBE_MAP_POSITIVE_X;
     const DEPTH_COMPONENT: u32 = glow::DEPTH_COMPONENT;
     const DEPTH_COMPONENT16: u32 = glow::DEPTH_COMPONENT16;
+    const RGBA16F: u32 = glow::RGBA16F;
+    const HALF_FLOAT: u32 = glow::HALF_FLOAT;
+    const DEPTH_STENCIL: u32 = glow::DEPTH_STENCIL;
+    const DEPTH24_STENCIL8: u32 = glow::DEPTH24_STENCIL8;
+    const DEPTH_STENCIL_ATTACHMENT: u32 = glow::DEPTH_STENCIL_ATTACHMENT;
+    const UNSIGNED_INT_24_8: u32 = glow::UNSIGNED_INT_24_8;
     const UNSIGNED_BYTE: u32 = glow::UNSIGNED_BYTE;
     const TEXTURE_WRAP_S: u32 = glow::TEXTURE_WRAP_S;
     const TEXTURE_WRAP_T: u32 = glow::TEXTURE_WRAP_T;
@@ -55,13 +65,19 @@ impl AbstractContextConst for GLContext {
     const RGBA: u32 = glow::RGBA;
     const TEXTURE0: u32 = glow::TEXTURE0;
     const TEXTURE1: u32 = glow::TEXTURE1;
+    const TEXTURE2: u32 = glow::TEXTURE2;
     const REPEAT: u32 = glow::REPEAT;
     const MIRRORED_REPEAT: u32 = glow::MIRRORED_REPEAT;
+    const TEXTURE_MAX_ANISOTROPY: u32 = glow::TEXTURE_MAX_ANISOTROPY_EXT;
+    const MAX_TEXTURE_MAX_ANISOTROPY: u32 = glow::MAX_TEXTURE_MAX_ANISOTROPY_EXT;
     const LINEAR_MIPMAP_LINEAR: u32 = glow::LINEAR_MIPMAP_LINEAR;
     const TRIANGLES: u32 = glow::TRIANGLES;
     const CULL_FACE: u32 = glow::CULL_FACE;
+    const POLYGON_OFFSET_FILL: u32 = glow::POLYGON_OFFSET_FILL;
     const FRONT_AND_BACK: u32 = glow::FRONT_AND_BACK;
     const LINES: u32 = glow::LINES;
+    const ALIASED_LINE_WIDTH_RANGE: u32 = glow::ALIASED_LINE_WIDTH_RANGE;
+    const VIEWPORT: u32 = glow::VIEWPORT;
     const POINTS: u32 = glow::POINTS;
     const TRIANGLE_STRIP: u32 = glow::TRIANGLE_STRIP;
     const COLOR_BUFFER_BIT: u32 = glow::COLOR_BUFFER_BIT;
@@ -71,6 +87,7 @@ impl AbstractContextConst for GLContext {
     const SCISSOR_TEST: u32 = glow::SCISSOR_TEST;
     const LEQUAL: u32 = glow::LEQUAL;
     const BACK: u32 = glow::BACK;
+    const FRONT: u32 = glow::FRONT;
     const PACK_ALIGNMENT: u32 = glow::PACK_ALIGNMENT;
     const PROGRAM_POINT_SIZE: u32 = glow::PROGRAM_POINT_SIZE;
     const LINE: u32 = glow::LINE;
@@ -80,12 +97,25 @@ impl AbstractContextConst for GLContext {
     const SRC_ALPHA: u32 = glow::SRC_ALPHA;
     const ONE_MINUS_SRC_ALPHA: u32 = glow::ONE_MINUS_SRC_ALPHA;
     const ONE: u32 = glow::ONE;
+    const ZERO: u32 = glow::ZERO;
+    const ONE_MINUS_SRC_COLOR: u32 = glow::ONE_MINUS_SRC_COLOR;
+    const DST_COLOR: u32 = glow::DST_COLOR;
     const UNPACK_ALIGNMENT: u32 = glow::UNPACK_ALIGNMENT;
     const ALPHA: u32 = glow::ALPHA;
     #[cfg(not(target_arch = "wasm32"))]
     const RED: u32 = glow::RED;
     #[cfg(target_arch = "wasm32")]
     const RED: u32 = glow::LUMINANCE; // WebGL 1
+    const STENCIL_TEST: u32 = glow::STENCIL_TEST;
+    const STENCIL_BUFFER_BIT: u32 = glow::STENCIL_BUFFER_BIT;
+    const ALWAYS: u32 = glow::ALWAYS;
+    const NOTEQUAL: u32 = glow::NOTEQUAL;
+    const KEEP: u32 = glow::KEEP;
+    const REPLACE: u32 = glow::REPLACE;
+    const READ_FRAMEBUFFER: u32 = glow::READ_FRAMEBUFFER;
+    const DRAW_FRAMEBUFFER: u32 = glow::DRAW_FRAMEBUFFER;
+    const SAMPLE_BUFFERS: u32 = glow::SAMPLE_BUFFERS;
+    const RGBA8: u32 = glow::RGBA8;
 }
 
 impl AbstractContext for GLContext {
@@ -102,6 +132,29 @@ impl AbstractContext for GLContext {
         unsafe { self.context.get_error() }
     }
 
+    fn enable_debug_output(&self) {
+        if !self.context.supports_debug() {
+            return;
+        }
+
+        unsafe {
+            self.context.enable(glow::DEBUG_OUTPUT);
+            // The callback is stored in a thread-local, so it must be called on this thread.
+            self.context.enable(glow::DEBUG_OUTPUT_SYNCHRONOUS);
+            // NOTE: glow does not keep this closure alive, so it must not capture anything.
+            self.context
+                .debug_message_callback(|_, _, _, severity, message| {
+                    let severity = match severity {
+                        glow::DEBUG_SEVERITY_HIGH => DebugSeverity::High,
+                        glow::DEBUG_SEVERITY_MEDIUM => DebugSeverity::Medium,
+                        glow::DEBUG_SEVERITY_LOW => DebugSeverity::Low,
+                        _ => DebugSeverity::Notification,
+                    };
+                    let _ = report_debug_message(message, severity);
+                });
+        }
+    }
+
     fn uniform_matrix2fv(
         &self,
         location: Option<&Self::UniformLocation>,
@@ -349,6 +402,24 @@ impl AbstractContext for GLContext {
         unsafe { self.context.get_uniform_location(*program, name) }
     }
 
+    fn get_active_uniform_names(&self, program: &Self::Program) -> Vec<String> {
+        unsafe {
+            (0..self.context.get_active_uniforms(*program))
+                .filter_map(|i| self.context.get_active_uniform(*program, i))
+                .map(|u| u.name)
+                .collect()
+        }
+    }
+
+    fn get_active_attribute_names(&self, program: &Self::Program) -> Vec<String> {
+        unsafe {
+            (0..self.context.get_active_attributes(*program))
+                .filter_map(|i| self.context.get_active_attribute(*program, i))
+                .map(|a| a.name)
+                .collect()
+        }
+    }
+
     fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
         unsafe { self.context.viewport(x, y, width, height) }
     }
@@ -375,6 +446,26 @@ impl AbstractContext for GLContext {
         }
     }
 
+    fn blit_framebuffer(
+        &self,
+        src_x0: i32,
+        src_y0: i32,
+        src_x1: i32,
+        src_y1: i32,
+        dst_x0: i32,
+        dst_y0: i32,
+        dst_x1: i32,
+        dst_y1: i32,
+        mask: u32,
+        filter: GLenum,
+    ) {
+        unsafe {
+            self.context.blit_framebuffer(
+                src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1, mask, filter,
+            )
+        }
+    }
+
     fn framebuffer_texture2d(
         &self,
         target: GLenum,
@@ -467,6 +558,33 @@ impl AbstractContext for GLContext {
         }
     }
 
+    fn tex_image2d_typed(
+        &self,
+        target: GLenum,
+        level: i32,
+        internalformat: i32,
+        width: i32,
+        height: i32,
+        border: i32,
+        format: GLenum,
+        ty: GLenum,
+        pixels: Option<&[u8]>,
+    ) {
+        unsafe {
+            self.context.tex_image_2d(
+                target,
+                level,
+                internalformat,
+                width,
+                height,
+                border,
+                format,
+                ty,
+                pixels,
+            )
+        }
+    }
+
     fn tex_image2di(
         &self,
         target: GLenum,
@@ -529,6 +647,53 @@ impl AbstractContext for GLContext {
         unsafe { self.context.tex_parameter_i32(target, pname, param) }
     }
 
+    fn tex_parameterf(&self, target: GLenum, pname: GLenum, param: f32) {
+        unsafe { self.context.tex_parameter_f32(target, pname, param) }
+    }
+
+    fn supports_anisotropic_filtering(&self) -> bool {
+        // The extension names are not prefixed by `GL_` on WebGL.
+        let extensions = self.context.supported_extensions();
+        [
+            "GL_EXT_texture_filter_anisotropic",
+            "GL_ARB_texture_filter_anisotropic",
+            "EXT_texture_filter_anisotropic",
+        ]
+        .iter()
+        .any(|ext| extensions.contains(*ext))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn supports_depth_stencil_textures(&self) -> bool {
+        true
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn supports_depth_stencil_textures(&self) -> bool {
+        // WebGL 1 needs an extension to render depth into a texture.
+        self.context
+            .supported_extensions()
+            .contains("WEBGL_depth_texture")
+    }
+
+    fn supports_half_float_render_targets(&self) -> bool {
+        let version = self.context.version();
+
+        if !version.is_embedded {
+            return version.major >= 3;
+        }
+
+        // OpenGL ES 3 can only render to floating-point textures with an extension.
+        let extensions = self.context.supported_extensions();
+        version.major >= 3
+            && [
+                "GL_EXT_color_buffer_float",
+                "GL_EXT_color_buffer_half_float",
+            ]
+            .iter()
+            .any(|ext| extensions.contains(*ext))
+    }
+
     fn is_texture(&self, texture: Option<&Self::Texture>) -> bool {
         if let Some(t) = texture {
             unsafe { self.context.is_texture(t.clone()) }
@@ -578,10 +743,22 @@ impl AbstractContext for GLContext {
         unsafe { self.context.line_width(width) }
     }
 
+    fn get_parameter_i32_slice(&self, parameter: GLenum, out: &mut [i32]) {
+        unsafe { self.context.get_parameter_i32_slice(parameter, out) }
+    }
+
+    fn get_parameter_f32_slice(&self, parameter: GLenum, out: &mut [f32]) {
+        unsafe { self.context.get_parameter_f32_slice(parameter, out) }
+    }
+
     fn clear(&self, mask: u32) {
         unsafe { self.context.clear(mask) }
     }
 
+    fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool) {
+        unsafe { self.context.color_mask(red, green, blue, alpha) }
+    }
+
     fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
         unsafe { self.context.clear_color(r, g, b, a) }
     }
@@ -602,10 +779,18 @@ impl AbstractContext for GLContext {
         unsafe { self.context.depth_func(mode) }
     }
 
+    fn depth_mask(&self, flag: bool) {
+        unsafe { self.context.depth_mask(flag) }
+    }
+
     fn cull_face(&self, mode: GLenum) {
         unsafe { self.context.cull_face(mode) }
     }
 
+    fn polygon_offset(&self, factor: f32, units: f32) {
+        unsafe { self.context.polygon_offset(factor, units) }
+    }
+
     fn read_pixels(
         &self,
         x: i32,
@@ -647,4 +832,16 @@ impl AbstractContext for GLContext {
                 .blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha)
         }
     }
+
+    fn stencil_func(&self, func: GLenum, ref_: i32, mask: u32) {
+        unsafe { self.context.stencil_func(func, ref_, mask) }
+    }
+
+    fn stencil_op(&self, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        unsafe { self.context.stencil_op(sfail, dpfail, dppass) }
+    }
+
+    fn stencil_mask(&self, mask: u32) {
+        unsafe { self.context.stencil_mask(mask) }
+    }
 }
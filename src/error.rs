@@ -4,7 +4,7 @@ macro_rules! verify(
         {
             let res = $e;
             #[cfg(not(target_arch = "wasm32"))]
-            { assert_eq!($crate::context::Context::get().get_error(), 0); }
+            { $crate::context::Context::get().check_error(file!(), line!()); }
             res
         }
     }
@@ -19,7 +19,7 @@ impl<'a> Drop for Event<'a> {
     #[inline]
     fn drop(&mut self) {
         if !self.inhibited {
-            self.inhibitor.borrow_mut().push(self.value)
+            self.inhibitor.borrow_mut().push(self.value.clone())
         }
     }
 }
@@ -33,6 +33,21 @@ impl<'a> Event<'a> {
             inhibitor,
         }
     }
+
+    /// Marks this event as consumed, preventing the window and the cameras from handling it.
+    ///
+    /// This is equivalent to setting `inhibited` to `true`, e.g., to implement a custom
+    /// mouse dragging without the default camera rotating as well.
+    #[inline]
+    pub fn inhibit(&mut self) {
+        self.inhibited = true
+    }
+
+    /// Whether this event was marked as consumed with `inhibit`.
+    #[inline]
+    pub fn is_inhibited(&self) -> bool {
+        self.inhibited
+    }
 }
 
 /// An iterator through events.
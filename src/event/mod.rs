@@ -1,7 +1,10 @@
 //! Window event handling.
 
 pub use self::event_manager::{Event, EventManager, Events};
-pub use self::window_event::{Action, Key, Modifiers, MouseButton, TouchAction, WindowEvent};
+pub use self::window_event::{
+    Action, GamepadAxis, GamepadButton, ImeEvent, Key, Modifiers, MouseButton, TouchAction,
+    UnknownInputName, WindowEvent,
+};
 
 mod event_manager;
 mod window_event;
@@ -1,6 +1,14 @@
 #![allow(missing_docs)]
 
-#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An event received by a window.
+///
+/// This is not `Copy` because `WindowEvent::Ime` carries strings: match on a reference, or clone
+/// the events that must outlive the event iterator.
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
 pub enum WindowEvent {
     Pos(i32, i32),
     Size(u32, u32),
@@ -10,27 +18,65 @@ pub enum WindowEvent {
     Iconify(bool),
     FramebufferSize(u32, u32),
     MouseButton(MouseButton, Action, Modifiers),
+    /// A mouse button press, with the number of consecutive clicks (2 for a double-click, etc.)
+    /// and the cursor position.
+    MouseClick(MouseButton, u32, f64, f64, Modifiers),
     CursorPos(f64, f64, Modifiers),
+    /// A raw relative mouse displacement.
+    ///
+    /// Only reported while the relative mouse mode is enabled with
+    /// `Window::set_relative_mouse_mode`.
+    MouseMotion(f64, f64),
     CursorEnter(bool),
     Scroll(f64, f64, Modifiers),
     Key(Key, Action, Modifiers),
     Char(char),
     CharModifiers(char, Modifiers),
+    Ime(ImeEvent),
     Touch(u64, f64, f64, TouchAction, Modifiers),
+    /// A button of the gamepad with the given id was pressed or released.
+    ///
+    /// Only reported when the `gamepad` feature is enabled.
+    GamepadButton(usize, GamepadButton, Action),
+    /// An axis of the gamepad with the given id changed. The value is in `[-1.0, 1.0]`.
+    ///
+    /// Only reported when the `gamepad` feature is enabled.
+    GamepadAxis(usize, GamepadAxis, f32),
+}
+
+/// An input method editor (IME) event, used for composed text input.
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+pub enum ImeEvent {
+    /// The IME was enabled and composition started.
+    Enabled,
+    /// The text being composed changed.
+    ///
+    /// The second member is the byte range of the cursor inside of the composed text, if any.
+    /// An empty string means the composition has been cleared.
+    Preedit(String, Option<(usize, usize)>),
+    /// The composition ended and the given text must be inserted.
+    Commit(String),
+    /// The IME was disabled.
+    Disabled,
 }
 
 use WindowEvent::*;
 impl WindowEvent {
     /// Tests if this event is related to the keyboard.
     pub fn is_keyboard_event(&self) -> bool {
-        matches!(self, Key(..) | Char(..) | CharModifiers(..))
+        matches!(self, Key(..) | Char(..) | CharModifiers(..) | Ime(..))
     }
 
     /// Tests if this event is related to the mouse.
     pub fn is_mouse_event(&self) -> bool {
         matches!(
             self,
-            MouseButton(..) | CursorPos(..) | CursorEnter(..) | Scroll(..)
+            MouseButton(..)
+                | MouseClick(..)
+                | CursorPos(..)
+                | MouseMotion(..)
+                | CursorEnter(..)
+                | Scroll(..)
         )
     }
 
@@ -38,190 +84,300 @@ impl WindowEvent {
     pub fn is_touch_event(&self) -> bool {
         matches!(self, Touch(..))
     }
+
+    /// Tests if this event is related to a gamepad.
+    pub fn is_gamepad_event(&self) -> bool {
+        matches!(self, GamepadButton(..) | GamepadAxis(..))
+    }
+}
+
+/// Error returned when parsing a `Key` or a `MouseButton` from an unknown name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownInputName(pub String);
+
+impl fmt::Display for UnknownInputName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown key or mouse button name: {}", self.0)
+    }
+}
+
+impl Error for UnknownInputName {}
+
+// Defines an enum with conversions between its variants and their names, e.g., for key bindings
+// loaded from configuration files. Names are parsed case-insensitively.
+macro_rules! named_enum {
+    ($(#[$attr: meta])* pub enum $name: ident { $($variant: ident),* $(,)* }) => {
+        $(#[$attr])*
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl $name {
+            /// All the variants of this enum.
+            pub const ALL: &'static [$name] = &[$($name::$variant),*];
+
+            /// The name of this variant, e.g., for display in a key bindings menu.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant)),*
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = UnknownInputName;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $name::ALL
+                    .iter()
+                    .find(|v| v.name().eq_ignore_ascii_case(s))
+                    .cloned()
+                    .ok_or_else(|| UnknownInputName(s.to_string()))
+            }
+        }
+    };
 }
 
 // NOTE: list of keys inspired from glutin.
+named_enum! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+    pub enum Key {
+        Key1,
+        Key2,
+        Key3,
+        Key4,
+        Key5,
+        Key6,
+        Key7,
+        Key8,
+        Key9,
+        Key0,
+        A,
+        B,
+        C,
+        D,
+        E,
+        F,
+        G,
+        H,
+        I,
+        J,
+        K,
+        L,
+        M,
+        N,
+        O,
+        P,
+        Q,
+        R,
+        S,
+        T,
+        U,
+        V,
+        W,
+        X,
+        Y,
+        Z,
+        Escape,
+        F1,
+        F2,
+        F3,
+        F4,
+        F5,
+        F6,
+        F7,
+        F8,
+        F9,
+        F10,
+        F11,
+        F12,
+        F13,
+        F14,
+        F15,
+        F16,
+        F17,
+        F18,
+        F19,
+        F20,
+        F21,
+        F22,
+        F23,
+        F24,
+        Snapshot,
+        Scroll,
+        Pause,
+        Insert,
+        Home,
+        Delete,
+        End,
+        PageDown,
+        PageUp,
+        Left,
+        Up,
+        Right,
+        Down,
+        Back,
+        Return,
+        Space,
+        Compose,
+        Caret,
+        Numlock,
+        Numpad0,
+        Numpad1,
+        Numpad2,
+        Numpad3,
+        Numpad4,
+        Numpad5,
+        Numpad6,
+        Numpad7,
+        Numpad8,
+        Numpad9,
+        AbntC1,
+        AbntC2,
+        Add,
+        Apostrophe,
+        Apps,
+        At,
+        Ax,
+        Backslash,
+        Calculator,
+        Capital,
+        Colon,
+        Comma,
+        Convert,
+        Decimal,
+        Divide,
+        Equals,
+        Grave,
+        Kana,
+        Kanji,
+        LAlt,
+        LBracket,
+        LControl,
+        LShift,
+        LWin,
+        Mail,
+        MediaSelect,
+        MediaStop,
+        Minus,
+        Multiply,
+        Mute,
+        MyComputer,
+        NavigateForward,
+        NavigateBackward,
+        NextTrack,
+        NoConvert,
+        NumpadComma,
+        NumpadEnter,
+        NumpadEquals,
+        OEM102,
+        Period,
+        PlayPause,
+        Power,
+        PrevTrack,
+        RAlt,
+        RBracket,
+        RControl,
+        RShift,
+        RWin,
+        Semicolon,
+        Slash,
+        Sleep,
+        Stop,
+        Subtract,
+        Sysrq,
+        Tab,
+        Underline,
+        Unlabeled,
+        VolumeDown,
+        VolumeUp,
+        Wake,
+        WebBack,
+        WebFavorites,
+        WebForward,
+        WebHome,
+        WebRefresh,
+        WebSearch,
+        WebStop,
+        Yen,
+        Copy,
+        Paste,
+        Cut,
+        Unknown,
+    }
+}
+
+named_enum! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+    pub enum MouseButton {
+        Button1,
+        Button2,
+        Button3,
+        Button4,
+        Button5,
+        Button6,
+        Button7,
+        Button8,
+    }
+}
+
+/// A gamepad button, following the layout of an Xbox controller.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
-pub enum Key {
-    Key1,
-    Key2,
-    Key3,
-    Key4,
-    Key5,
-    Key6,
-    Key7,
-    Key8,
-    Key9,
-    Key0,
-    A,
-    B,
+pub enum GamepadButton {
+    /// The bottom action button (A on Xbox controllers).
+    South,
+    /// The right action button (B on Xbox controllers).
+    East,
+    /// The top action button (Y on Xbox controllers).
+    North,
+    /// The left action button (X on Xbox controllers).
+    West,
     C,
-    D,
-    E,
-    F,
-    G,
-    H,
-    I,
-    J,
-    K,
-    L,
-    M,
-    N,
-    O,
-    P,
-    Q,
-    R,
-    S,
-    T,
-    U,
-    V,
-    W,
-    X,
-    Y,
     Z,
-    Escape,
-    F1,
-    F2,
-    F3,
-    F4,
-    F5,
-    F6,
-    F7,
-    F8,
-    F9,
-    F10,
-    F11,
-    F12,
-    F13,
-    F14,
-    F15,
-    F16,
-    F17,
-    F18,
-    F19,
-    F20,
-    F21,
-    F22,
-    F23,
-    F24,
-    Snapshot,
-    Scroll,
-    Pause,
-    Insert,
-    Home,
-    Delete,
-    End,
-    PageDown,
-    PageUp,
-    Left,
-    Up,
-    Right,
-    Down,
-    Back,
-    Return,
-    Space,
-    Compose,
-    Caret,
-    Numlock,
-    Numpad0,
-    Numpad1,
-    Numpad2,
-    Numpad3,
-    Numpad4,
-    Numpad5,
-    Numpad6,
-    Numpad7,
-    Numpad8,
-    Numpad9,
-    AbntC1,
-    AbntC2,
-    Add,
-    Apostrophe,
-    Apps,
-    At,
-    Ax,
-    Backslash,
-    Calculator,
-    Capital,
-    Colon,
-    Comma,
-    Convert,
-    Decimal,
-    Divide,
-    Equals,
-    Grave,
-    Kana,
-    Kanji,
-    LAlt,
-    LBracket,
-    LControl,
-    LShift,
-    LWin,
-    Mail,
-    MediaSelect,
-    MediaStop,
-    Minus,
-    Multiply,
-    Mute,
-    MyComputer,
-    NavigateForward,
-    NavigateBackward,
-    NextTrack,
-    NoConvert,
-    NumpadComma,
-    NumpadEnter,
-    NumpadEquals,
-    OEM102,
-    Period,
-    PlayPause,
-    Power,
-    PrevTrack,
-    RAlt,
-    RBracket,
-    RControl,
-    RShift,
-    RWin,
-    Semicolon,
-    Slash,
-    Sleep,
-    Stop,
-    Subtract,
-    Sysrq,
-    Tab,
-    Underline,
-    Unlabeled,
-    VolumeDown,
-    VolumeUp,
-    Wake,
-    WebBack,
-    WebFavorites,
-    WebForward,
-    WebHome,
-    WebRefresh,
-    WebSearch,
-    WebStop,
-    Yen,
-    Copy,
-    Paste,
-    Cut,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
     Unknown,
 }
+
+/// A gamepad axis.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
-pub enum MouseButton {
-    Button1,
-    Button2,
-    Button3,
-    Button4,
-    Button5,
-    Button6,
-    Button7,
-    Button8,
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    LeftZ,
+    RightStickX,
+    RightStickY,
+    RightZ,
+    DPadX,
+    DPadY,
+    Unknown,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 pub enum Action {
     Release,
     Press,
+    /// A key is held down and the system generated an auto-repeat event.
+    ///
+    /// This is never reported for mouse buttons.
+    Repeat,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
@@ -160,6 +160,7 @@ pub mod loader;
 pub mod planar_camera;
 pub mod planar_line_renderer;
 pub mod post_processing;
+pub mod procedural;
 pub mod renderer;
 pub mod resource;
 pub mod scene;
@@ -1,6 +1,6 @@
 //! Lights.
 
-use na::Point3;
+use na::{Point3, Vector3};
 
 /// The light configuration.
 #[derive(Clone)]
@@ -9,4 +9,12 @@ pub enum Light {
     Absolute(Point3<f32>),
     /// A light superimposed with the camera position.
     StickToCamera,
+    /// A light following the camera, offset from its position.
+    ///
+    /// The offset is expressed in view space, e.g., `Vector3::new(-1.0, 1.0, 0.0)` places the
+    /// light up and to the left of the eye.
+    StickToCameraWithOffset(Vector3<f32>),
+    /// A light infinitely far away, with parallel rays going toward the given direction in world
+    /// space, e.g., `-Vector3::y()` for a light shining straight down.
+    Directional(Vector3<f32>),
 }
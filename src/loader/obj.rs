@@ -74,6 +74,32 @@ pub fn parse(
     string: &str,
     mtl_base_dir: &Path,
     basename: &str,
+) -> Vec<(String, Mesh, Option<MtlMaterial>)> {
+    parse_with_mtllib(string, Some(mtl_base_dir), HashMap::new(), basename)
+}
+
+/// Parses a string representing an obj file, using already parsed materials.
+///
+/// The `mtllib` statements of the obj file are ignored: `usemtl` statements refer to the
+/// materials of `materials` by name.
+pub fn parse_with_materials(
+    string: &str,
+    materials: Vec<MtlMaterial>,
+    basename: &str,
+) -> Vec<(String, Mesh, Option<MtlMaterial>)> {
+    let mtllib = materials
+        .into_iter()
+        .map(|m| (m.name.to_string(), m))
+        .collect();
+
+    parse_with_mtllib(string, None, mtllib, basename)
+}
+
+fn parse_with_mtllib(
+    string: &str,
+    mtl_base_dir: Option<&Path>,
+    mut mtllib: HashMap<String, MtlMaterial>,
+    basename: &str,
 ) -> Vec<(String, Mesh, Option<MtlMaterial>)> {
     let mut coords: Vec<Coord> = Vec::new();
     let mut normals: Vec<Normal> = Vec::new();
@@ -83,7 +109,6 @@ pub fn parse(
     let mut curr_group: usize = 0;
     let mut ignore_normals = false;
     let mut ignore_uvs = false;
-    let mut mtllib = HashMap::new();
     let mut group2mtl = HashMap::new();
     let mut curr_mtl = None::<MtlMaterial>;
 
@@ -126,7 +151,11 @@ pub fn parse(
                                 .as_ref()
                                 .map(|mtl| group2mtl.insert(curr_group, mtl.clone()));
                         }
-                        "mtllib" => parse_mtllib(l, words, mtl_base_dir, &mut mtllib),
+                        "mtllib" => {
+                            if let Some(mtl_base_dir) = mtl_base_dir {
+                                parse_mtllib(l, words, mtl_base_dir, &mut mtllib)
+                            }
+                        }
                         "usemtl" => {
                             curr_group = parse_usemtl(
                                 l,
@@ -0,0 +1,327 @@
+//! Simplistic PLY loader, supporting the ASCII and binary formats.
+
+use na::{Point2, Point3, Vector3};
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result as IoResult};
+use std::path::Path;
+use std::str;
+
+/// The geometry read from a PLY file.
+///
+/// Polygonal faces are triangulated. Missing vertex attributes are set to `None`.
+pub struct PlyMesh {
+    /// The vertex positions.
+    pub coords: Vec<Point3<f32>>,
+    /// The vertex normals, from the `nx`, `ny`, `nz` properties.
+    pub normals: Option<Vec<Vector3<f32>>>,
+    /// The vertex texture coordinates, from the `s`, `t` or `u`, `v` properties.
+    pub uvs: Option<Vec<Point2<f32>>>,
+    /// The vertex colors, from the `red`, `green`, `blue` properties, in `[0.0, 1.0]`.
+    pub colors: Option<Vec<Point3<f32>>>,
+    /// The triangles, as indices into the vertex arrays. Empty for point clouds.
+    pub faces: Vec<Point3<u32>>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Copy, Clone)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+enum Property {
+    Scalar(String, Scalar),
+    List(String, Scalar, Scalar),
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+fn error(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Parses a PLY file.
+pub fn parse_file(path: &Path) -> IoResult<PlyMesh> {
+    let mut data = Vec::new();
+    let _ = File::open(path)?.read_to_end(&mut data)?;
+    parse(&data)
+}
+
+/// Parses the content of a PLY file.
+pub fn parse(data: &[u8]) -> IoResult<PlyMesh> {
+    let (format, elements, body) = parse_header(data)?;
+    let mut body = match format {
+        Format::Ascii => Body::Ascii(
+            str::from_utf8(body)
+                .map_err(|_| error("Invalid ASCII PLY body."))?
+                .split_whitespace(),
+        ),
+        _ => Body::Binary(body, format == Format::BinaryBigEndian),
+    };
+
+    let mut mesh = PlyMesh {
+        coords: Vec::new(),
+        normals: None,
+        uvs: None,
+        colors: None,
+        faces: Vec::new(),
+    };
+
+    for element in &elements {
+        match &element.name[..] {
+            "vertex" => read_vertices(element, &mut body, &mut mesh)?,
+            "face" => read_faces(element, &mut body, &mut mesh)?,
+            _ => {
+                // Skip unsupported elements.
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        let _ = read_property(property, &mut body)?;
+                    }
+                }
+            }
+        }
+    }
+
+    let nvertices = mesh.coords.len() as u32;
+
+    if mesh.faces.iter().any(|f| f.iter().any(|i| *i >= nvertices)) {
+        return Err(error("Face index out of bounds."));
+    }
+
+    Ok(mesh)
+}
+
+fn parse_header(data: &[u8]) -> IoResult<(Format, Vec<Element>, &[u8])> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut pos = 0;
+
+    loop {
+        let end = data[pos..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or_else(|| error("Unterminated PLY header."))?;
+        let line = str::from_utf8(&data[pos..pos + end])
+            .map_err(|_| error("Invalid PLY header."))?
+            .trim();
+        pos += end + 1;
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["ply"] if format.is_none() && elements.is_empty() => {}
+            ["format", "ascii", _] => format = Some(Format::Ascii),
+            ["format", "binary_little_endian", _] => format = Some(Format::BinaryLittleEndian),
+            ["format", "binary_big_endian", _] => format = Some(Format::BinaryBigEndian),
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| error("Invalid PLY element count."))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count_ty, item_ty, name] => elements
+                .last_mut()
+                .ok_or_else(|| error("PLY property declared outside of an element."))?
+                .properties
+                .push(Property::List(
+                    name.to_string(),
+                    parse_scalar(count_ty)?,
+                    parse_scalar(item_ty)?,
+                )),
+            ["property", ty, name] => elements
+                .last_mut()
+                .ok_or_else(|| error("PLY property declared outside of an element."))?
+                .properties
+                .push(Property::Scalar(name.to_string(), parse_scalar(ty)?)),
+            ["end_header"] => break,
+            _ => return Err(error(&format!("Unsupported PLY header line: {}", line))),
+        }
+    }
+
+    let format = format.ok_or_else(|| error("Missing PLY format."))?;
+    Ok((format, elements, &data[pos..]))
+}
+
+fn parse_scalar(name: &str) -> IoResult<Scalar> {
+    match name {
+        "char" | "int8" => Ok(Scalar::I8),
+        "uchar" | "uint8" => Ok(Scalar::U8),
+        "short" | "int16" => Ok(Scalar::I16),
+        "ushort" | "uint16" => Ok(Scalar::U16),
+        "int" | "int32" => Ok(Scalar::I32),
+        "uint" | "uint32" => Ok(Scalar::U32),
+        "float" | "float32" => Ok(Scalar::F32),
+        "double" | "float64" => Ok(Scalar::F64),
+        _ => Err(error(&format!("Unsupported PLY property type: {}", name))),
+    }
+}
+
+enum Body<'a> {
+    Ascii(str::SplitWhitespace<'a>),
+    // The remaining bytes, and whether they are big endian.
+    Binary(&'a [u8], bool),
+}
+
+impl<'a> Body<'a> {
+    fn read(&mut self, ty: Scalar) -> IoResult<f64> {
+        match self {
+            Body::Ascii(words) => words
+                .next()
+                .and_then(|w| w.parse().ok())
+                .ok_or_else(|| error("Invalid or missing PLY value.")),
+            Body::Binary(bytes, big_endian) => {
+                let size = match ty {
+                    Scalar::I8 | Scalar::U8 => 1,
+                    Scalar::I16 | Scalar::U16 => 2,
+                    Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+                    Scalar::F64 => 8,
+                };
+
+                let data: &'a [u8] = *bytes;
+
+                if data.len() < size {
+                    return Err(error("Unexpected end of PLY data."));
+                }
+
+                let mut buf = [0u8; 8];
+                buf[..size].copy_from_slice(&data[..size]);
+                *bytes = &data[size..];
+
+                if *big_endian {
+                    buf[..size].reverse();
+                }
+
+                let val = match ty {
+                    Scalar::I8 => buf[0] as i8 as f64,
+                    Scalar::U8 => buf[0] as f64,
+                    Scalar::I16 => i16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    Scalar::U16 => u16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    Scalar::I32 => i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    Scalar::U32 => u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    Scalar::F32 => f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    Scalar::F64 => f64::from_le_bytes(buf),
+                };
+
+                Ok(val)
+            }
+        }
+    }
+}
+
+fn read_property(property: &Property, body: &mut Body) -> IoResult<Vec<f64>> {
+    match property {
+        Property::Scalar(_, ty) => Ok(vec![body.read(*ty)?]),
+        Property::List(_, count_ty, item_ty) => {
+            let count = body.read(*count_ty)? as usize;
+            (0..count).map(|_| body.read(*item_ty)).collect()
+        }
+    }
+}
+
+fn read_vertices(element: &Element, body: &mut Body, mesh: &mut PlyMesh) -> IoResult<()> {
+    let index_of = |names: &[&str]| {
+        element.properties.iter().position(|p| match p {
+            Property::Scalar(name, _) => names.contains(&&name[..]),
+            Property::List(..) => false,
+        })
+    };
+    let indices =
+        |names: &[&[&str]]| -> Option<Vec<usize>> { names.iter().map(|n| index_of(*n)).collect() };
+
+    let pos_ids =
+        indices(&[&["x"], &["y"], &["z"]]).ok_or_else(|| error("Missing PLY vertex position."))?;
+    let normal_ids = indices(&[&["nx"], &["ny"], &["nz"]]);
+    let uv_ids = indices(&[&["s", "u", "texture_u"], &["t", "v", "texture_v"]]);
+    let color_ids = indices(&[&["red", "r"], &["green", "g"], &["blue", "b"]]);
+
+    // Integer colors are in [0, 255].
+    let color_scale = match color_ids.as_ref().map(|ids| &element.properties[ids[0]]) {
+        Some(Property::Scalar(_, Scalar::F32)) | Some(Property::Scalar(_, Scalar::F64)) => 1.0,
+        _ => 1.0 / 255.0,
+    };
+
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut colors = Vec::new();
+
+    for _ in 0..element.count {
+        let mut values = Vec::with_capacity(element.properties.len());
+
+        for property in &element.properties {
+            values.push(
+                read_property(property, body)?
+                    .first()
+                    .cloned()
+                    .unwrap_or(0.0) as f32,
+            );
+        }
+
+        mesh.coords.push(Point3::new(
+            values[pos_ids[0]],
+            values[pos_ids[1]],
+            values[pos_ids[2]],
+        ));
+
+        if let Some(ids) = &normal_ids {
+            normals.push(Vector3::new(values[ids[0]], values[ids[1]], values[ids[2]]));
+        }
+
+        if let Some(ids) = &uv_ids {
+            uvs.push(Point2::new(values[ids[0]], values[ids[1]]));
+        }
+
+        if let Some(ids) = &color_ids {
+            colors.push(Point3::new(values[ids[0]], values[ids[1]], values[ids[2]]) * color_scale);
+        }
+    }
+
+    mesh.normals = normal_ids.map(|_| normals);
+    mesh.uvs = uv_ids.map(|_| uvs);
+    mesh.colors = color_ids.map(|_| colors);
+
+    Ok(())
+}
+
+fn read_faces(element: &Element, body: &mut Body, mesh: &mut PlyMesh) -> IoResult<()> {
+    let index_id = element.properties.iter().position(|p| match p {
+        Property::List(name, ..) => name == "vertex_indices" || name == "vertex_index",
+        Property::Scalar(..) => false,
+    });
+
+    for _ in 0..element.count {
+        for (i, property) in element.properties.iter().enumerate() {
+            let values = read_property(property, body)?;
+
+            if Some(i) == index_id {
+                // Triangulate polygons as fans.
+                for k in 2..values.len() {
+                    mesh.faces.push(Point3::new(
+                        values[0] as u32,
+                        values[k - 1] as u32,
+                        values[k] as u32,
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -25,6 +25,10 @@ pub trait PlanarCamera {
         view: &mut ShaderUniform<Matrix3<f32>>,
     );
 
-    /// Computes the 2D world-space coordiates corresponding to the given screen-space coordiates.
+    /// Computes the 2D world-space coordinates corresponding to the given screen-space coordinates.
+    ///
+    /// `window_coord` is expressed in pixels with its origin at the top-left corner of the
+    /// window, e.g., the cursor position given by `WindowEvent::CursorPos`. `window_size` is the
+    /// size of the window in the same units.
     fn unproject(&self, window_coord: &Point2<f32>, window_size: &Vector2<f32>) -> Point2<f32>;
 }
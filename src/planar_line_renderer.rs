@@ -4,20 +4,43 @@ use crate::context::Context;
 use crate::planar_camera::PlanarCamera;
 use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform};
 use crate::verify;
-use na::{Matrix3, Point2, Point3};
+use na::{Matrix3, Point2, Point3, Vector2};
 
 /// Structure which manages the display of short-living lines.
+///
+/// Each line is expanded into a quad with round caps, so consecutive lines sharing an endpoint
+/// are joined smoothly. The edges of the lines are antialiased in the fragment shader, based on
+/// the distance to the line center.
 pub struct PlanarLineRenderer {
     shader: Effect,
-    pos: ShaderAttribute<Point2<f32>>,
+    start: ShaderAttribute<Point2<f32>>,
+    end: ShaderAttribute<Point2<f32>>,
+    corner: ShaderAttribute<Point2<f32>>,
     color: ShaderAttribute<Point3<f32>>,
     view: ShaderUniform<Matrix3<f32>>,
     proj: ShaderUniform<Matrix3<f32>>,
+    viewport: ShaderUniform<Vector2<f32>>,
+    half_width: ShaderUniform<f32>,
+    starts: GPUVec<Point2<f32>>,
+    ends: GPUVec<Point2<f32>>,
+    corners: GPUVec<Point2<f32>>,
     colors: GPUVec<Point3<f32>>,
-    lines: GPUVec<Point2<f32>>,
     line_width: f32,
 }
 
+/// The corners of the quad covering a line, as `(endpoint, side)` pairs.
+///
+/// The endpoint is `0.0` for the start of the line and `1.0` for its end. The side is `-1.0` or
+/// `1.0` depending on the side of the line the corner lies on.
+const CORNERS: [[f32; 2]; 6] = [
+    [0.0, -1.0],
+    [0.0, 1.0],
+    [1.0, -1.0],
+    [1.0, -1.0],
+    [0.0, 1.0],
+    [1.0, 1.0],
+];
+
 impl PlanarLineRenderer {
     /// Creates a new lines manager.
     pub fn new() -> PlanarLineRenderer {
@@ -26,10 +49,18 @@ impl PlanarLineRenderer {
         shader.use_program();
 
         PlanarLineRenderer {
-            lines: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            starts: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            ends: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            corners: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
             colors: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
-            pos: shader
-                .get_attrib::<Point2<f32>>("position")
+            start: shader
+                .get_attrib::<Point2<f32>>("start")
+                .expect("Failed to get shader attribute."),
+            end: shader
+                .get_attrib::<Point2<f32>>("end")
+                .expect("Failed to get shader attribute."),
+            corner: shader
+                .get_attrib::<Point2<f32>>("corner")
                 .expect("Failed to get shader attribute."),
             color: shader
                 .get_attrib::<Point3<f32>>("color")
@@ -40,6 +71,12 @@ impl PlanarLineRenderer {
             proj: shader
                 .get_uniform::<Matrix3<f32>>("proj")
                 .expect("Failed to get shader uniform."),
+            viewport: shader
+                .get_uniform::<Vector2<f32>>("viewport")
+                .expect("Failed to get shader uniform."),
+            half_width: shader
+                .get_uniform::<f32>("half_width")
+                .expect("Failed to get shader uniform."),
             shader,
             line_width: 1.0,
         }
@@ -47,46 +84,83 @@ impl PlanarLineRenderer {
 
     /// Indicates whether some lines have to be drawn.
     pub fn needs_rendering(&self) -> bool {
-        self.lines.len() != 0
+        self.starts.len() != 0
     }
 
     /// Adds a line to be drawn during the next frame. Lines are not persistent between frames.
     /// This method must be called for each line to draw, and at each update loop iteration.
     pub fn draw_line(&mut self, a: Point2<f32>, b: Point2<f32>, color: Point3<f32>) {
-        for lines in self.lines.data_mut().iter_mut() {
-            lines.push(a);
-            lines.push(b);
+        for starts in self.starts.data_mut().iter_mut() {
+            starts.extend_from_slice(&[a; 6]);
+        }
+        for ends in self.ends.data_mut().iter_mut() {
+            ends.extend_from_slice(&[b; 6]);
+        }
+        for corners in self.corners.data_mut().iter_mut() {
+            corners.extend(CORNERS.iter().map(|c| Point2::new(c[0], c[1])));
         }
         for colors in self.colors.data_mut().iter_mut() {
-            colors.push(color);
-            colors.push(color);
+            colors.extend_from_slice(&[color; 6]);
         }
     }
 
     /// Actually draws the lines.
     pub fn render(&mut self, camera: &mut dyn PlanarCamera) {
-        if self.lines.len() == 0 {
+        if self.starts.len() == 0 {
             return;
         }
 
-        self.shader.use_program();
-        self.pos.enable();
-        self.color.enable();
-
-        camera.upload(&mut self.proj, &mut self.view);
-
-        self.color.bind_sub_buffer(&mut self.colors, 0, 0);
-        self.pos.bind_sub_buffer(&mut self.lines, 0, 0);
-
         let ctxt = Context::get();
-        verify!(ctxt.line_width(self.line_width));
-        verify!(ctxt.draw_arrays(Context::LINES, 0, self.lines.len() as i32));
+        let mut viewport = [0; 4];
+        verify!(ctxt.get_parameter_i32_slice(Context::VIEWPORT, &mut viewport));
+
+        if viewport[2] > 0 && viewport[3] > 0 {
+            self.shader.use_program();
+            self.start.enable();
+            self.end.enable();
+            self.corner.enable();
+            self.color.enable();
+
+            camera.upload(&mut self.proj, &mut self.view);
+
+            self.viewport
+                .upload(&Vector2::new(viewport[2] as f32, viewport[3] as f32));
+            self.half_width.upload(&(self.line_width / 2.0));
+            self.start.bind_sub_buffer(&mut self.starts, 0, 0);
+            self.end.bind_sub_buffer(&mut self.ends, 0, 0);
+            self.corner.bind_sub_buffer(&mut self.corners, 0, 0);
+            self.color.bind_sub_buffer(&mut self.colors, 0, 0);
+
+            verify!(ctxt.enable(Context::BLEND));
+            verify!(ctxt.blend_func_separate(
+                Context::SRC_ALPHA,
+                Context::ONE_MINUS_SRC_ALPHA,
+                Context::ONE,
+                Context::ONE_MINUS_SRC_ALPHA,
+            ));
+            verify!(ctxt.disable(Context::CULL_FACE));
+            let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
+
+            verify!(ctxt.draw_arrays(Context::TRIANGLES, 0, self.starts.len() as i32));
+
+            verify!(ctxt.disable(Context::BLEND));
+
+            self.start.disable();
+            self.end.disable();
+            self.corner.disable();
+            self.color.disable();
+        }
 
-        self.pos.disable();
-        self.color.disable();
+        for starts in self.starts.data_mut().iter_mut() {
+            starts.clear()
+        }
 
-        for lines in self.lines.data_mut().iter_mut() {
-            lines.clear()
+        for ends in self.ends.data_mut().iter_mut() {
+            ends.clear()
+        }
+
+        for corners in self.corners.data_mut().iter_mut() {
+            corners.clear()
         }
 
         for colors in self.colors.data_mut().iter_mut() {
@@ -94,11 +168,9 @@ impl PlanarLineRenderer {
         }
     }
 
-    /// Sets the line width for the rendered lines.
+    /// Sets the line width for the rendered lines, in pixels.
     pub fn set_line_width(&mut self, line_width: f32) {
-        self.line_width = line_width.max(
-            f32::EPSILON, /* Gl will usually round this to 1 pixel */
-        );
+        self.line_width = line_width.max(f32::EPSILON);
     }
 }
 
@@ -108,17 +180,42 @@ static LINES_VERTEX_SRC: &str = A_VERY_LONG_STRING;
 static LINES_FRAGMENT_SRC: &str = ANOTHER_VERY_LONG_STRING;
 
 const A_VERY_LONG_STRING: &str = "#version 100
-    attribute vec2 position;
+    attribute vec2 start;
+    attribute vec2 end;
+    attribute vec2 corner;
     attribute vec3 color;
     varying   vec3 vColor;
+    varying   vec2 vLocal;
+    varying   float vLength;
     uniform   mat3 proj;
     uniform   mat3 view;
+    uniform   vec2 viewport;
+    uniform   float half_width;
 
     void main() {
-        vec3 projected_pos = proj * view * vec3(position, 1.0);
-        projected_pos.z = 0.0;
+        // Work in pixels so the width of the lines does not depend on the camera zoom.
+        vec2 a = (proj * view * vec3(start, 1.0)).xy * viewport * 0.5;
+        vec2 b = (proj * view * vec3(end, 1.0)).xy * viewport * 0.5;
+        float len = length(b - a);
+        vec2 dir = len > 0.0 ? (b - a) / len : vec2(1.0, 0.0);
+        vec2 normal = vec2(-dir.y, dir.x);
+
+        // Leave one pixel of margin for antialiasing.
+        float extent = half_width + 1.0;
+        vec2 pos;
+
+        if (corner.x < 0.5) {
+            pos = a - dir * extent;
+            vLocal = vec2(-extent, corner.y * extent);
+        } else {
+            pos = b + dir * extent;
+            vLocal = vec2(len + extent, corner.y * extent);
+        }
 
-        gl_Position = vec4(projected_pos, 1.0);
+        pos += normal * corner.y * extent;
+
+        gl_Position = vec4(pos * 2.0 / viewport, 0.0, 1.0);
+        vLength = len;
         vColor = color;
     }";
 
@@ -130,6 +227,19 @@ const ANOTHER_VERY_LONG_STRING: &str = "#version 100
 #endif
 
     varying vec3 vColor;
+    varying vec2 vLocal;
+    varying float vLength;
+    uniform float half_width;
+
     void main() {
-        gl_FragColor = vec4(vColor, 1.0);
+        // Distance, in pixels, between this fragment and the line segment.
+        float dx = max(max(-vLocal.x, vLocal.x - vLength), 0.0);
+        float dist = length(vec2(dx, vLocal.y));
+        float alpha = clamp(half_width + 0.5 - dist, 0.0, 1.0);
+
+        if (alpha <= 0.0) {
+            discard;
+        }
+
+        gl_FragColor = vec4(vColor, alpha);
     }";
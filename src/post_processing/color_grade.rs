@@ -0,0 +1,151 @@
+//! Post-processing effect adjusting the brightness, contrast, and saturation of the scene.
+
+use na::Vector2;
+
+use crate::context::Context;
+use crate::post_processing::PostProcessingEffect;
+use crate::resource::{
+    AllocationType, BufferType, Effect, GPUVec, RenderTarget, ShaderAttribute, ShaderUniform,
+};
+use crate::verify;
+
+/// Post processing effect which adjusts the brightness, contrast, and saturation of the scene.
+pub struct ColorGrade {
+    shader: Effect,
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+    fbo_texture: ShaderUniform<i32>,
+    brightness_uniform: ShaderUniform<f32>,
+    contrast_uniform: ShaderUniform<f32>,
+    saturation_uniform: ShaderUniform<f32>,
+    v_coord: ShaderAttribute<Vector2<f32>>,
+    fbo_vertices: GPUVec<Vector2<f32>>,
+}
+
+impl ColorGrade {
+    /// Creates a new `ColorGrade` post processing effect that leaves colors unchanged.
+    pub fn new() -> ColorGrade {
+        let fbo_vertices: Vec<Vector2<f32>> = vec![
+            Vector2::new(-1.0, -1.0),
+            Vector2::new(1.0, -1.0),
+            Vector2::new(-1.0, 1.0),
+            Vector2::new(1.0, 1.0),
+        ];
+
+        let mut fbo_vertices =
+            GPUVec::new(fbo_vertices, BufferType::Array, AllocationType::StaticDraw);
+        fbo_vertices.load_to_gpu();
+        fbo_vertices.unload_from_ram();
+
+        let mut shader = Effect::new_from_str(VERTEX_SHADER, FRAGMENT_SHADER);
+
+        shader.use_program();
+
+        ColorGrade {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            fbo_texture: shader.get_uniform("fbo_texture").unwrap(),
+            brightness_uniform: shader.get_uniform("brightness").unwrap(),
+            contrast_uniform: shader.get_uniform("contrast").unwrap(),
+            saturation_uniform: shader.get_uniform("saturation").unwrap(),
+            v_coord: shader.get_attrib("v_coord").unwrap(),
+            fbo_vertices,
+            shader,
+        }
+    }
+
+    /// The value added to each color component (default: 0.0).
+    pub fn brightness(&self) -> f32 {
+        self.brightness
+    }
+
+    /// Sets the value added to each color component.
+    pub fn set_brightness(&mut self, brightness: f32) {
+        self.brightness = brightness
+    }
+
+    /// The contrast factor applied around mid-gray (default: 1.0).
+    pub fn contrast(&self) -> f32 {
+        self.contrast
+    }
+
+    /// Sets the contrast factor applied around mid-gray.
+    pub fn set_contrast(&mut self, contrast: f32) {
+        self.contrast = contrast
+    }
+
+    /// The saturation factor, 0.0 giving gray scales (default: 1.0).
+    pub fn saturation(&self) -> f32 {
+        self.saturation
+    }
+
+    /// Sets the saturation factor, 0.0 giving gray scales.
+    pub fn set_saturation(&mut self, saturation: f32) {
+        self.saturation = saturation
+    }
+}
+
+impl PostProcessingEffect for ColorGrade {
+    fn update(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+
+    fn draw(&mut self, target: &RenderTarget) {
+        let ctxt = Context::get();
+        self.v_coord.enable();
+
+        /*
+         * Configure the post-process effect.
+         */
+        self.shader.use_program();
+        self.brightness_uniform.upload(&self.brightness);
+        self.contrast_uniform.upload(&self.contrast);
+        self.saturation_uniform.upload(&self.saturation);
+
+        /*
+         * Finalize draw
+         */
+        verify!(ctxt.clear_color(0.0, 0.0, 0.0, 1.0));
+        verify!(ctxt.clear(Context::COLOR_BUFFER_BIT | Context::DEPTH_BUFFER_BIT));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, target.texture_id()));
+
+        self.fbo_texture.upload(&0);
+        self.v_coord.bind(&mut self.fbo_vertices);
+
+        verify!(ctxt.draw_arrays(Context::TRIANGLE_STRIP, 0, 4));
+
+        self.v_coord.disable();
+    }
+}
+
+static VERTEX_SHADER: &str = "#version 100
+    attribute vec2    v_coord;
+    uniform sampler2D fbo_texture;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      gl_Position = vec4(v_coord, 0.0, 1.0);
+      f_texcoord  = (v_coord + 1.0) / 2.0;
+    }";
+
+static FRAGMENT_SHADER: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    uniform sampler2D fbo_texture;
+    uniform float     brightness;
+    uniform float     contrast;
+    uniform float     saturation;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      vec4 color    = texture2D(fbo_texture, f_texcoord);
+      vec3 rgb      = color.rgb + brightness;
+      rgb           = (rgb - 0.5) * contrast + 0.5;
+      float gray    = 0.2126 * rgb.r + 0.7152 * rgb.g + 0.0722 * rgb.b;
+      rgb           = mix(vec3(gray), rgb, saturation);
+      gl_FragColor  = vec4(clamp(rgb, 0.0, 1.0), color.a);
+    }";
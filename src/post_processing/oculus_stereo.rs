@@ -21,6 +21,11 @@ pub struct OculusStereo {
     kappa_3: ShaderUniform<f32>,
     scale: ShaderUniform<Vector2<f32>>,
     scale_in: ShaderUniform<Vector2<f32>>,
+    lens_center_left: ShaderUniform<Vector2<f32>>,
+    lens_center_right: ShaderUniform<Vector2<f32>>,
+    kappa: [f32; 4],
+    scale_factor: f32,
+    lens_center_offset: f32,
     w: f32,
     h: f32,
 }
@@ -54,11 +59,55 @@ impl OculusStereo {
             kappa_3: shader.get_uniform("kappa_3").unwrap(),
             scale: shader.get_uniform("Scale").unwrap(),
             scale_in: shader.get_uniform("ScaleIn").unwrap(),
+            lens_center_left: shader.get_uniform("LensCenterLeft").unwrap(),
+            lens_center_right: shader.get_uniform("LensCenterRight").unwrap(),
+            kappa: [1.0, 1.7, 0.7, 15.0],
+            scale_factor: 0.9, // firebox: in Oculus SDK example it's "1.0f/Distortion.Scale"
+            lens_center_offset: 0.0,
             shader,
             h: 1f32, // will be updated in the first update
             w: 1f32, // ditto
         }
     }
+
+    /// The coefficients of the barrel distortion polynomial.
+    pub fn distortion_coefficients(&self) -> [f32; 4] {
+        self.kappa
+    }
+
+    /// Sets the coefficients of the barrel distortion polynomial.
+    ///
+    /// A point at the squared distance `r²` from the lens center is moved by a factor
+    /// `k0 + k1 * r² + k2 * r⁴ + k3 * r⁶`. Defaults to `[1.0, 1.7, 0.7, 15.0]`.
+    pub fn set_distortion_coefficients(&mut self, kappa: [f32; 4]) {
+        self.kappa = kappa;
+    }
+
+    /// The scale applied to the image before the distortion.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Sets the scale applied to the image before the distortion.
+    ///
+    /// Smaller values zoom into the image to fill the lenses. Defaults to `0.9`.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// The horizontal offset of the lens centers toward the middle of the screen.
+    pub fn lens_center_offset(&self) -> f32 {
+        self.lens_center_offset
+    }
+
+    /// Sets the horizontal offset of the lens centers toward the middle of the screen.
+    ///
+    /// This is expressed as a fraction of the screen width, and should match the difference
+    /// between the separation of the lenses and half the screen width. Defaults to `0.0`, i.e.,
+    /// the lenses are centered on each half of the screen.
+    pub fn set_lens_center_offset(&mut self, offset: f32) {
+        self.lens_center_offset = offset;
+    }
 }
 
 impl PostProcessingEffect for OculusStereo {
@@ -69,7 +118,7 @@ impl PostProcessingEffect for OculusStereo {
 
     fn draw(&mut self, target: &RenderTarget) {
         let ctxt = Context::get();
-        let scale_factor = 0.9f32; // firebox: in Oculus SDK example it's "1.0f/Distortion.Scale"
+        let scale_factor = self.scale_factor;
         let aspect = (self.w / 2.0f32) / (self.h); // firebox: rift's "half screen aspect ratio"
 
         self.shader.use_program();
@@ -79,7 +128,7 @@ impl PostProcessingEffect for OculusStereo {
         /*
          * Configure the post-process effect.
          */
-        let kappa = [1.0, 1.7, 0.7, 15.0];
+        let kappa = self.kappa;
         self.kappa_0.upload(&kappa[0]);
         self.kappa_1.upload(&kappa[1]);
         self.kappa_2.upload(&kappa[2]);
@@ -89,6 +138,10 @@ impl PostProcessingEffect for OculusStereo {
             2.0f32 * scale_factor,
             1.0f32 / aspect * scale_factor,
         ));
+        self.lens_center_left
+            .upload(&Vector2::new(0.25 + self.lens_center_offset, 0.5));
+        self.lens_center_right
+            .upload(&Vector2::new(0.75 - self.lens_center_offset, 0.5));
 
         /*
          * Finalize draw
@@ -133,8 +186,8 @@ uniform float kappa_0;
 uniform float kappa_1;
 uniform float kappa_2;
 uniform float kappa_3;
-const vec2 LensCenterLeft = vec2(0.25, 0.5);
-const vec2 LensCenterRight = vec2(0.75, 0.5);
+uniform vec2 LensCenterLeft;
+uniform vec2 LensCenterRight;
 uniform vec2 Scale;
 uniform vec2 ScaleIn;
 
@@ -0,0 +1,159 @@
+//! Post-processing effect darkening the borders of the screen.
+
+use na::Vector2;
+
+use crate::context::Context;
+use crate::post_processing::PostProcessingEffect;
+use crate::resource::{
+    AllocationType, BufferType, Effect, GPUVec, RenderTarget, ShaderAttribute, ShaderUniform,
+};
+use crate::verify;
+
+/// Post processing effect which darkens the borders of the screen.
+pub struct Vignette {
+    shader: Effect,
+    radius: f32,
+    softness: f32,
+    strength: f32,
+    aspect: f32,
+    fbo_texture: ShaderUniform<i32>,
+    radius_uniform: ShaderUniform<f32>,
+    softness_uniform: ShaderUniform<f32>,
+    strength_uniform: ShaderUniform<f32>,
+    aspect_uniform: ShaderUniform<f32>,
+    v_coord: ShaderAttribute<Vector2<f32>>,
+    fbo_vertices: GPUVec<Vector2<f32>>,
+}
+
+impl Vignette {
+    /// Creates a new `Vignette` post processing effect.
+    pub fn new() -> Vignette {
+        let fbo_vertices: Vec<Vector2<f32>> = vec![
+            Vector2::new(-1.0, -1.0),
+            Vector2::new(1.0, -1.0),
+            Vector2::new(-1.0, 1.0),
+            Vector2::new(1.0, 1.0),
+        ];
+
+        let mut fbo_vertices =
+            GPUVec::new(fbo_vertices, BufferType::Array, AllocationType::StaticDraw);
+        fbo_vertices.load_to_gpu();
+        fbo_vertices.unload_from_ram();
+
+        let mut shader = Effect::new_from_str(VERTEX_SHADER, FRAGMENT_SHADER);
+
+        shader.use_program();
+
+        Vignette {
+            radius: 0.75,
+            softness: 0.45,
+            strength: 1.0,
+            aspect: 1.0,
+            fbo_texture: shader.get_uniform("fbo_texture").unwrap(),
+            radius_uniform: shader.get_uniform("radius").unwrap(),
+            softness_uniform: shader.get_uniform("softness").unwrap(),
+            strength_uniform: shader.get_uniform("strength").unwrap(),
+            aspect_uniform: shader.get_uniform("aspect").unwrap(),
+            v_coord: shader.get_attrib("v_coord").unwrap(),
+            fbo_vertices,
+            shader,
+        }
+    }
+
+    /// The distance from the screen center where the darkening starts (default: 0.75).
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Sets the distance from the screen center where the darkening starts.
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius
+    }
+
+    /// The width of the transition between the lit and darkened areas (default: 0.45).
+    pub fn softness(&self) -> f32 {
+        self.softness
+    }
+
+    /// Sets the width of the transition between the lit and darkened areas.
+    pub fn set_softness(&mut self, softness: f32) {
+        self.softness = softness
+    }
+
+    /// How much the borders are darkened, between 0.0 and 1.0 (default: 1.0).
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    /// Sets how much the borders are darkened, between 0.0 and 1.0.
+    pub fn set_strength(&mut self, strength: f32) {
+        self.strength = strength
+    }
+}
+
+impl PostProcessingEffect for Vignette {
+    fn update(&mut self, _: f32, w: f32, h: f32, _: f32, _: f32) {
+        self.aspect = w / h;
+    }
+
+    fn draw(&mut self, target: &RenderTarget) {
+        let ctxt = Context::get();
+        self.v_coord.enable();
+
+        /*
+         * Configure the post-process effect.
+         */
+        self.shader.use_program();
+        self.radius_uniform.upload(&self.radius);
+        self.softness_uniform.upload(&self.softness);
+        self.strength_uniform.upload(&self.strength);
+        self.aspect_uniform.upload(&self.aspect);
+
+        /*
+         * Finalize draw
+         */
+        verify!(ctxt.clear_color(0.0, 0.0, 0.0, 1.0));
+        verify!(ctxt.clear(Context::COLOR_BUFFER_BIT | Context::DEPTH_BUFFER_BIT));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, target.texture_id()));
+
+        self.fbo_texture.upload(&0);
+        self.v_coord.bind(&mut self.fbo_vertices);
+
+        verify!(ctxt.draw_arrays(Context::TRIANGLE_STRIP, 0, 4));
+
+        self.v_coord.disable();
+    }
+}
+
+static VERTEX_SHADER: &str = "#version 100
+    attribute vec2    v_coord;
+    uniform sampler2D fbo_texture;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      gl_Position = vec4(v_coord, 0.0, 1.0);
+      f_texcoord  = (v_coord + 1.0) / 2.0;
+    }";
+
+static FRAGMENT_SHADER: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    uniform sampler2D fbo_texture;
+    uniform float     radius;
+    uniform float     softness;
+    uniform float     strength;
+    uniform float     aspect;
+    varying vec2      f_texcoord;
+
+    void main(void) {
+      vec4 color    = texture2D(fbo_texture, f_texcoord);
+      vec2 centered = (f_texcoord - 0.5) * 2.0;
+      centered.x   *= aspect;
+      float dist    = length(centered) / max(aspect, 1.0);
+      float shade   = 1.0 - smoothstep(radius - softness, radius, dist);
+      gl_FragColor  = vec4(color.rgb * mix(1.0, shade, strength), color.a);
+    }";
@@ -21,7 +21,12 @@ use crate::verify;
 pub struct Waves {
     shader: Effect,
     time: f32,
+    amplitude: f32,
+    frequency: f32,
+    speed: f32,
     offset: ShaderUniform<f32>,
+    amplitude_uniform: ShaderUniform<f32>,
+    frequency_uniform: ShaderUniform<f32>,
     fbo_texture: ShaderUniform<i32>,
     v_coord: ShaderAttribute<Vector2<f32>>,
     fbo_vertices: GPUVec<Vector2<f32>>,
@@ -48,18 +53,61 @@ impl Waves {
 
         Waves {
             time: 0.0,
+            amplitude: 0.01,
+            frequency: 4.0,
+            speed: 0.75,
             offset: shader.get_uniform("offset").unwrap(),
+            amplitude_uniform: shader.get_uniform("amplitude").unwrap(),
+            frequency_uniform: shader.get_uniform("frequency").unwrap(),
             fbo_texture: shader.get_uniform("fbo_texture").unwrap(),
             v_coord: shader.get_attrib("v_coord").unwrap(),
             fbo_vertices,
             shader,
         }
     }
+
+    /// The maximum horizontal displacement, as a fraction of the screen width.
+    pub fn amplitude(&self) -> f32 {
+        self.amplitude
+    }
+
+    /// Sets the maximum horizontal displacement, as a fraction of the screen width.
+    ///
+    /// The default value is 0.01.
+    pub fn set_amplitude(&mut self, amplitude: f32) {
+        self.amplitude = amplitude
+    }
+
+    /// The number of waves along the screen height.
+    pub fn frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Sets the number of waves along the screen height.
+    ///
+    /// The default value is 4.0.
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency
+    }
+
+    /// The number of wave cycles per second.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the number of wave cycles per second.
+    ///
+    /// The default value is 0.75.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed
+    }
 }
 
 impl PostProcessingEffect for Waves {
     fn update(&mut self, dt: f32, _: f32, _: f32, _: f32, _: f32) {
-        self.time += dt;
+        // Accumulate the phase instead of the time so that changing the speed does not make
+        // the waves jump.
+        self.time += dt * self.speed;
     }
 
     fn draw(&mut self, target: &RenderTarget) {
@@ -70,9 +118,11 @@ impl PostProcessingEffect for Waves {
          */
         self.shader.use_program();
 
-        let move_amount = self.time * 2.0 * f32::consts::PI * 0.75; // 3/4 of a wave cycle per second
+        let move_amount = self.time * 2.0 * f32::consts::PI;
 
         self.offset.upload(&move_amount);
+        self.amplitude_uniform.upload(&self.amplitude);
+        self.frequency_uniform.upload(&self.frequency);
 
         /*
          * Finalize draw
@@ -110,10 +160,12 @@ static FRAGMENT_SHADER: &str = "#version 100
 
     uniform sampler2D fbo_texture;
     uniform float     offset;
+    uniform float     amplitude;
+    uniform float     frequency;
     varying vec2      f_texcoord;
 
     void main(void) {
       vec2 texcoord =  f_texcoord;
-      texcoord.x    += sin(texcoord.y * 4.0 * 2.0 * 3.14159 + offset) / 100.0;
+      texcoord.x    += sin(texcoord.y * frequency * 2.0 * 3.14159 + offset) * amplitude;
       gl_FragColor  =  texture2D(fbo_texture, texcoord);
     }";
@@ -0,0 +1,44 @@
+use na::{Point2, Point3, Vector3};
+use ncollide3d::procedural::{IndexBuffer, TriMesh};
+use std::f32;
+
+/// Generates a flat disk centered at the origin and lying on the `xz` plane, facing the `+y`
+/// direction.
+///
+/// The disk is a fan of triangles around its center. Its texture coordinates map the unit square
+/// to the square enclosing the disk.
+///
+/// # Arguments
+/// * `radius` - the disk radius.
+/// * `segments` - number of subdivisions of the disk boundary. Must be at least 3.
+pub fn disk(radius: f32, segments: u32) -> TriMesh<f32> {
+    assert!(segments >= 3, "A disk needs at least 3 segments.");
+
+    let mut coords = vec![Point3::origin()];
+    let normals = vec![Vector3::y(); segments as usize + 1];
+    let mut uvs = vec![Point2::new(0.5, 0.5)];
+    let mut faces = Vec::with_capacity(segments as usize);
+
+    for i in 0..segments {
+        let theta = i as f32 / segments as f32 * f32::consts::PI * 2.0;
+        let (sin, cos) = theta.sin_cos();
+
+        coords.push(Point3::new(radius * cos, 0.0, radius * sin));
+        uvs.push(Point2::new((1.0 + cos) / 2.0, (1.0 + sin) / 2.0));
+    }
+
+    for i in 0..segments {
+        let a = 1 + i;
+        let b = 1 + (i + 1) % segments;
+
+        // Counterclockwise when seen from the `+y` direction.
+        faces.push(Point3::new(0, b, a));
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
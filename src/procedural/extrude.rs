@@ -0,0 +1,176 @@
+use na::{Point2, Point3, Vector3};
+use ncollide3d::procedural::{IndexBuffer, TriMesh};
+
+/// Generates a prism by extruding a closed 2D outline along the `y` axis.
+///
+/// The outline lies on the `xz` plane: each point `(x, y)` of the outline is mapped to
+/// `(x, 0, -y)` for the bottom of the prism, and to `(x, height, -y)` for its top. The outline
+/// may be given in any winding order and may be concave, but must not self-intersect. If its
+/// last point is equal to its first one, it is ignored.
+///
+/// # Arguments
+/// * `outline` - the closed polyline to extrude. Must have at least 3 distinct points.
+/// * `height` - the extrusion length along the `y` axis.
+/// * `caps` - whether the top and bottom faces of the prism are generated.
+pub fn extrude(outline: &[Point2<f32>], height: f32, caps: bool) -> TriMesh<f32> {
+    let mut outline = outline.to_vec();
+
+    if outline.len() > 1 && outline.first() == outline.last() {
+        let _ = outline.pop();
+    }
+
+    assert!(
+        outline.len() >= 3,
+        "An extrusion outline needs at least 3 points."
+    );
+
+    // Work with a counterclockwise outline so the faces point outward.
+    if signed_area(&outline) < 0.0 {
+        outline.reverse();
+    }
+
+    let to_3d = |p: &Point2<f32>, y: f32| Point3::new(p.x, y, -p.y);
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut faces = Vec::new();
+
+    /*
+     * Side walls, with one quad per edge so each wall is shaded flat.
+     */
+    let n = outline.len();
+    let perimeter: f32 = (0..n)
+        .map(|i| (outline[(i + 1) % n] - outline[i]).norm())
+        .sum();
+    let mut length = 0.0;
+
+    for i in 0..n {
+        let a = outline[i];
+        let b = outline[(i + 1) % n];
+        let dir = b - a;
+        let normal = Vector3::new(dir.y, 0.0, dir.x).normalize();
+        let u_a = length / perimeter;
+        length += dir.norm();
+        let u_b = length / perimeter;
+
+        let base = coords.len() as u32;
+        coords.extend_from_slice(&[
+            to_3d(&a, 0.0),
+            to_3d(&b, 0.0),
+            to_3d(&b, height),
+            to_3d(&a, height),
+        ]);
+        normals.extend_from_slice(&[normal; 4]);
+        uvs.extend_from_slice(&[
+            Point2::new(u_a, 1.0),
+            Point2::new(u_b, 1.0),
+            Point2::new(u_b, 0.0),
+            Point2::new(u_a, 0.0),
+        ]);
+        faces.push(Point3::new(base, base + 1, base + 2));
+        faces.push(Point3::new(base, base + 2, base + 3));
+    }
+
+    /*
+     * Caps.
+     */
+    if caps {
+        let mut min = outline[0];
+        let mut max = outline[0];
+
+        for p in &outline {
+            min = min.inf(p);
+            max = max.sup(p);
+        }
+
+        let extents = max - min;
+        let triangles = triangulate(&outline);
+
+        for &(y, normal) in &[(height, Vector3::y()), (0.0, -Vector3::y())] {
+            let base = coords.len() as u32;
+
+            for p in &outline {
+                coords.push(to_3d(p, y));
+                normals.push(normal);
+                uvs.push(Point2::new(
+                    (p.x - min.x) / extents.x,
+                    (max.y - p.y) / extents.y,
+                ));
+            }
+
+            for t in &triangles {
+                if y == 0.0 {
+                    // The bottom cap faces downward.
+                    faces.push(Point3::new(base + t[0], base + t[2], base + t[1]));
+                } else {
+                    faces.push(Point3::new(base + t[0], base + t[1], base + t[2]));
+                }
+            }
+        }
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
+
+fn signed_area(polygon: &[Point2<f32>]) -> f32 {
+    let n = polygon.len();
+    let twice_area: f32 = (0..n)
+        .map(|i| polygon[i].coords.perp(&polygon[(i + 1) % n].coords))
+        .sum();
+
+    twice_area / 2.0
+}
+
+/// Triangulates a simple counterclockwise polygon by ear clipping.
+fn triangulate(polygon: &[Point2<f32>]) -> Vec<[u32; 3]> {
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    let mut triangles = Vec::new();
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&i| {
+            let ia = remaining[(i + m - 1) % m];
+            let ib = remaining[i];
+            let ic = remaining[(i + 1) % m];
+            let (a, b, c) = (polygon[ia], polygon[ib], polygon[ic]);
+
+            // Reflex vertices are not ears.
+            if (b - a).perp(&(c - b)) <= 0.0 {
+                return false;
+            }
+
+            !remaining
+                .iter()
+                .filter(|&&j| j != ia && j != ib && j != ic)
+                .any(|&j| in_triangle(&polygon[j], &a, &b, &c))
+        });
+
+        match ear {
+            Some(i) => {
+                let ia = remaining[(i + m - 1) % m];
+                let ic = remaining[(i + 1) % m];
+                triangles.push([ia as u32, remaining[i] as u32, ic as u32]);
+                let _ = remaining.remove(i);
+            }
+            // Degenerate polygon: leave the rest of the cap open.
+            None => return triangles,
+        }
+    }
+
+    triangles.push([
+        remaining[0] as u32,
+        remaining[1] as u32,
+        remaining[2] as u32,
+    ]);
+    triangles
+}
+
+fn in_triangle(p: &Point2<f32>, a: &Point2<f32>, b: &Point2<f32>, c: &Point2<f32>) -> bool {
+    (b - a).perp(&(p - a)) >= 0.0 && (c - b).perp(&(p - b)) >= 0.0 && (a - c).perp(&(p - c)) >= 0.0
+}
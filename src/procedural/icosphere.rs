@@ -0,0 +1,109 @@
+use na::{Point2, Point3};
+use ncollide3d::procedural::{IndexBuffer, TriMesh};
+use std::collections::HashMap;
+use std::f32;
+
+/// Generates a geodesic sphere centered at the origin by subdividing an icosahedron.
+///
+/// Contrary to `ncollide3d::procedural::sphere`, the triangles of the result have roughly the same
+/// size everywhere. Each subdivision multiplies the number of triangles by four.
+///
+/// # Arguments
+/// * `radius` - the sphere radius.
+/// * `subdivisions` - the number of times the initial icosahedron is subdivided.
+pub fn icosphere(radius: f32, subdivisions: u32) -> TriMesh<f32> {
+    let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+
+    let mut coords = vec![
+        Point3::new(-1.0, t, 0.0),
+        Point3::new(1.0, t, 0.0),
+        Point3::new(-1.0, -t, 0.0),
+        Point3::new(1.0, -t, 0.0),
+        Point3::new(0.0, -1.0, t),
+        Point3::new(0.0, 1.0, t),
+        Point3::new(0.0, -1.0, -t),
+        Point3::new(0.0, 1.0, -t),
+        Point3::new(t, 0.0, -1.0),
+        Point3::new(t, 0.0, 1.0),
+        Point3::new(-t, 0.0, -1.0),
+        Point3::new(-t, 0.0, 1.0),
+    ];
+
+    for pt in coords.iter_mut() {
+        *pt = Point3::from(pt.coords.normalize());
+    }
+
+    let mut faces = vec![
+        Point3::new(0, 11, 5),
+        Point3::new(0, 5, 1),
+        Point3::new(0, 1, 7),
+        Point3::new(0, 7, 10),
+        Point3::new(0, 10, 11),
+        Point3::new(1, 5, 9),
+        Point3::new(5, 11, 4),
+        Point3::new(11, 10, 2),
+        Point3::new(10, 7, 6),
+        Point3::new(7, 1, 8),
+        Point3::new(3, 9, 4),
+        Point3::new(3, 4, 2),
+        Point3::new(3, 2, 6),
+        Point3::new(3, 6, 8),
+        Point3::new(3, 8, 9),
+        Point3::new(4, 9, 5),
+        Point3::new(2, 4, 11),
+        Point3::new(6, 2, 10),
+        Point3::new(8, 6, 7),
+        Point3::new(9, 8, 1),
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints = HashMap::new();
+        let mut new_faces = Vec::with_capacity(faces.len() * 4);
+
+        let mut midpoint = |a: u32, b: u32, coords: &mut Vec<Point3<f32>>| -> u32 {
+            let key = if a < b { (a, b) } else { (b, a) };
+
+            *midpoints.entry(key).or_insert_with(|| {
+                let mid = na::center(&coords[a as usize], &coords[b as usize]);
+                coords.push(Point3::from(mid.coords.normalize()));
+                coords.len() as u32 - 1
+            })
+        };
+
+        for f in faces.iter() {
+            let ab = midpoint(f.x, f.y, &mut coords);
+            let bc = midpoint(f.y, f.z, &mut coords);
+            let ca = midpoint(f.z, f.x, &mut coords);
+
+            new_faces.push(Point3::new(f.x, ab, ca));
+            new_faces.push(Point3::new(f.y, bc, ab));
+            new_faces.push(Point3::new(f.z, ca, bc));
+            new_faces.push(Point3::new(ab, bc, ca));
+        }
+
+        faces = new_faces;
+    }
+
+    // Spherical mapping of the texture coordinates.
+    let uvs = coords
+        .iter()
+        .map(|pt| {
+            Point2::new(
+                0.5 + pt.z.atan2(pt.x) / (f32::consts::PI * 2.0),
+                0.5 - pt.y.asin() / f32::consts::PI,
+            )
+        })
+        .collect();
+    let normals = coords.iter().map(|pt| pt.coords).collect();
+
+    for pt in coords.iter_mut() {
+        *pt = Point3::from(pt.coords * radius);
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
@@ -0,0 +1,16 @@
+//! Procedural mesh generators complementing those of `ncollide3d::procedural`.
+
+pub use self::disk::disk;
+pub use self::extrude::extrude;
+pub use self::icosphere::icosphere;
+pub use self::sphere_sector::sphere_sector;
+pub use self::torus::torus;
+pub use self::wireframe::{wire_cube, wire_sphere};
+
+mod disk;
+mod extrude;
+mod icosphere;
+mod sphere_sector;
+mod torus;
+pub mod utils;
+mod wireframe;
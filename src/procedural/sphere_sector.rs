@@ -0,0 +1,74 @@
+use na::{Point2, Point3, Vector3};
+use ncollide3d::procedural::{IndexBuffer, TriMesh};
+use std::ops::Range;
+
+/// Generates a part of a sphere centered at the origin, delimited by ranges of spherical angles.
+///
+/// A point of the sphere has the polar angle `phi` from the `+y` axis, in `[0, π]`, and the
+/// azimuthal angle `theta` around the `y` axis, starting from the `+x` axis toward the `+z` axis.
+/// For example, `0.0..FRAC_PI_2` for `phi` and `0.0..2.0 * PI` for `theta` give a dome over the
+/// `xz` plane. The surface is open, so it should be drawn without backface culling.
+///
+/// # Arguments
+/// * `radius` - the sphere radius.
+/// * `phi_range` - the range of polar angles, in radians.
+/// * `theta_range` - the range of azimuthal angles, in radians.
+/// * `rings` - number of subdivisions along `phi_range`. Must be at least 1.
+/// * `sectors` - number of subdivisions along `theta_range`. Must be at least 1.
+pub fn sphere_sector(
+    radius: f32,
+    phi_range: Range<f32>,
+    theta_range: Range<f32>,
+    rings: u32,
+    sectors: u32,
+) -> TriMesh<f32> {
+    assert!(
+        rings >= 1 && sectors >= 1,
+        "A sphere sector needs at least 1 subdivision along each direction."
+    );
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut faces = Vec::new();
+
+    for i in 0..=rings {
+        let v = i as f32 / rings as f32;
+        let phi = phi_range.start + (phi_range.end - phi_range.start) * v;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+
+        for j in 0..=sectors {
+            let u = j as f32 / sectors as f32;
+            let theta = theta_range.start + (theta_range.end - theta_range.start) * u;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+
+            let normal = Vector3::new(sin_phi * cos_theta, cos_phi, sin_phi * sin_theta);
+
+            coords.push(Point3::from(normal * radius));
+            normals.push(normal);
+            uvs.push(Point2::new(u, v));
+        }
+    }
+
+    let stride = sectors + 1;
+
+    for i in 0..rings {
+        for j in 0..sectors {
+            let a = i * stride + j;
+            let b = a + 1;
+            let c = a + stride;
+            let d = c + 1;
+
+            // Counterclockwise when seen from the outside of the sphere.
+            faces.push(Point3::new(a, b, c));
+            faces.push(Point3::new(b, d, c));
+        }
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
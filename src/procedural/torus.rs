@@ -0,0 +1,66 @@
+use na::{Point2, Point3, Vector3};
+use ncollide3d::procedural::{IndexBuffer, TriMesh};
+use std::f32;
+
+/// Generates a torus centered at the origin and lying on the `xz` plane.
+///
+/// # Arguments
+/// * `major_radius` - the distance from the torus center to the center of its tube.
+/// * `minor_radius` - the radius of the tube.
+/// * `major_segments` - number of subdivisions around the torus center. Must be at least 3.
+/// * `minor_segments` - number of subdivisions around the tube. Must be at least 3.
+pub fn torus(
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: u32,
+    minor_segments: u32,
+) -> TriMesh<f32> {
+    assert!(
+        major_segments >= 3 && minor_segments >= 3,
+        "A torus needs at least 3 segments along each direction."
+    );
+
+    let mut coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut faces = Vec::new();
+
+    // The seam vertices are duplicated so that texture coordinates wrap properly.
+    for i in 0..=major_segments {
+        let u = i as f32 / major_segments as f32;
+        let (sin_theta, cos_theta) = (u * f32::consts::PI * 2.0).sin_cos();
+
+        for j in 0..=minor_segments {
+            let v = j as f32 / minor_segments as f32;
+            let (sin_phi, cos_phi) = (v * f32::consts::PI * 2.0).sin_cos();
+
+            let center = Point3::new(major_radius * cos_theta, 0.0, major_radius * sin_theta);
+            let normal = Vector3::new(cos_phi * cos_theta, sin_phi, cos_phi * sin_theta);
+
+            coords.push(center + normal * minor_radius);
+            normals.push(normal);
+            uvs.push(Point2::new(u, v));
+        }
+    }
+
+    let stride = minor_segments + 1;
+
+    for i in 0..major_segments {
+        for j in 0..minor_segments {
+            let a = i * stride + j;
+            let b = (i + 1) * stride + j;
+            let c = b + 1;
+            let d = a + 1;
+
+            faces.push(Point3::new(a, d, b));
+            faces.push(Point3::new(d, c, b));
+        }
+    }
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        Some(uvs),
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
@@ -0,0 +1,293 @@
+//! Utilities for building custom meshes.
+
+use na::{Point2, Point3, Vector3, Vector4};
+use ncollide3d::procedural::{IndexBuffer, TriMesh};
+use std::collections::HashMap;
+
+/// Computes the per-vertex tangents of a triangle mesh from its texture coordinates.
+///
+/// The tangents follow the direction of increasing `u` texture coordinate. The `w` component of
+/// each tangent is the handedness of the tangent space (`1.0` or `-1.0`): the bitangent is
+/// `w * normal.cross(&tangent.xyz())`. The normals used to orthogonalize the tangents are
+/// computed from the faces of the mesh, weighted by their areas.
+///
+/// This uses the method of Eric Lengyel, "Computing Tangent Space Basis Vectors for an Arbitrary
+/// Mesh". Vertices that are not referenced by any face, or whose faces have degenerate texture
+/// coordinates, get an arbitrary tangent orthogonal to their normal.
+///
+/// # Arguments
+/// * `vertices` - the vertex positions.
+/// * `uvs` - the vertex texture coordinates. Must have the same length as `vertices`.
+/// * `indices` - the triangles, as indices into `vertices`.
+pub fn compute_tangents(
+    vertices: &[Point3<f32>],
+    uvs: &[Point2<f32>],
+    indices: &[Point3<u32>],
+) -> Vec<Vector4<f32>> {
+    assert_eq!(
+        vertices.len(),
+        uvs.len(),
+        "There must be exactly one texture coordinate per vertex."
+    );
+
+    let mut normals = vec![Vector3::zeros(); vertices.len()];
+    let mut tangents = vec![Vector3::zeros(); vertices.len()];
+    let mut bitangents = vec![Vector3::zeros(); vertices.len()];
+
+    for face in indices {
+        let (i1, i2, i3) = (face.x as usize, face.y as usize, face.z as usize);
+        let e1 = vertices[i2] - vertices[i1];
+        let e2 = vertices[i3] - vertices[i1];
+        let duv1 = uvs[i2] - uvs[i1];
+        let duv2 = uvs[i3] - uvs[i1];
+
+        // Not normalized so larger faces contribute more.
+        let normal = e1.cross(&e2);
+
+        for i in &[i1, i2, i3] {
+            normals[*i] += normal;
+        }
+
+        let det = duv1.x * duv2.y - duv2.x * duv1.y;
+
+        if det.abs() < 1.0e-12 {
+            continue;
+        }
+
+        let r = 1.0 / det;
+        let tangent = (e1 * duv2.y - e2 * duv1.y) * r;
+        let bitangent = (e2 * duv1.x - e1 * duv2.x) * r;
+
+        for i in &[i1, i2, i3] {
+            tangents[*i] += tangent;
+            bitangents[*i] += bitangent;
+        }
+    }
+
+    normals
+        .iter()
+        .zip(tangents.iter().zip(bitangents.iter()))
+        .map(|(n, (t, b))| {
+            let n = n.try_normalize(1.0e-12).unwrap_or_else(Vector3::y);
+
+            // Gram-Schmidt orthogonalization.
+            let t = (t - n * n.dot(t))
+                .try_normalize(1.0e-12)
+                .unwrap_or_else(|| any_orthogonal(&n));
+            let w = if n.cross(&t).dot(b) < 0.0 { -1.0 } else { 1.0 };
+
+            Vector4::new(t.x, t.y, t.z, w)
+        })
+        .collect()
+}
+
+// A unit vector orthogonal to the unit vector `n`.
+fn any_orthogonal(n: &Vector3<f32>) -> Vector3<f32> {
+    let axis = if n.x.abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+
+    n.cross(&axis).normalize()
+}
+
+/// Smooths a triangle mesh with the Loop subdivision scheme.
+///
+/// Each iteration splits every triangle into four, and moves the vertices toward a weighted
+/// average of their neighbors. The edges with only one adjacent triangle are kept as creases.
+/// Vertices at the same position are considered connected, so the texture seams of the input
+/// mesh do not open holes. The texture coordinates, if any, are interpolated linearly, and the
+/// normals are recomputed.
+///
+/// # Arguments
+/// * `mesh` - the mesh to subdivide.
+/// * `iterations` - the number of subdivisions. Each one multiplies the number of triangles by
+///   four.
+pub fn subdivide_loop(mesh: &TriMesh<f32>, iterations: u32) -> TriMesh<f32> {
+    let mut mesh = mesh.clone();
+    mesh.unify_index_buffer();
+
+    let mut coords = mesh.coords;
+    let mut uvs = mesh.uvs;
+    let mut faces = mesh.indices.unwrap_unified();
+
+    for _ in 0..iterations {
+        let (new_coords, new_uvs, new_faces) = loop_step(&coords, uvs.as_deref(), &faces);
+        coords = new_coords;
+        uvs = new_uvs;
+        faces = new_faces;
+    }
+
+    let normals = welded_normals(&coords, &faces);
+
+    TriMesh::new(
+        coords,
+        Some(normals),
+        uvs,
+        Some(IndexBuffer::Unified(faces)),
+    )
+}
+
+// Maps each vertex to the index of the first vertex at the same position.
+fn weld(coords: &[Point3<f32>]) -> Vec<usize> {
+    let mut first = HashMap::new();
+
+    coords
+        .iter()
+        .enumerate()
+        .map(|(i, pt)| {
+            let key = [pt.x.to_bits(), pt.y.to_bits(), pt.z.to_bits()];
+            *first.entry(key).or_insert(i)
+        })
+        .collect()
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn loop_step(
+    coords: &[Point3<f32>],
+    uvs: Option<&[Point2<f32>]>,
+    faces: &[Point3<u32>],
+) -> (Vec<Point3<f32>>, Option<Vec<Point2<f32>>>, Vec<Point3<u32>>) {
+    let welded = weld(coords);
+
+    /*
+     * Topology of the welded mesh: the vertices opposite to each edge.
+     */
+    let mut opposites: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+    for face in faces {
+        let ids = [
+            welded[face.x as usize],
+            welded[face.y as usize],
+            welded[face.z as usize],
+        ];
+
+        for i in 0..3 {
+            let (a, b, c) = (ids[i], ids[(i + 1) % 3], ids[(i + 2) % 3]);
+            opposites.entry(edge_key(a, b)).or_default().push(c);
+        }
+    }
+
+    let mut neighbors: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut boundary_neighbors: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    for (&(a, b), opp) in &opposites {
+        neighbors.entry(a).or_default().push(b);
+        neighbors.entry(b).or_default().push(a);
+
+        if opp.len() != 2 {
+            boundary_neighbors.entry(a).or_default().push(b);
+            boundary_neighbors.entry(b).or_default().push(a);
+        }
+    }
+
+    /*
+     * Move the original vertices.
+     */
+    let mut new_coords: Vec<Point3<f32>> = coords
+        .iter()
+        .enumerate()
+        .map(|(i, pt)| {
+            let w = welded[i];
+
+            if let Some(bn) = boundary_neighbors.get(&w) {
+                // Corners and non-manifold vertices are kept in place.
+                return if bn.len() == 2 {
+                    let sum = coords[bn[0]].coords + coords[bn[1]].coords;
+                    Point3::from(pt.coords * 0.75 + sum * 0.125)
+                } else {
+                    *pt
+                };
+            }
+
+            match neighbors.get(&w) {
+                Some(nbs) => {
+                    let n = nbs.len() as f32;
+                    let beta = if nbs.len() == 3 {
+                        3.0 / 16.0
+                    } else {
+                        3.0 / (8.0 * n)
+                    };
+                    let sum = nbs
+                        .iter()
+                        .fold(Vector3::zeros(), |acc, j| acc + coords[*j].coords);
+
+                    Point3::from(pt.coords * (1.0 - n * beta) + sum * beta)
+                }
+                None => *pt,
+            }
+        })
+        .collect();
+    let mut new_uvs = uvs.map(|uvs| uvs.to_vec());
+
+    /*
+     * Insert one vertex per edge, and split each face into four.
+     */
+    let mut edge_vertices: HashMap<(usize, usize), u32> = HashMap::new();
+    let mut new_faces = Vec::with_capacity(faces.len() * 4);
+
+    for face in faces {
+        let ids = [face.x as usize, face.y as usize, face.z as usize];
+        let mut mids = [0u32; 3];
+
+        for i in 0..3 {
+            let (a, b) = (ids[i], ids[(i + 1) % 3]);
+            mids[i] = *edge_vertices.entry(edge_key(a, b)).or_insert_with(|| {
+                let (wa, wb) = (welded[a], welded[b]);
+                let opp = &opposites[&edge_key(wa, wb)];
+                let pt = if opp.len() == 2 {
+                    (coords[wa].coords + coords[wb].coords) * 0.375
+                        + (coords[opp[0]].coords + coords[opp[1]].coords) * 0.125
+                } else {
+                    (coords[wa].coords + coords[wb].coords) * 0.5
+                };
+
+                new_coords.push(Point3::from(pt));
+
+                if let (Some(new_uvs), Some(uvs)) = (new_uvs.as_mut(), uvs) {
+                    new_uvs.push(na::center(&uvs[a], &uvs[b]));
+                }
+
+                new_coords.len() as u32 - 1
+            });
+        }
+
+        new_faces.push(Point3::new(face.x, mids[0], mids[2]));
+        new_faces.push(Point3::new(face.y, mids[1], mids[0]));
+        new_faces.push(Point3::new(face.z, mids[2], mids[1]));
+        new_faces.push(Point3::new(mids[0], mids[1], mids[2]));
+    }
+
+    (new_coords, new_uvs, new_faces)
+}
+
+// Area-weighted vertex normals, shared by the vertices at the same position.
+fn welded_normals(coords: &[Point3<f32>], faces: &[Point3<u32>]) -> Vec<Vector3<f32>> {
+    let welded = weld(coords);
+    let mut normals = vec![Vector3::zeros(); coords.len()];
+
+    for face in faces {
+        let (a, b, c) = (face.x as usize, face.y as usize, face.z as usize);
+        let normal = (coords[b] - coords[a]).cross(&(coords[c] - coords[a]));
+
+        for i in &[a, b, c] {
+            normals[welded[*i]] += normal;
+        }
+    }
+
+    (0..coords.len())
+        .map(|i| {
+            normals[welded[i]]
+                .try_normalize(1.0e-12)
+                .unwrap_or_else(Vector3::y)
+        })
+        .collect()
+}
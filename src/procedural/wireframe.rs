@@ -0,0 +1,94 @@
+use na::{Point2, Point3};
+use std::f32::consts::PI;
+
+/// Generates the 12 edges of a box centered at the origin.
+///
+/// Returns the box corners, and the segments joining them as pairs of indices into the corners.
+///
+/// # Arguments
+/// * `wx` - the box extent along the x axis
+/// * `wy` - the box extent along the y axis
+/// * `wz` - the box extent along the z axis
+pub fn wire_cube(wx: f32, wy: f32, wz: f32) -> (Vec<Point3<f32>>, Vec<Point2<u32>>) {
+    let (hx, hy, hz) = (wx / 2.0, wy / 2.0, wz / 2.0);
+    let coords = (0..8)
+        .map(|i| {
+            Point3::new(
+                if i & 1 == 0 { -hx } else { hx },
+                if i & 2 == 0 { -hy } else { hy },
+                if i & 4 == 0 { -hz } else { hz },
+            )
+        })
+        .collect();
+
+    // Two corners share an edge if their indices differ by exactly one bit.
+    let mut edges = Vec::with_capacity(12);
+
+    for i in 0..8u32 {
+        for bit in &[1, 2, 4] {
+            if i & bit == 0 {
+                edges.push(Point2::new(i, i | bit));
+            }
+        }
+    }
+
+    (coords, edges)
+}
+
+/// Generates the meridians and parallels of a sphere centered at the origin.
+///
+/// Returns the vertices, and the segments joining them as pairs of indices into the vertices.
+///
+/// # Arguments
+/// * `diameter` - the sphere diameter
+/// * `ntheta_subdiv` - the number of meridians, and of segments of each parallel. Must be at
+///   least 3.
+/// * `nphi_subdiv` - the number of segments of each meridian. There are `nphi_subdiv - 1`
+///   parallels. Must be at least 2.
+pub fn wire_sphere(
+    diameter: f32,
+    ntheta_subdiv: u32,
+    nphi_subdiv: u32,
+) -> (Vec<Point3<f32>>, Vec<Point2<u32>>) {
+    assert!(
+        ntheta_subdiv >= 3 && nphi_subdiv >= 2,
+        "A wire sphere needs at least 3 meridians and 2 segments per meridian."
+    );
+
+    let r = diameter / 2.0;
+    let mut coords = vec![Point3::new(0.0, -r, 0.0), Point3::new(0.0, r, 0.0)];
+    let mut edges = Vec::new();
+
+    // Vertices of the parallels, from the bottom to the top of the sphere.
+    for j in 1..nphi_subdiv {
+        let phi = PI * j as f32 / nphi_subdiv as f32 - PI / 2.0;
+        let (y, ring_r) = (r * phi.sin(), r * phi.cos());
+
+        for i in 0..ntheta_subdiv {
+            let theta = 2.0 * PI * i as f32 / ntheta_subdiv as f32;
+            coords.push(Point3::new(ring_r * theta.cos(), y, ring_r * theta.sin()));
+        }
+    }
+
+    let vertex = |i: u32, j: u32| 2 + (j - 1) * ntheta_subdiv + i % ntheta_subdiv;
+
+    for j in 1..nphi_subdiv {
+        for i in 0..ntheta_subdiv {
+            // Parallels.
+            edges.push(Point2::new(vertex(i, j), vertex(i + 1, j)));
+
+            // Meridians.
+            if j == 1 {
+                edges.push(Point2::new(0, vertex(i, j)));
+            } else {
+                edges.push(Point2::new(vertex(i, j - 1), vertex(i, j)));
+            }
+
+            if j == nphi_subdiv - 1 {
+                edges.push(Point2::new(vertex(i, j), 1));
+            }
+        }
+    }
+
+    (coords, edges)
+}
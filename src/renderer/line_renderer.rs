@@ -5,9 +5,12 @@ use crate::context::Context;
 use crate::renderer::Renderer;
 use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform};
 use crate::verify;
-use na::{Matrix4, Point3};
+use na::{Matrix4, Point2, Point3, Vector2};
 
 /// Structure which manages the display of short-living lines.
+///
+/// Lines wider than the maximum width supported by the graphics driver, which is often `1.0`,
+/// are drawn as screen-aligned quads instead of native lines.
 pub struct LineRenderer {
     shader: Effect,
     pos: ShaderAttribute<Point3<f32>>,
@@ -16,6 +19,133 @@ pub struct LineRenderer {
     proj: ShaderUniform<Matrix4<f32>>,
     lines: GPUVec<Point3<f32>>,
     line_width: f32,
+    max_line_width: f32,
+    thick: ThickLineEffect,
+}
+
+/// The shader used to draw lines as quads.
+struct ThickLineEffect {
+    shader: Effect,
+    start: ShaderAttribute<Point3<f32>>,
+    end: ShaderAttribute<Point3<f32>>,
+    color: ShaderAttribute<Point3<f32>>,
+    corner: ShaderAttribute<Point2<f32>>,
+    view: ShaderUniform<Matrix4<f32>>,
+    proj: ShaderUniform<Matrix4<f32>>,
+    viewport: ShaderUniform<Vector2<f32>>,
+    width: ShaderUniform<f32>,
+    // The start, end, and color of the line of each vertex.
+    vertices: GPUVec<Point3<f32>>,
+    corners: GPUVec<Point2<f32>>,
+}
+
+/// The corners of the quad covering a line, as `(endpoint, side)` pairs.
+const CORNERS: [[f32; 2]; 6] = [
+    [0.0, -1.0],
+    [0.0, 1.0],
+    [1.0, -1.0],
+    [1.0, -1.0],
+    [0.0, 1.0],
+    [1.0, 1.0],
+];
+
+impl ThickLineEffect {
+    fn new() -> ThickLineEffect {
+        let mut shader = Effect::new_from_str(THICK_LINES_VERTEX_SRC, LINES_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        ThickLineEffect {
+            vertices: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            corners: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            start: shader
+                .get_attrib::<Point3<f32>>("start")
+                .expect("Failed to get shader attribute."),
+            end: shader
+                .get_attrib::<Point3<f32>>("end")
+                .expect("Failed to get shader attribute."),
+            color: shader
+                .get_attrib::<Point3<f32>>("color")
+                .expect("Failed to get shader attribute."),
+            corner: shader
+                .get_attrib::<Point2<f32>>("corner")
+                .expect("Failed to get shader attribute."),
+            view: shader
+                .get_uniform::<Matrix4<f32>>("view")
+                .expect("Failed to get shader uniform."),
+            proj: shader
+                .get_uniform::<Matrix4<f32>>("proj")
+                .expect("Failed to get shader uniform."),
+            viewport: shader
+                .get_uniform::<Vector2<f32>>("viewport")
+                .expect("Failed to get shader uniform."),
+            width: shader
+                .get_uniform::<f32>("width")
+                .expect("Failed to get shader uniform."),
+            shader,
+        }
+    }
+
+    /// Draws the given interleaved line endpoints and colors as quads.
+    fn render(
+        &mut self,
+        pass: usize,
+        camera: &mut dyn Camera,
+        lines: &[Point3<f32>],
+        line_width: f32,
+    ) {
+        let ctxt = Context::get();
+        let mut viewport = [0; 4];
+        verify!(ctxt.get_parameter_i32_slice(Context::VIEWPORT, &mut viewport));
+
+        if viewport[2] <= 0 || viewport[3] <= 0 {
+            return;
+        }
+
+        for vertices in self.vertices.data_mut().iter_mut() {
+            vertices.clear();
+
+            for line in lines.chunks(4) {
+                // Each line is stored as `[a, color, b, color]`.
+                for _ in 0..6 {
+                    vertices.extend_from_slice(&[line[0], line[2], line[1]]);
+                }
+            }
+        }
+
+        for corners in self.corners.data_mut().iter_mut() {
+            corners.clear();
+
+            for _ in 0..lines.len() / 4 {
+                corners.extend(CORNERS.iter().map(|c| Point2::new(c[0], c[1])));
+            }
+        }
+
+        self.shader.use_program();
+        self.start.enable();
+        self.end.enable();
+        self.color.enable();
+        self.corner.enable();
+
+        camera.upload(pass, &mut self.proj, &mut self.view);
+
+        self.viewport
+            .upload(&Vector2::new(viewport[2] as f32, viewport[3] as f32));
+        self.width.upload(&line_width);
+        self.start.bind_sub_buffer(&mut self.vertices, 2, 0);
+        self.end.bind_sub_buffer(&mut self.vertices, 2, 1);
+        self.color.bind_sub_buffer(&mut self.vertices, 2, 2);
+        self.corner.bind_sub_buffer(&mut self.corners, 0, 0);
+
+        verify!(ctxt.disable(Context::CULL_FACE));
+        let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
+        verify!(ctxt.draw_arrays(Context::TRIANGLES, 0, self.corners.len() as i32));
+
+        self.start.disable();
+        self.end.disable();
+        self.color.disable();
+        self.corner.disable();
+    }
 }
 
 impl LineRenderer {
@@ -41,6 +171,8 @@ impl LineRenderer {
                 .expect("Failed to get shader uniform."),
             shader,
             line_width: 1.0,
+            max_line_width: max_line_width(),
+            thick: ThickLineEffect::new(),
         }
     }
 
@@ -60,7 +192,9 @@ impl LineRenderer {
         }
     }
 
-    /// Sets the line width for the rendered lines.
+    /// Sets the line width for the rendered lines, in pixels.
+    ///
+    /// Lines wider than the maximum width supported by the graphics driver are drawn as quads.
     pub fn set_line_width(&mut self, line_width: f32) {
         self.line_width = line_width.max(
             f32::EPSILON, /* Gl will usually round this to 1 pixel */
@@ -75,21 +209,26 @@ impl Renderer for LineRenderer {
             return;
         }
 
-        self.shader.use_program();
-        self.pos.enable();
-        self.color.enable();
+        if self.line_width > self.max_line_width {
+            let lines = self.lines.data().as_ref().unwrap();
+            self.thick.render(pass, camera, lines, self.line_width);
+        } else {
+            self.shader.use_program();
+            self.pos.enable();
+            self.color.enable();
 
-        camera.upload(pass, &mut self.proj, &mut self.view);
+            camera.upload(pass, &mut self.proj, &mut self.view);
 
-        self.color.bind_sub_buffer(&mut self.lines, 1, 1);
-        self.pos.bind_sub_buffer(&mut self.lines, 1, 0);
+            self.color.bind_sub_buffer(&mut self.lines, 1, 1);
+            self.pos.bind_sub_buffer(&mut self.lines, 1, 0);
 
-        let ctxt = Context::get();
-        verify!(ctxt.line_width(self.line_width));
-        verify!(ctxt.draw_arrays(Context::LINES, 0, (self.lines.len() / 2) as i32));
+            let ctxt = Context::get();
+            verify!(ctxt.line_width(self.line_width));
+            verify!(ctxt.draw_arrays(Context::LINES, 0, (self.lines.len() / 2) as i32));
 
-        self.pos.disable();
-        self.color.disable();
+            self.pos.disable();
+            self.color.disable();
+        }
 
         for lines in self.lines.data_mut().iter_mut() {
             lines.clear()
@@ -97,6 +236,13 @@ impl Renderer for LineRenderer {
     }
 }
 
+/// The maximum width of the lines natively supported by the graphics driver.
+fn max_line_width() -> f32 {
+    let mut range = [1.0; 2];
+    verify!(Context::get().get_parameter_f32_slice(Context::ALIASED_LINE_WIDTH_RANGE, &mut range));
+    range[1].max(1.0)
+}
+
 /// Vertex shader used by the material to display line.
 pub static LINES_VERTEX_SRC: &str = A_VERY_LONG_STRING;
 /// Fragment shader used by the material to display line.
@@ -124,3 +270,41 @@ const ANOTHER_VERY_LONG_STRING: &str = "#version 100
     void main() {
         gl_FragColor = vec4(vColor, 1.0);
     }";
+
+/// Vertex shader used to display lines as quads.
+static THICK_LINES_VERTEX_SRC: &str = "#version 100
+    attribute vec3 start;
+    attribute vec3 end;
+    attribute vec3 color;
+    attribute vec2 corner;
+    varying   vec3 vColor;
+    uniform   mat4 proj;
+    uniform   mat4 view;
+    uniform   vec2 viewport;
+    uniform   float width;
+
+    void main() {
+        vec4 a = proj * view * vec4(start, 1.0);
+        vec4 b = proj * view * vec4(end, 1.0);
+        vColor = color;
+
+        // Clip the line against the near plane so both endpoints can be projected.
+        float min_w = 1.0e-5;
+
+        if (a.w < min_w && b.w < min_w) {
+            gl_Position = vec4(0.0, 0.0, 2.0, 1.0);
+            return;
+        } else if (a.w < min_w) {
+            a = mix(a, b, (min_w - a.w) / (b.w - a.w));
+        } else if (b.w < min_w) {
+            b = mix(b, a, (min_w - b.w) / (a.w - b.w));
+        }
+
+        vec2 dir = (b.xy / b.w - a.xy / a.w) * viewport;
+        dir = length(dir) > 0.0 ? normalize(dir) : vec2(1.0, 0.0);
+        vec2 normal = vec2(-dir.y, dir.x);
+
+        vec4 pos = corner.x < 0.5 ? a : b;
+        pos.xy += normal * corner.y * width / viewport * pos.w;
+        gl_Position = pos;
+    }";
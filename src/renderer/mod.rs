@@ -3,11 +3,19 @@
 #[cfg(feature = "conrod")]
 pub use self::conrod_renderer::ConrodRenderer;
 pub use self::line_renderer::LineRenderer;
-pub use self::point_renderer::PointRenderer;
+pub use self::point_renderer::{PointRenderer, PointShape};
+pub(crate) use self::rectangle_renderer::RectangleRenderer;
 pub use self::renderer::Renderer;
+pub use self::sprite_batch::SpriteBatch;
+pub(crate) use self::sprite_batch::SpriteBatchRenderer;
+pub use self::transparency::TransparencyMode;
 
 #[cfg(feature = "conrod")]
 mod conrod_renderer;
 pub mod line_renderer;
 pub mod point_renderer;
+mod rectangle_renderer;
 mod renderer;
+pub(crate) mod shadow_map;
+mod sprite_batch;
+pub(crate) mod transparency;
@@ -7,6 +7,15 @@ use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribut
 use crate::verify;
 use na::{Matrix4, Point3};
 
+/// The shape of the points drawn by a `PointRenderer`.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PointShape {
+    /// Screen-aligned squares.
+    Square,
+    /// Discs with antialiased edges.
+    Round,
+}
+
 /// Structure which manages the display of short-living points.
 pub struct PointRenderer {
     shader: Effect,
@@ -14,8 +23,11 @@ pub struct PointRenderer {
     color: ShaderAttribute<Point3<f32>>,
     proj: ShaderUniform<Matrix4<f32>>,
     view: ShaderUniform<Matrix4<f32>>,
+    round_points: ShaderUniform<i32>,
+    size: ShaderUniform<f32>,
     points: GPUVec<Point3<f32>>,
     point_size: f32,
+    point_shape: PointShape,
 }
 
 impl PointRenderer {
@@ -31,8 +43,11 @@ impl PointRenderer {
             color: shader.get_attrib::<Point3<f32>>("color").unwrap(),
             proj: shader.get_uniform::<Matrix4<f32>>("proj").unwrap(),
             view: shader.get_uniform::<Matrix4<f32>>("view").unwrap(),
+            round_points: shader.get_uniform::<i32>("round_points").unwrap(),
+            size: shader.get_uniform::<f32>("size").unwrap(),
             shader,
             point_size: 1.0,
+            point_shape: PointShape::Square,
         }
     }
 
@@ -46,6 +61,16 @@ impl PointRenderer {
         self.point_size = pt_size;
     }
 
+    /// Sets the shape of the rendered points. Defaults to `PointShape::Square`.
+    pub fn set_point_shape(&mut self, shape: PointShape) {
+        self.point_shape = shape;
+    }
+
+    /// The shape of the rendered points.
+    pub fn point_shape(&self) -> PointShape {
+        self.point_shape
+    }
+
     /// Adds a point to be drawn during the next frame. Points are not persistent between frames.
     /// This method must be called for each point to draw, and at each update loop iteration.
     pub fn draw_point(&mut self, pt: Point3<f32>, color: Point3<f32>) {
@@ -72,10 +97,30 @@ impl Renderer for PointRenderer {
         self.color.bind_sub_buffer(&mut self.points, 1, 1);
         self.pos.bind_sub_buffer(&mut self.points, 1, 0);
 
+        let round = self.point_shape == PointShape::Round;
+        self.round_points.upload(&(round as i32));
+        self.size.upload(&self.point_size);
+
         let ctxt = Context::get();
+
+        if round {
+            // Blend the antialiased edges of the discs.
+            verify!(ctxt.enable(Context::BLEND));
+            verify!(ctxt.blend_func_separate(
+                Context::SRC_ALPHA,
+                Context::ONE_MINUS_SRC_ALPHA,
+                Context::ONE,
+                Context::ONE_MINUS_SRC_ALPHA,
+            ));
+        }
+
         verify!(ctxt.point_size(self.point_size));
         verify!(ctxt.draw_arrays(Context::POINTS, 0, (self.points.len() / 2) as i32));
 
+        if round {
+            verify!(ctxt.disable(Context::BLEND));
+        }
+
         self.pos.disable();
         self.color.disable();
 
@@ -108,7 +153,21 @@ const ANOTHER_VERY_LONG_STRING: &str = "#version 100
    precision mediump float;
 #endif
 
-    varying vec3 Color;
+    varying vec3  Color;
+    uniform int   round_points;
+    uniform float size;
     void main() {
-        gl_FragColor = vec4(Color, 1.0);
+        float alpha = 1.0;
+
+        if (round_points != 0) {
+            // Distance to the point center, in pixels.
+            float dist = length(gl_PointCoord - vec2(0.5)) * size;
+            alpha = clamp(size / 2.0 - dist + 0.5, 0.0, 1.0);
+
+            if (alpha == 0.0) {
+                discard;
+            }
+        }
+
+        gl_FragColor = vec4(Color, alpha);
     }";
@@ -0,0 +1,127 @@
+//! A batched renderer of screen-space rectangles.
+
+use crate::context::Context;
+use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform};
+use crate::verify;
+use na::{Point2, Point4, Vector2};
+
+/// Structure which manages the display of short-living screen-space filled rectangles.
+pub(crate) struct RectangleRenderer {
+    shader: Effect,
+    pos: ShaderAttribute<Point2<f32>>,
+    color: ShaderAttribute<Point4<f32>>,
+    invsz: ShaderUniform<Vector2<f32>>,
+    coords: GPUVec<Point2<f32>>,
+    colors: GPUVec<Point4<f32>>,
+}
+
+impl RectangleRenderer {
+    /// Creates a new rectangle renderer.
+    pub fn new() -> RectangleRenderer {
+        let mut shader = Effect::new_from_str(RECTANGLES_VERTEX_SRC, RECTANGLES_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        RectangleRenderer {
+            coords: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            colors: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            pos: shader
+                .get_attrib::<Point2<f32>>("pos")
+                .expect("Failed to get shader attribute."),
+            color: shader
+                .get_attrib::<Point4<f32>>("color")
+                .expect("Failed to get shader attribute."),
+            invsz: shader
+                .get_uniform::<Vector2<f32>>("invsz")
+                .expect("Failed to get shader uniform."),
+            shader,
+        }
+    }
+
+    /// Adds a rectangle to be drawn during the next frame.
+    ///
+    /// The corners are given in pixels, the origin being the top-left corner of the screen.
+    pub fn draw_rectangle(&mut self, min: Point2<f32>, max: Point2<f32>, color: Point4<f32>) {
+        let top_right = Point2::new(max.x, min.y);
+        let bottom_left = Point2::new(min.x, max.y);
+
+        for coords in self.coords.data_mut().iter_mut() {
+            coords.extend_from_slice(&[min, bottom_left, top_right, top_right, bottom_left, max]);
+        }
+
+        for colors in self.colors.data_mut().iter_mut() {
+            colors.extend_from_slice(&[color; 6]);
+        }
+    }
+
+    /// Actually draws the rectangles.
+    pub fn render(&mut self, width: f32, height: f32) {
+        if self.coords.len() == 0 {
+            return;
+        }
+
+        if width > 0.0 && height > 0.0 {
+            let ctxt = Context::get();
+            self.shader.use_program();
+            self.pos.enable();
+            self.color.enable();
+
+            self.invsz.upload(&Vector2::new(2.0 / width, -2.0 / height));
+            self.pos.bind_sub_buffer(&mut self.coords, 0, 0);
+            self.color.bind_sub_buffer(&mut self.colors, 0, 0);
+
+            verify!(ctxt.enable(Context::BLEND));
+            verify!(ctxt.blend_func_separate(
+                Context::SRC_ALPHA,
+                Context::ONE_MINUS_SRC_ALPHA,
+                Context::ONE,
+                Context::ONE_MINUS_SRC_ALPHA,
+            ));
+            verify!(ctxt.disable(Context::DEPTH_TEST));
+            verify!(ctxt.disable(Context::CULL_FACE));
+            let _ = verify!(ctxt.polygon_mode(Context::FRONT_AND_BACK, Context::FILL));
+
+            verify!(ctxt.draw_arrays(Context::TRIANGLES, 0, self.coords.len() as i32));
+
+            verify!(ctxt.enable(Context::DEPTH_TEST));
+            verify!(ctxt.disable(Context::BLEND));
+
+            self.pos.disable();
+            self.color.disable();
+        }
+
+        for coords in self.coords.data_mut().iter_mut() {
+            coords.clear()
+        }
+
+        for colors in self.colors.data_mut().iter_mut() {
+            colors.clear()
+        }
+    }
+}
+
+/// Vertex shader used to draw screen-space rectangles.
+static RECTANGLES_VERTEX_SRC: &str = "#version 100
+    attribute vec2 pos;
+    attribute vec4 color;
+    varying   vec4 vColor;
+    uniform   vec2 invsz;
+
+    void main() {
+        gl_Position = vec4(pos.x * invsz.x - 1.0, pos.y * invsz.y + 1.0, -1.0, 1.0);
+        vColor = color;
+    }";
+
+/// Fragment shader used to draw screen-space rectangles.
+static RECTANGLES_FRAGMENT_SRC: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    varying vec4 vColor;
+
+    void main() {
+        gl_FragColor = vColor;
+    }";
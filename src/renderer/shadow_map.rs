@@ -0,0 +1,196 @@
+//! Shadow mapping for the single light of the scene.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use na::{Isometry3, Matrix3, Matrix4, Perspective3, Point3, Vector3};
+
+use crate::context::{Context, Texture};
+use crate::resource::vertex_index::VERTEX_INDEX_TYPE;
+use crate::resource::{Effect, FramebufferManager, RenderTarget, ShaderAttribute, ShaderUniform};
+use crate::scene::SceneNodeData;
+use crate::verify;
+
+/// The width and height of the shadow map, in pixels.
+const SHADOW_MAP_SIZE: usize = 2048;
+/// The distance to the light beyond which objects neither cast nor receive shadows.
+const SHADOW_FAR: f32 = 1000.0;
+/// The distance to the light below which objects do not cast shadows.
+const SHADOW_NEAR: f32 = 0.1;
+
+/// The shadow map rendered for the current frame, sampled by the default object material.
+#[derive(Clone)]
+pub(crate) struct ShadowMap {
+    /// The distances to the light, packed into RGBA colors.
+    pub texture: Rc<Texture>,
+    /// The projection-view matrix of the light.
+    pub view_proj: Matrix4<f32>,
+    /// The position of the light.
+    pub light_position: Point3<f32>,
+    /// The distance the distances stored in the shadow map are normalized by.
+    pub far: f32,
+    /// The size of a texel of the shadow map, in texture coordinates.
+    pub texel_size: f32,
+}
+
+thread_local!(static SHADOW_MAP: RefCell<Option<ShadowMap>> = RefCell::new(None));
+
+/// The shadow map to sample, if shadows are enabled.
+pub(crate) fn shadow_map() -> Option<ShadowMap> {
+    SHADOW_MAP.with(|s| s.borrow().clone())
+}
+
+/// Renders the distances from the light to the objects of the scene into a shadow map.
+pub(crate) struct ShadowMapRenderer {
+    target: RenderTarget,
+    shader: Effect,
+    pos: ShaderAttribute<Point3<f32>>,
+    view_proj: ShaderUniform<Matrix4<f32>>,
+    transform: ShaderUniform<Matrix4<f32>>,
+    scale: ShaderUniform<Matrix3<f32>>,
+    light_position: ShaderUniform<Point3<f32>>,
+    far: ShaderUniform<f32>,
+}
+
+impl ShadowMapRenderer {
+    /// Creates the shadow map buffers.
+    pub fn new() -> ShadowMapRenderer {
+        let mut shader = Effect::new_from_str(SHADOW_VERTEX_SRC, SHADOW_FRAGMENT_SRC);
+        shader.use_program();
+
+        ShadowMapRenderer {
+            target: FramebufferManager::new_render_target(SHADOW_MAP_SIZE, SHADOW_MAP_SIZE, false),
+            pos: shader.get_attrib("position").unwrap(),
+            view_proj: shader.get_uniform("view_proj").unwrap(),
+            transform: shader.get_uniform("transform").unwrap(),
+            scale: shader.get_uniform("scale").unwrap(),
+            light_position: shader.get_uniform("light_position").unwrap(),
+            far: shader.get_uniform("far").unwrap(),
+            shader,
+        }
+    }
+
+    /// Renders the shadow map of `scene` seen from a light at `light_position` looking toward
+    /// the origin, and makes it available to the default object material.
+    ///
+    /// This changes the selected render target and the viewport.
+    pub fn render(
+        &mut self,
+        framebuffer_manager: &mut FramebufferManager,
+        scene: &mut SceneNodeData,
+        light_position: &Point3<f32>,
+    ) {
+        let ctxt = Context::get();
+
+        let mut dir = -light_position.coords;
+
+        if dir.norm_squared() < 1.0e-6 {
+            dir = -Vector3::y();
+        }
+
+        let up = if dir.cross(&Vector3::y()).norm_squared() < 1.0e-6 * dir.norm_squared() {
+            Vector3::z()
+        } else {
+            Vector3::y()
+        };
+        let view = Isometry3::look_at_rh(light_position, &(light_position + dir), &up);
+        let proj = Perspective3::new(1.0, std::f32::consts::FRAC_PI_2, SHADOW_NEAR, SHADOW_FAR);
+        let view_proj = proj.to_homogeneous() * view.to_homogeneous();
+
+        framebuffer_manager.select(&self.target);
+
+        let size = SHADOW_MAP_SIZE as i32;
+        verify!(ctxt.viewport(0, 0, size, size));
+        verify!(ctxt.scissor(0, 0, size, size));
+        // The farthest possible distance.
+        verify!(ctxt.clear_color(1.0, 1.0, 1.0, 1.0));
+        verify!(ctxt.clear(Context::COLOR_BUFFER_BIT | Context::DEPTH_BUFFER_BIT));
+        // Single-sided objects like planes cast shadows too.
+        verify!(ctxt.disable(Context::CULL_FACE));
+
+        self.shader.use_program();
+        self.pos.enable();
+        self.view_proj.upload(&view_proj);
+        self.light_position.upload(light_position);
+        self.far.upload(&SHADOW_FAR);
+
+        let (pos, transform_uniform, scale_uniform) =
+            (&mut self.pos, &mut self.transform, &mut self.scale);
+
+        scene.apply_to_visible_objects_with_transform(
+            &na::one(),
+            &Vector3::from_element(1.0),
+            &mut |object, transform, scale| {
+                if !object.data().surface_rendering_active() {
+                    return;
+                }
+
+                let formated_scale = Matrix3::from_diagonal(scale);
+                transform_uniform.upload(&transform.to_homogeneous());
+                scale_uniform.upload(&formated_scale);
+
+                let mut mesh = object.mesh().borrow_mut();
+                mesh.bind_coords(pos);
+                mesh.bind_faces();
+                verify!(ctxt.draw_elements(
+                    Context::TRIANGLES,
+                    mesh.num_pts() as i32,
+                    VERTEX_INDEX_TYPE,
+                    0
+                ));
+                mesh.unbind();
+            },
+        );
+
+        self.pos.disable();
+        verify!(ctxt.enable(Context::CULL_FACE));
+
+        let shadow_map = ShadowMap {
+            texture: self.target.texture().unwrap(),
+            view_proj,
+            light_position: *light_position,
+            far: SHADOW_FAR,
+            texel_size: 1.0 / SHADOW_MAP_SIZE as f32,
+        };
+
+        SHADOW_MAP.with(|s| *s.borrow_mut() = Some(shadow_map));
+    }
+
+    /// Stops sampling the shadow map in the default object material.
+    pub fn disable() {
+        SHADOW_MAP.with(|s| *s.borrow_mut() = None);
+    }
+}
+
+static SHADOW_VERTEX_SRC: &str = "#version 100
+attribute vec3 position;
+uniform mat4 view_proj;
+uniform mat4 transform;
+uniform mat3 scale;
+uniform vec3 light_position;
+uniform float far;
+varying float dist;
+
+void main() {
+    vec4 world_pos = transform * vec4(scale * position, 1.0);
+    gl_Position = view_proj * world_pos;
+    dist = length(world_pos.xyz / world_pos.w - light_position) / far;
+}
+";
+
+static SHADOW_FRAGMENT_SRC: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+varying float dist;
+
+void main() {
+    // Pack the distance into the four 8-bits channels.
+    vec4 packed = fract(clamp(dist, 0.0, 0.999999) * vec4(1.0, 255.0, 65025.0, 16581375.0));
+    packed -= packed.yzww * vec4(1.0 / 255.0, 1.0 / 255.0, 1.0 / 255.0, 0.0);
+    gl_FragColor = packed;
+}
+";
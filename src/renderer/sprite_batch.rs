@@ -0,0 +1,254 @@
+//! A batched 2D sprite renderer.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::context::Context;
+use crate::planar_camera::PlanarCamera;
+use crate::resource::{
+    AllocationType, BufferType, Effect, GPUVec, ShaderAttribute, ShaderUniform, Texture,
+};
+use crate::verify;
+use na::{Matrix3, Point2, Point3, Vector2};
+
+struct SpriteBatchData {
+    texture: Rc<Texture>,
+    // Interleaved positions and texture coordinates.
+    coords: GPUVec<Point2<f32>>,
+    colors: GPUVec<Point3<f32>>,
+    len: usize,
+}
+
+/// A set of textured 2D quads sharing the same texture, drawn with a single draw call.
+///
+/// Sprites are persistent: they are drawn at each frame until `clear` is called. Cloning a
+/// `SpriteBatch` yields another handle to the same batch.
+#[derive(Clone)]
+pub struct SpriteBatch {
+    data: Rc<RefCell<SpriteBatchData>>,
+}
+
+impl SpriteBatch {
+    /// Creates a new empty sprite batch using the given texture.
+    pub fn new(texture: Rc<Texture>) -> SpriteBatch {
+        let data = SpriteBatchData {
+            texture,
+            coords: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::DynamicDraw),
+            colors: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::DynamicDraw),
+            len: 0,
+        };
+
+        SpriteBatch {
+            data: Rc::new(RefCell::new(data)),
+        }
+    }
+
+    /// Adds a sprite to this batch.
+    ///
+    /// # Arguments
+    /// * `pos` - the position of the sprite center.
+    /// * `size` - the width and height of the sprite.
+    /// * `uv_rect` - the `(min, max)` texture coordinates mapped to the top-left and
+    ///   bottom-right corners of the sprite.
+    /// * `color` - the color the texture is multiplied by.
+    pub fn push(
+        &mut self,
+        pos: Point2<f32>,
+        size: Vector2<f32>,
+        uv_rect: (Point2<f32>, Point2<f32>),
+        color: Point3<f32>,
+    ) {
+        let mut data = self.data.borrow_mut();
+        let half = size / 2.0;
+        let (uv_min, uv_max) = uv_rect;
+
+        let top_left = (pos + Vector2::new(-half.x, half.y), uv_min);
+        let top_right = (pos + half, Point2::new(uv_max.x, uv_min.y));
+        let bottom_left = (pos - half, Point2::new(uv_min.x, uv_max.y));
+        let bottom_right = (pos + Vector2::new(half.x, -half.y), uv_max);
+
+        if let Some(coords) = data.coords.data_mut() {
+            for (pt, uv) in &[
+                top_left,
+                bottom_left,
+                top_right,
+                top_right,
+                bottom_left,
+                bottom_right,
+            ] {
+                coords.push(*pt);
+                coords.push(*uv);
+            }
+        }
+
+        if let Some(colors) = data.colors.data_mut() {
+            colors.extend_from_slice(&[color; 6]);
+        }
+
+        data.len += 1;
+    }
+
+    /// Removes all the sprites from this batch.
+    pub fn clear(&mut self) {
+        let mut data = self.data.borrow_mut();
+
+        if let Some(coords) = data.coords.data_mut() {
+            coords.clear()
+        }
+
+        if let Some(colors) = data.colors.data_mut() {
+            colors.clear()
+        }
+
+        data.len = 0;
+    }
+
+    /// The number of sprites in this batch.
+    pub fn len(&self) -> usize {
+        self.data.borrow().len
+    }
+
+    /// Whether this batch contains no sprite.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The texture shared by all the sprites of this batch.
+    pub fn texture(&self) -> Rc<Texture> {
+        self.data.borrow().texture.clone()
+    }
+
+    /// Sets the texture shared by all the sprites of this batch.
+    pub fn set_texture(&mut self, texture: Rc<Texture>) {
+        self.data.borrow_mut().texture = texture
+    }
+
+    /// Whether `self` and `other` are handles to the same batch.
+    pub fn is_same_as(&self, other: &SpriteBatch) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+}
+
+/// Structure which draws sprite batches.
+pub(crate) struct SpriteBatchRenderer {
+    shader: Effect,
+    pos: ShaderAttribute<Point2<f32>>,
+    uv: ShaderAttribute<Point2<f32>>,
+    color: ShaderAttribute<Point3<f32>>,
+    tex: ShaderUniform<i32>,
+    view: ShaderUniform<Matrix3<f32>>,
+    proj: ShaderUniform<Matrix3<f32>>,
+}
+
+impl SpriteBatchRenderer {
+    /// Creates a new sprite batch renderer.
+    pub fn new() -> SpriteBatchRenderer {
+        let mut shader = Effect::new_from_str(SPRITES_VERTEX_SRC, SPRITES_FRAGMENT_SRC);
+
+        shader.use_program();
+
+        SpriteBatchRenderer {
+            pos: shader
+                .get_attrib::<Point2<f32>>("position")
+                .expect("Failed to get shader attribute."),
+            uv: shader
+                .get_attrib::<Point2<f32>>("tex_coord")
+                .expect("Failed to get shader attribute."),
+            color: shader
+                .get_attrib::<Point3<f32>>("color")
+                .expect("Failed to get shader attribute."),
+            tex: shader
+                .get_uniform::<i32>("tex")
+                .expect("Failed to get shader uniform."),
+            view: shader
+                .get_uniform::<Matrix3<f32>>("view")
+                .expect("Failed to get shader uniform."),
+            proj: shader
+                .get_uniform::<Matrix3<f32>>("proj")
+                .expect("Failed to get shader uniform."),
+            shader,
+        }
+    }
+
+    /// Draws the given batches, with one draw call per batch.
+    pub fn render(&mut self, camera: &mut dyn PlanarCamera, batches: &[SpriteBatch]) {
+        if batches.iter().all(|b| b.is_empty()) {
+            return;
+        }
+
+        let ctxt = Context::get();
+        self.shader.use_program();
+        self.pos.enable();
+        self.uv.enable();
+        self.color.enable();
+
+        camera.upload(&mut self.proj, &mut self.view);
+        self.tex.upload(&0);
+
+        verify!(ctxt.enable(Context::BLEND));
+        verify!(ctxt.blend_func_separate(
+            Context::SRC_ALPHA,
+            Context::ONE_MINUS_SRC_ALPHA,
+            Context::ONE,
+            Context::ONE_MINUS_SRC_ALPHA,
+        ));
+        verify!(ctxt.disable(Context::CULL_FACE));
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+
+        for batch in batches {
+            let mut data = batch.data.borrow_mut();
+            let data = &mut *data;
+
+            if data.len == 0 {
+                continue;
+            }
+
+            verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*data.texture)));
+            self.pos.bind_sub_buffer(&mut data.coords, 1, 0);
+            self.uv.bind_sub_buffer(&mut data.coords, 1, 1);
+            self.color.bind(&mut data.colors);
+
+            verify!(ctxt.draw_arrays(Context::TRIANGLES, 0, data.len as i32 * 6));
+        }
+
+        verify!(ctxt.disable(Context::BLEND));
+        self.pos.disable();
+        self.uv.disable();
+        self.color.disable();
+    }
+}
+
+/// Vertex shader used to draw sprites.
+static SPRITES_VERTEX_SRC: &str = "#version 100
+    attribute vec2 position;
+    attribute vec2 tex_coord;
+    attribute vec3 color;
+    varying   vec2 tex_coord_v;
+    varying   vec3 color_v;
+    uniform   mat3 proj;
+    uniform   mat3 view;
+
+    void main() {
+        vec3 projected_pos = proj * view * vec3(position, 1.0);
+        projected_pos.z = 0.0;
+
+        gl_Position = vec4(projected_pos, 1.0);
+        tex_coord_v = tex_coord;
+        color_v = color;
+    }";
+
+/// Fragment shader used to draw sprites.
+static SPRITES_FRAGMENT_SRC: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+    uniform sampler2D tex;
+    varying vec2 tex_coord_v;
+    varying vec3 color_v;
+
+    void main() {
+        gl_FragColor = texture2D(tex, tex_coord_v) * vec4(color_v, 1.0);
+    }";
@@ -0,0 +1,254 @@
+//! Rendering of the transparent objects of the scene.
+
+use std::cell::Cell;
+
+use na::Vector2;
+
+use crate::camera::Camera;
+use crate::context::Context;
+use crate::light::Light;
+use crate::resource::{
+    AllocationType, BufferType, Effect, FramebufferManager, GPUVec, RenderTarget, ShaderAttribute,
+    ShaderUniform,
+};
+use crate::scene::SceneNodeData;
+use crate::verify;
+
+/// The technique used to render the transparent objects of the scene.
+///
+/// An object is transparent if its alpha, set with `SceneNode::set_alpha`, is smaller than 1.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TransparencyMode {
+    /// The transparent objects are sorted by distance to the camera and blended from the farthest
+    /// to the closest.
+    ///
+    /// Objects are sorted as a whole so intersecting or concave transparent objects may be
+    /// blended in the wrong order.
+    Sorted,
+    /// Weighted blended order-independent transparency.
+    ///
+    /// The transparent objects are accumulated in two off-screen buffers, then composited over the
+    /// opaque objects in a final pass. This does not depend on the drawing order but is only an
+    /// approximation of the correct blending. The buffers use half-float components, which
+    /// requires OpenGL 3, or OpenGL ES 3 with the `EXT_color_buffer_float` extension;
+    /// `Window::set_transparency_mode` falls back to `Sorted` otherwise. This is always the case
+    /// on the web, where the canvas uses WebGL 1. Only objects using the default material
+    /// support this mode.
+    WeightedBlended,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum OitPass {
+    None = 0,
+    Accumulation = 1,
+    Revealage = 2,
+}
+
+thread_local!(static OIT_PASS: Cell<OitPass> = Cell::new(OitPass::None));
+
+/// The order-independent transparency pass being rendered, if any.
+pub(crate) fn oit_pass() -> OitPass {
+    OIT_PASS.with(|p| p.get())
+}
+
+fn set_oit_pass(pass: OitPass) {
+    OIT_PASS.with(|p| p.set(pass))
+}
+
+/// Renders the transparent objects with weighted blended order-independent transparency.
+pub(crate) struct OitRenderer {
+    accumulation: RenderTarget,
+    revealage: RenderTarget,
+    shader: Effect,
+    v_coord: ShaderAttribute<Vector2<f32>>,
+    accumulation_texture: ShaderUniform<i32>,
+    revealage_texture: ShaderUniform<i32>,
+    size: ShaderUniform<Vector2<f32>>,
+    vertices: GPUVec<Vector2<f32>>,
+    width: f32,
+    height: f32,
+}
+
+impl OitRenderer {
+    /// Creates the off-screen buffers of the given size.
+    ///
+    /// The context must support half-float render targets.
+    pub fn new(width: f32, height: f32) -> OitRenderer {
+        let vertices = vec![
+            Vector2::new(-1.0, -1.0),
+            Vector2::new(1.0, -1.0),
+            Vector2::new(-1.0, 1.0),
+            Vector2::new(1.0, 1.0),
+        ];
+        let mut vertices = GPUVec::new(vertices, BufferType::Array, AllocationType::StaticDraw);
+        vertices.load_to_gpu();
+        vertices.unload_from_ram();
+
+        let mut shader = Effect::new_from_str(COMPOSITE_VERTEX_SRC, COMPOSITE_FRAGMENT_SRC);
+        shader.use_program();
+
+        OitRenderer {
+            // With 8-bit buffers, faint fragments would be rounded to zero.
+            accumulation: FramebufferManager::new_render_target_with_format(
+                width as usize,
+                height as usize,
+                false,
+                Context::RGBA16F,
+                Context::HALF_FLOAT,
+            ),
+            revealage: FramebufferManager::new_render_target_with_format(
+                width as usize,
+                height as usize,
+                false,
+                Context::RGBA16F,
+                Context::HALF_FLOAT,
+            ),
+            v_coord: shader.get_attrib("v_coord").unwrap(),
+            accumulation_texture: shader.get_uniform("accumulation").unwrap(),
+            revealage_texture: shader.get_uniform("revealage").unwrap(),
+            size: shader.get_uniform("size").unwrap(),
+            shader,
+            vertices,
+            width,
+            height,
+        }
+    }
+
+    /// Resizes the off-screen buffers.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.accumulation.resize(width, height);
+        self.revealage.resize(width, height);
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Renders the transparent objects of `scene` and composites them onto `target`.
+    ///
+    /// The opaque objects must already have been rendered onto `target`: its depth buffer is
+    /// reused so the transparent objects are occluded by the opaque ones.
+    pub fn render(
+        &mut self,
+        framebuffer_manager: &mut FramebufferManager,
+        target: &RenderTarget,
+        scene: &mut SceneNodeData,
+        pass: usize,
+        camera: &mut dyn Camera,
+        light: &Light,
+    ) {
+        let ctxt = Context::get();
+
+        // The depth buffer of the screen cannot be attached to our framebuffer so it is copied
+        // into the one of the accumulation buffer instead.
+        let depth = match *target {
+            RenderTarget::Screen => {
+                framebuffer_manager.blit_screen_depth(&self.accumulation);
+                &self.accumulation
+            }
+            RenderTarget::Offscreen(_) => target,
+        };
+
+        let passes = [
+            (&self.accumulation, 0.0, OitPass::Accumulation),
+            (&self.revealage, 1.0, OitPass::Revealage),
+        ];
+
+        for (buffer, clear, oit_pass) in passes.iter() {
+            framebuffer_manager.select_with_depth(buffer, depth);
+            verify!(ctxt.clear_color(*clear, *clear, *clear, *clear));
+            verify!(ctxt.clear(Context::COLOR_BUFFER_BIT));
+
+            verify!(ctxt.enable(Context::BLEND));
+            verify!(ctxt.depth_mask(false));
+
+            if *oit_pass == OitPass::Accumulation {
+                verify!(ctxt.blend_func_separate(
+                    Context::ONE,
+                    Context::ONE,
+                    Context::ONE,
+                    Context::ONE
+                ));
+            } else {
+                verify!(ctxt.blend_func_separate(
+                    Context::ZERO,
+                    Context::ONE_MINUS_SRC_COLOR,
+                    Context::ZERO,
+                    Context::ONE_MINUS_SRC_COLOR
+                ));
+            }
+
+            set_oit_pass(*oit_pass);
+            scene.render_transparent(pass, camera, light, false);
+            set_oit_pass(OitPass::None);
+
+            verify!(ctxt.depth_mask(true));
+            verify!(ctxt.disable(Context::BLEND));
+        }
+
+        /*
+         * Composite the transparent objects over the opaque ones.
+         */
+        framebuffer_manager.select(target);
+
+        self.shader.use_program();
+        self.v_coord.enable();
+
+        verify!(ctxt.active_texture(Context::TEXTURE1));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, self.revealage.texture_id()));
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, self.accumulation.texture_id()));
+
+        self.accumulation_texture.upload(&0);
+        self.revealage_texture.upload(&1);
+        self.size.upload(&Vector2::new(self.width, self.height));
+        self.v_coord.bind(&mut self.vertices);
+
+        verify!(ctxt.disable(Context::DEPTH_TEST));
+        verify!(ctxt.enable(Context::BLEND));
+        verify!(ctxt.blend_func_separate(
+            Context::SRC_ALPHA,
+            Context::ONE_MINUS_SRC_ALPHA,
+            Context::ONE,
+            Context::ONE_MINUS_SRC_ALPHA
+        ));
+
+        verify!(ctxt.draw_arrays(Context::TRIANGLE_STRIP, 0, 4));
+
+        verify!(ctxt.disable(Context::BLEND));
+        verify!(ctxt.enable(Context::DEPTH_TEST));
+
+        self.v_coord.disable();
+    }
+}
+
+static COMPOSITE_VERTEX_SRC: &str = "#version 100
+attribute vec2 v_coord;
+
+void main() {
+    gl_Position = vec4(v_coord, 0.0, 1.0);
+}
+";
+
+static COMPOSITE_FRAGMENT_SRC: &str = "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+   precision highp float;
+#else
+   precision mediump float;
+#endif
+
+uniform sampler2D accumulation;
+uniform sampler2D revealage;
+uniform vec2 size;
+
+void main() {
+    // Use the fragment coordinates so the camera viewport is taken into account.
+    vec2 uv = gl_FragCoord.xy / size;
+    float reveal = texture2D(revealage, uv).r;
+
+    if (reveal >= 0.999) {
+        discard;
+    }
+
+    vec4 accum = texture2D(accumulation, uv);
+    gl_FragColor = vec4(accum.rgb / max(accum.a, 1.0e-5), 1.0 - reveal);
+}
+";
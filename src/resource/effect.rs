@@ -51,6 +51,9 @@ impl Effect {
     }
 
     /// Gets a uniform variable from the shader program.
+    ///
+    /// Returns `None` if the program has no active uniform named `name`. Use
+    /// `Effect::uniforms` to list the available names.
     pub fn get_uniform<T: GLPrimitive>(&self, name: &str) -> Option<ShaderUniform<T>> {
         let ctxt = Context::get();
         let location = ctxt.get_uniform_location(&self.program, name);
@@ -79,6 +82,21 @@ impl Effect {
         None
     }
 
+    /// The names of the active uniform variables of the shader program.
+    ///
+    /// Uniforms declared but unused by the shaders are optimized away by the driver and are not
+    /// listed. Arrays are listed by the name of their first element, e.g., `"lights[0]"`.
+    pub fn uniforms(&self) -> Vec<String> {
+        verify!(Context::get().get_active_uniform_names(&self.program))
+    }
+
+    /// The names of the active attributes of the shader program.
+    ///
+    /// Attributes declared but unused by the vertex shader are not listed.
+    pub fn attributes(&self) -> Vec<String> {
+        verify!(Context::get().get_active_attribute_names(&self.program))
+    }
+
     /// Make this program active.
     pub fn use_program(&mut self) {
         verify!(Context::get().use_program(Some(&self.program)));
@@ -0,0 +1,106 @@
+//! Cube maps used for environment reflections.
+
+use image::{self, DynamicImage, GenericImageView};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::{
+    context::{Context, Texture},
+    verify,
+};
+
+/// A cube map surrounding the scene, reflected by the objects it is attached to.
+///
+/// See `SceneNode::set_environment_map` for details.
+pub struct EnvironmentMap {
+    texture: Texture,
+    size: u32,
+}
+
+impl EnvironmentMap {
+    /// Creates an environment map from the six faces of a cube.
+    ///
+    /// The faces are given in the order `+X`, `-X`, `+Y`, `-Y`, `+Z`, `-Z`, following the
+    /// OpenGL cube map conventions. They must all be square and have the same size.
+    pub fn new(faces: [DynamicImage; 6]) -> Rc<EnvironmentMap> {
+        let size = faces[0].width();
+
+        for face in &faces {
+            assert!(
+                face.dimensions() == (size, size),
+                "The faces of an environment map must be squares of the same size."
+            );
+        }
+
+        let ctxt = Context::get();
+        let texture = verify!(ctxt
+            .create_texture()
+            .expect("Could not create environment map."));
+
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_CUBE_MAP, Some(&texture)));
+
+        for (i, face) in faces.iter().enumerate() {
+            let face = face.to_rgba8();
+
+            verify!(ctxt.tex_image2d(
+                Context::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
+                0,
+                Context::RGBA as i32,
+                size as i32,
+                size as i32,
+                0,
+                Context::RGBA,
+                Some(&face.as_raw()[..])
+            ));
+        }
+
+        for param in &[Context::TEXTURE_WRAP_S, Context::TEXTURE_WRAP_T] {
+            verify!(ctxt.tex_parameteri(
+                Context::TEXTURE_CUBE_MAP,
+                *param,
+                Context::CLAMP_TO_EDGE as i32
+            ));
+        }
+
+        for param in &[Context::TEXTURE_MIN_FILTER, Context::TEXTURE_MAG_FILTER] {
+            verify!(ctxt.tex_parameteri(Context::TEXTURE_CUBE_MAP, *param, Context::LINEAR as i32));
+        }
+
+        verify!(ctxt.bind_texture(Context::TEXTURE_CUBE_MAP, None));
+
+        Rc::new(EnvironmentMap { texture, size })
+    }
+
+    /// Creates an environment map from six image files.
+    ///
+    /// The faces are given in the order `+X`, `-X`, `+Y`, `-Y`, `+Z`, `-Z`.
+    pub fn from_files(paths: [&Path; 6]) -> Rc<EnvironmentMap> {
+        let open = |path: &Path| {
+            image::open(path).unwrap_or_else(|e| {
+                panic!("Unable to load environment map face {:?}: {:?}", path, e)
+            })
+        };
+
+        EnvironmentMap::new([
+            open(paths[0]),
+            open(paths[1]),
+            open(paths[2]),
+            open(paths[3]),
+            open(paths[4]),
+            open(paths[5]),
+        ])
+    }
+
+    /// The cube map texture of this environment map.
+    #[inline]
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// The width and height of each face of this environment map, in pixels.
+    #[inline]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
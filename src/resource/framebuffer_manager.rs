@@ -1,10 +1,11 @@
 //! Resource manager to allocate and switch between framebuffers.
 
 use crate::{
-    context::{Context, Framebuffer, Renderbuffer, Texture},
+    context::{Context, Framebuffer, GLenum, Renderbuffer, Texture},
     verify,
 };
 use either::Either;
+use std::rc::Rc;
 
 /// The target to every rendering call.
 pub enum RenderTarget {
@@ -16,8 +17,11 @@ pub enum RenderTarget {
 
 /// OpenGL identifiers to an off-screen buffer.
 pub struct OffscreenBuffers {
-    texture: Texture,
+    texture: Rc<Texture>,
     depth: Either<Texture, Renderbuffer>,
+    size: (usize, usize),
+    // The internal format and the component type of the color texture.
+    color_format: (GLenum, GLenum),
 }
 
 impl RenderTarget {
@@ -27,7 +31,28 @@ impl RenderTarget {
     pub fn texture_id(&self) -> Option<&Texture> {
         match *self {
             RenderTarget::Screen => None,
-            RenderTarget::Offscreen(ref o) => Some(&o.texture),
+            RenderTarget::Offscreen(ref o) => Some(&*o.texture),
+        }
+    }
+
+    /// Returns the off-screen texture buffer, e.g., to use it as the texture of an object with
+    /// `SceneNode::set_texture`.
+    ///
+    /// Returns `None` if the render target is the screen.
+    pub fn texture(&self) -> Option<Rc<Texture>> {
+        match *self {
+            RenderTarget::Screen => None,
+            RenderTarget::Offscreen(ref o) => Some(o.texture.clone()),
+        }
+    }
+
+    /// Returns the width and height of the off-screen buffers, in pixels.
+    ///
+    /// Returns `None` if the render target is the screen.
+    pub fn size(&self) -> Option<(usize, usize)> {
+        match *self {
+            RenderTarget::Screen => None,
+            RenderTarget::Offscreen(ref o) => Some(o.size),
         }
     }
 
@@ -50,17 +75,20 @@ impl RenderTarget {
             RenderTarget::Screen => {
                 verify!(ctxt.viewport(0, 0, w as i32, h as i32));
             }
-            RenderTarget::Offscreen(ref o) => {
+            RenderTarget::Offscreen(ref mut o) => {
+                o.size = (w as usize, h as usize);
+
                 // Update the fbo
-                verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&o.texture)));
-                verify!(ctxt.tex_image2d(
+                verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&*o.texture)));
+                verify!(ctxt.tex_image2d_typed(
                     Context::TEXTURE_2D,
                     0,
-                    Context::RGBA as i32,
+                    o.color_format.0 as i32,
                     w as i32,
                     h as i32,
                     0,
                     Context::RGBA,
+                    o.color_format.1,
                     None
                 ));
                 verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
@@ -68,22 +96,13 @@ impl RenderTarget {
                 match &o.depth {
                     Either::Left(texture) => {
                         verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(texture)));
-                        verify!(ctxt.tex_image2d(
-                            Context::TEXTURE_2D,
-                            0,
-                            Context::DEPTH_COMPONENT as i32,
-                            w as i32,
-                            h as i32,
-                            0,
-                            Context::DEPTH_COMPONENT,
-                            None
-                        ));
+                        depth_stencil_tex_image2d(&ctxt, w as i32, h as i32);
                         verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
                     }
                     Either::Right(renderbuffer) => {
                         verify!(ctxt.bind_renderbuffer(Some(renderbuffer)));
                         verify!(ctxt.renderbuffer_storage(
-                            Context::DEPTH_COMPONENT16,
+                            depth_stencil_renderbuffer_format(),
                             w as i32,
                             h as i32
                         ));
@@ -95,6 +114,39 @@ impl RenderTarget {
     }
 }
 
+// The off-screen depth buffers have a stencil buffer too, so the object outlines are drawn
+// when post-processing effects are used.
+fn depth_stencil_tex_image2d(ctxt: &Context, width: i32, height: i32) {
+    // With `WEBGL_depth_texture`, WebGL 1 only has an unsized depth-stencil format. Its
+    // `UNSIGNED_INT_24_8_WEBGL` component type has the same value as `UNSIGNED_INT_24_8`.
+    let internal_format = if cfg!(target_arch = "wasm32") {
+        Context::DEPTH_STENCIL
+    } else {
+        Context::DEPTH24_STENCIL8
+    };
+
+    verify!(ctxt.tex_image2d_typed(
+        Context::TEXTURE_2D,
+        0,
+        internal_format as i32,
+        width,
+        height,
+        0,
+        Context::DEPTH_STENCIL,
+        Context::UNSIGNED_INT_24_8,
+        None
+    ));
+}
+
+fn depth_stencil_renderbuffer_format() -> u32 {
+    // WebGL 1 only has an unsized depth-stencil format.
+    if cfg!(target_arch = "wasm32") {
+        Context::DEPTH_STENCIL
+    } else {
+        Context::DEPTH24_STENCIL8
+    }
+}
+
 /// A framebuffer manager. It is a simple to to switch between an off-screen framebuffer and the
 /// default (window) framebuffer.
 pub struct FramebufferManager {
@@ -122,11 +174,34 @@ impl FramebufferManager {
     }
 
     /// Creates a new render target. A render target is the combination of a color buffer and a
-    /// depth buffer.
+    /// depth buffer. The depth buffer also has an 8-bit stencil buffer.
+    ///
+    /// If `create_depth_texture` is `true`, the depth buffer is a texture, except on WebGL without
+    /// the `WEBGL_depth_texture` extension where a renderbuffer is used instead.
     pub fn new_render_target(
         width: usize,
         height: usize,
         create_depth_texture: bool,
+    ) -> RenderTarget {
+        Self::new_render_target_with_format(
+            width,
+            height,
+            create_depth_texture,
+            Context::RGBA,
+            Context::UNSIGNED_BYTE,
+        )
+    }
+
+    /// Creates a new render target which color buffer has the given internal format and
+    /// component type, e.g., `Context::RGBA16F` and `Context::HALF_FLOAT`.
+    ///
+    /// The caller must check that the context can render to this format.
+    pub(crate) fn new_render_target_with_format(
+        width: usize,
+        height: usize,
+        create_depth_texture: bool,
+        internal_format: GLenum,
+        ty: GLenum,
     ) -> RenderTarget {
         let ctxt = Context::get();
 
@@ -156,20 +231,24 @@ impl FramebufferManager {
             Context::TEXTURE_WRAP_T,
             Context::CLAMP_TO_EDGE as i32
         ));
-        verify!(ctxt.tex_image2d(
+        verify!(ctxt.tex_image2d_typed(
             Context::TEXTURE_2D,
             0,
-            Context::RGBA as i32,
+            internal_format as i32,
             width as i32,
             height as i32,
             0,
             Context::RGBA,
+            ty,
             None
         ));
         verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
 
         /* Depth buffer */
-        if create_depth_texture && cfg!(not(any(target_arch = "wasm32", target_arch = "asmjs"))) {
+        if create_depth_texture
+            && cfg!(not(target_arch = "asmjs"))
+            && ctxt.supports_depth_stencil_textures()
+        {
             verify!(ctxt.active_texture(Context::TEXTURE1));
             let fbo_depth = verify!(ctxt.create_texture().expect("Failed to create a texture."));
             verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&fbo_depth)));
@@ -193,21 +272,14 @@ impl FramebufferManager {
                 Context::TEXTURE_WRAP_T,
                 Context::CLAMP_TO_EDGE as i32
             ));
-            verify!(ctxt.tex_image2di(
-                Context::TEXTURE_2D,
-                0,
-                Context::DEPTH_COMPONENT as i32,
-                width as i32,
-                height as i32,
-                0,
-                Context::DEPTH_COMPONENT,
-                None
-            ));
+            depth_stencil_tex_image2d(&ctxt, width as i32, height as i32);
             verify!(ctxt.bind_texture(Context::TEXTURE_2D, None));
 
             RenderTarget::Offscreen(OffscreenBuffers {
-                texture: fbo_texture,
+                texture: Rc::new(fbo_texture),
                 depth: Either::Left(fbo_depth),
+                size: (width, height),
+                color_format: (internal_format, ty),
             })
         } else {
             // Create a renderbuffer instead of the texture for the depth.
@@ -215,15 +287,17 @@ impl FramebufferManager {
                 verify!(ctxt.create_renderbuffer()).expect("Failed to create a renderbuffer.");
             verify!(ctxt.bind_renderbuffer(Some(&renderbuffer)));
             verify!(ctxt.renderbuffer_storage(
-                Context::DEPTH_COMPONENT16,
+                depth_stencil_renderbuffer_format(),
                 width as i32,
                 height as i32
             ));
             verify!(ctxt.bind_renderbuffer(None));
 
             RenderTarget::Offscreen(OffscreenBuffers {
-                texture: fbo_texture,
+                texture: Rc::new(fbo_texture),
                 depth: Either::Right(renderbuffer),
+                size: (width, height),
+                color_format: (internal_format, ty),
             })
         }
     }
@@ -239,34 +313,74 @@ impl FramebufferManager {
             RenderTarget::Screen => {
                 self.select_onscreen();
             }
-            RenderTarget::Offscreen(ref o) => {
-                let ctxt = Context::get();
-                self.select_fbo();
+            RenderTarget::Offscreen(ref o) => self.select_buffers(o, o),
+        }
+    }
+
+    /// Selects the color buffer of `color` together with the depth-stencil buffer of `depth`.
+    ///
+    /// Both render targets must have the same size. If either of them is the screen, this is the
+    /// same as `self.select(color)`.
+    pub(crate) fn select_with_depth(&mut self, color: &RenderTarget, depth: &RenderTarget) {
+        match (color, depth) {
+            (RenderTarget::Offscreen(c), RenderTarget::Offscreen(d)) => self.select_buffers(c, d),
+            _ => self.select(color),
+        }
+    }
+
+    /// Copies the depth buffer of the screen into the depth-stencil buffer of `target`.
+    ///
+    /// `target` must be off-screen and have the same size as the screen. This requires
+    /// OpenGL 3 or OpenGL ES 3.
+    pub(crate) fn blit_screen_depth(&mut self, target: &RenderTarget) {
+        if let RenderTarget::Offscreen(ref o) = *target {
+            let ctxt = Context::get();
+            let (w, h) = (o.size.0 as i32, o.size.1 as i32);
 
-                // FIXME: don't switch if the current texture is
-                // already o.texture ?
+            self.select(target);
+            verify!(ctxt.bind_framebuffer(Context::READ_FRAMEBUFFER, None));
+            verify!(ctxt.blit_framebuffer(
+                0,
+                0,
+                w,
+                h,
+                0,
+                0,
+                w,
+                h,
+                Context::DEPTH_BUFFER_BIT,
+                Context::NEAREST
+            ));
+            verify!(ctxt.bind_framebuffer(Context::READ_FRAMEBUFFER, Some(&self.fbo)));
+        }
+    }
+
+    fn select_buffers(&mut self, color: &OffscreenBuffers, depth: &OffscreenBuffers) {
+        let ctxt = Context::get();
+        self.select_fbo();
+
+        // FIXME: don't switch if the current texture is
+        // already color.texture ?
+        verify!(ctxt.framebuffer_texture2d(
+            Context::FRAMEBUFFER,
+            Context::COLOR_ATTACHMENT0,
+            Context::TEXTURE_2D,
+            Some(&*color.texture),
+            0
+        ));
+
+        match &depth.depth {
+            Either::Left(texture) => {
                 verify!(ctxt.framebuffer_texture2d(
                     Context::FRAMEBUFFER,
-                    Context::COLOR_ATTACHMENT0,
+                    Context::DEPTH_STENCIL_ATTACHMENT,
                     Context::TEXTURE_2D,
-                    Some(&o.texture),
+                    Some(texture),
                     0
                 ));
-
-                match &o.depth {
-                    Either::Left(texture) => {
-                        verify!(ctxt.framebuffer_texture2d(
-                            Context::FRAMEBUFFER,
-                            Context::DEPTH_ATTACHMENT,
-                            Context::TEXTURE_2D,
-                            Some(texture),
-                            0
-                        ));
-                    }
-                    Either::Right(renderbuffer) => verify!(ctxt
-                        .framebuffer_renderbuffer(Context::DEPTH_ATTACHMENT, Some(renderbuffer))),
-                }
             }
+            Either::Right(renderbuffer) => verify!(ctxt
+                .framebuffer_renderbuffer(Context::DEPTH_STENCIL_ATTACHMENT, Some(renderbuffer))),
         }
     }
 
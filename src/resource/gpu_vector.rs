@@ -167,6 +167,39 @@ impl<T: GLPrimitive> GPUVec<T> {
 }
 
 impl<T: Clone + GLPrimitive> GPUVec<T> {
+    /// Replaces the elements of this vector starting at index `start` by `data`.
+    ///
+    /// Both the RAM and GPU copies are updated, if present. Unlike modifying the vector with
+    /// `data_mut`, only the modified range of the GPU buffer is uploaded, which is much faster
+    /// for large vectors modified partially at each frame. The buffer is then reallocated with
+    /// the `DynamicDraw` allocation type if it ever needs to be.
+    ///
+    /// # Failure
+    /// Fails if the range does not fit in this vector.
+    pub fn update_range(&mut self, start: usize, data: &[T]) {
+        assert!(
+            start + data.len() <= self.len(),
+            "The updated range does not fit in the vector."
+        );
+
+        if let Some(ref mut d) = self.data {
+            d[start..start + data.len()].clone_from_slice(data);
+        }
+
+        self.alloc_type = AllocationType::DynamicDraw;
+
+        // A stale GPU buffer is fully re-uploaded by the next `load_to_gpu` anyway.
+        if !self.trash {
+            if let Some((_, ref buffer)) = self.buffer {
+                let ctxt = Context::get();
+                let offset = start * std::mem::size_of::<T>();
+
+                verify!(ctxt.bind_buffer(self.buf_type.to_gl(), Some(buffer)));
+                verify!(ctxt.buffer_sub_data(self.buf_type.to_gl(), offset as u32, data));
+            }
+        }
+    }
+
     /// Returns this vector as an owned vector if it is available on RAM.
     ///
     /// If it has been uploaded to the GPU, and unloaded from the RAM, call `load_to_ram` first to
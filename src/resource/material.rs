@@ -5,7 +5,7 @@ use crate::light::Light;
 use crate::planar_camera::PlanarCamera;
 use crate::resource::{Mesh, PlanarMesh};
 use crate::scene::{ObjectData, PlanarObjectData};
-use na::{Isometry2, Isometry3, Vector2, Vector3};
+use na::{Isometry2, Isometry3, Point3, Vector2, Vector3};
 
 /// Trait implemented by materials.
 pub trait Material {
@@ -21,6 +21,11 @@ pub trait Material {
         data: &ObjectData,
         mesh: &mut Mesh,
     );
+
+    /// Sets the color of the ambient light lighting every object drawn by this material.
+    ///
+    /// Materials that do not support ambient lighting can ignore this.
+    fn set_ambient_light(&mut self, _color: &Point3<f32>) {}
 }
 
 /// A material for 2D objects.
@@ -1,6 +1,8 @@
 //! A resource manager to load materials.
 
-use crate::builtin::{NormalsMaterial, ObjectMaterial, UvsMaterial};
+use crate::builtin::{
+    NormalsMaterial, ObjectMaterial, PointCloudMaterial, UvsMaterial, WireframeMaterial,
+};
 use crate::resource::Material;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -11,6 +13,9 @@ use std::rc::Rc;
 /// Upon construction, it contains:
 /// * the `object` material, used as the default to render objects.
 /// * the `normals` material, used do display an object normals.
+/// * the `uvs` material, used do display an object texture coordinates.
+/// * the `point_cloud` material, used to display the vertices of an object as colored points.
+/// * the `wireframe` material, used to display the explicit edges of an object as lines.
 ///
 /// It keeps a cache of already-loaded materials. Note that this is only a cache, nothing more.
 /// Thus, its usage is not required to load materials.
@@ -40,6 +45,16 @@ impl MaterialManager {
         ));
         let _ = materials.insert("uvs".to_string(), um.clone());
 
+        let pcm = Rc::new(RefCell::new(
+            Box::new(PointCloudMaterial::new()) as Box<dyn Material + 'static>
+        ));
+        let _ = materials.insert("point_cloud".to_string(), pcm.clone());
+
+        let wm = Rc::new(RefCell::new(
+            Box::new(WireframeMaterial::new()) as Box<dyn Material + 'static>
+        ));
+        let _ = materials.insert("wireframe".to_string(), wm.clone());
+
         MaterialManager {
             default_material: om,
             materials,
@@ -1,5 +1,9 @@
 //! Data structure of a scene node geometry.
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Result as IoResult, Write};
 use std::iter;
+use std::path::Path;
 use std::sync::{Arc, RwLock};
 
 use crate::resource::gpu_vector::{AllocationType, BufferType, GPUVec};
@@ -9,6 +13,9 @@ use na::{self, Point2, Point3, Vector3};
 use ncollide3d::procedural::{IndexBuffer, TriMesh};
 use num::Zero;
 
+const MESH_FILE_MAGIC: &[u8; 4] = b"KMSH";
+const MESH_FILE_VERSION: u32 = 1;
+
 /// Aggregation of vertices, indices, normals and texture coordinates.
 ///
 /// It also contains the GPU location of those buffers.
@@ -18,6 +25,7 @@ pub struct Mesh {
     normals: Arc<RwLock<GPUVec<Vector3<f32>>>>,
     uvs: Arc<RwLock<GPUVec<Point2<f32>>>>,
     edges: Option<Arc<RwLock<GPUVec<Point2<VertexIndex>>>>>,
+    colors: Option<Arc<RwLock<GPUVec<Point3<f32>>>>>,
 }
 
 impl Mesh {
@@ -185,6 +193,7 @@ impl Mesh {
             normals,
             uvs,
             edges: None,
+            colors: None,
         }
     }
 
@@ -203,6 +212,15 @@ impl Mesh {
         uvs.bind(&mut *self.uvs.write().unwrap());
     }
 
+    /// Binds this mesh vertex colors buffer to a vertex attribute.
+    ///
+    /// Does nothing if this mesh has no vertex colors.
+    pub fn bind_colors(&mut self, colors: &mut ShaderAttribute<Point3<f32>>) {
+        if let Some(cs) = &self.colors {
+            colors.bind(&mut *cs.write().unwrap());
+        }
+    }
+
     /// Binds this mesh index buffer to a vertex attribute.
     pub fn bind_faces(&mut self) {
         self.faces.write().unwrap().bind();
@@ -244,6 +262,10 @@ impl Mesh {
         self.normals.write().unwrap().unbind();
         self.uvs.write().unwrap().unbind();
         self.faces.write().unwrap().unbind();
+
+        if let Some(colors) = &self.colors {
+            colors.write().unwrap().unbind();
+        }
     }
 
     /// Number of points needed to draw this mesh.
@@ -251,6 +273,16 @@ impl Mesh {
         self.faces.read().unwrap().len() * 3
     }
 
+    /// Number of triangles of this mesh.
+    pub fn num_triangles(&self) -> usize {
+        self.faces.read().unwrap().len()
+    }
+
+    /// Number of vertices of this mesh.
+    pub fn num_vertices(&self) -> usize {
+        self.coords.read().unwrap().len()
+    }
+
     /// Recompute this mesh normals.
     pub fn recompute_normals(&mut self) {
         Mesh::compute_normals(
@@ -275,11 +307,83 @@ impl Mesh {
         &self.coords
     }
 
+    /// Replaces the vertex coordinates starting at index `start` by `coords`.
+    ///
+    /// Only the modified range is uploaded to the GPU, so this is well suited for meshes
+    /// deformed at each frame, e.g., cloth or waves. Such meshes should be created with
+    /// `dynamic_draw` set to `true`. The normals are not recomputed.
+    ///
+    /// # Failure
+    /// Fails if the range does not fit in the vertex coordinates.
+    pub fn update_vertices_range(&mut self, start: usize, coords: &[Point3<f32>]) {
+        self.coords.write().unwrap().update_range(start, coords)
+    }
+
+    /// Creates a mesh sharing the vertices, faces, normals and colors of this one, but with its
+    /// own copy of the texture coordinates.
+    ///
+    /// Returns `None` if the texture coordinates are not available on the CPU.
+    pub fn with_own_uvs(&self) -> Option<Mesh> {
+        let uvs = self.uvs.read().unwrap().data().clone()?;
+
+        Some(Mesh {
+            coords: self.coords.clone(),
+            faces: self.faces.clone(),
+            normals: self.normals.clone(),
+            uvs: Arc::new(RwLock::new(GPUVec::new(
+                uvs,
+                BufferType::Array,
+                AllocationType::StaticDraw,
+            ))),
+            edges: self.edges.clone(),
+            colors: self.colors.clone(),
+        })
+    }
+
     /// This mesh texture coordinates.
     pub fn uvs(&self) -> &Arc<RwLock<GPUVec<Point2<f32>>>> {
         &self.uvs
     }
 
+    /// This mesh vertex colors, if any.
+    pub fn colors(&self) -> Option<&Arc<RwLock<GPUVec<Point3<f32>>>>> {
+        self.colors.as_ref()
+    }
+
+    /// The edges of this mesh, if they were set explicitly or already computed from its faces.
+    pub fn edges(&self) -> Option<&Arc<RwLock<GPUVec<Point2<VertexIndex>>>>> {
+        self.edges.as_ref()
+    }
+
+    /// Sets the edges of this mesh, as pairs of vertex indices.
+    ///
+    /// By default, the edges are computed from the faces the first time they are needed. Setting
+    /// them explicitly allows drawing only some segments, e.g., without the diagonals of the
+    /// triangulated faces. Set to `None` to compute them from the faces again.
+    pub fn set_edges(&mut self, edges: Option<Vec<Point2<VertexIndex>>>) {
+        self.edges = edges.map(|edges| {
+            Arc::new(RwLock::new(GPUVec::new(
+                edges,
+                BufferType::ElementArray,
+                AllocationType::StaticDraw,
+            )))
+        });
+    }
+
+    /// Sets the per-vertex colors of this mesh.
+    ///
+    /// Vertex colors are ignored by the default material. They are used by the `point_cloud`
+    /// material.
+    pub fn set_colors(&mut self, colors: Option<Vec<Point3<f32>>>) {
+        self.colors = colors.map(|colors| {
+            Arc::new(RwLock::new(GPUVec::new(
+                colors,
+                BufferType::Array,
+                AllocationType::DynamicDraw,
+            )))
+        });
+    }
+
     /// Computes normals from a set of faces.
     pub fn compute_normals_array(
         coordinates: &[Point3<f32>],
@@ -330,4 +434,192 @@ impl Mesh {
             *n /= *divisor
         }
     }
+
+    /// Saves this mesh to a file, using a compact binary format.
+    ///
+    /// The vertices, faces, normals, texture coordinates, and vertex colors are saved, and can be
+    /// loaded back with `Mesh::load`. Fails if the mesh data is not available on the CPU.
+    pub fn save(&self, path: &Path) -> IoResult<()> {
+        let coords = self.coords.read().unwrap();
+        let faces = self.faces.read().unwrap();
+        let normals = self.normals.read().unwrap();
+        let uvs = self.uvs.read().unwrap();
+        let colors = self.colors.as_ref().map(|c| c.read().unwrap());
+
+        let (coords, faces, normals, uvs) =
+            match (coords.data(), faces.data(), normals.data(), uvs.data()) {
+                (Some(coords), Some(faces), Some(normals), Some(uvs)) => {
+                    (coords, faces, normals, uvs)
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        "The mesh data is not available on the CPU.",
+                    ))
+                }
+            };
+        let colors = match colors {
+            Some(ref colors) => colors.data().as_ref().map(|c| &c[..]).unwrap_or(&[]),
+            None => &[],
+        };
+
+        let mut out = BufWriter::new(File::create(path)?);
+
+        out.write_all(MESH_FILE_MAGIC)?;
+        write_u32(&mut out, MESH_FILE_VERSION)?;
+
+        for len in &[
+            coords.len(),
+            faces.len(),
+            normals.len(),
+            uvs.len(),
+            colors.len(),
+        ] {
+            write_u32(&mut out, *len as u32)?;
+        }
+
+        for p in coords.iter().chain(colors.iter()) {
+            write_f32s(&mut out, &[p.x, p.y, p.z])?;
+        }
+
+        for f in faces {
+            for i in f.iter() {
+                write_u32(&mut out, u32::from(*i))?;
+            }
+        }
+
+        for n in normals {
+            write_f32s(&mut out, &[n.x, n.y, n.z])?;
+        }
+
+        for uv in uvs {
+            write_f32s(&mut out, &[uv.x, uv.y])?;
+        }
+
+        out.flush()
+    }
+
+    /// Loads a mesh from a file written by `Mesh::save`.
+    pub fn load(path: &Path, dynamic_draw: bool) -> IoResult<Mesh> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut input = BufReader::new(file);
+
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+
+        if &magic != MESH_FILE_MAGIC || read_u32(&mut input)? != MESH_FILE_VERSION {
+            return Err(invalid_data("Not a mesh file, or unsupported version."));
+        }
+
+        let mut lens = [0usize; 5];
+
+        for len in lens.iter_mut() {
+            *len = read_u32(&mut input)? as usize;
+        }
+
+        let [ncoords, nfaces, nnormals, nuvs, ncolors] = lens;
+
+        if nnormals != ncoords || nuvs != ncoords || (ncolors != 0 && ncolors != ncoords) {
+            return Err(invalid_data("Inconsistent mesh buffer lengths."));
+        }
+
+        // Check the lengths against the file size before allocating anything, so a corrupt file
+        // cannot trigger huge allocations. All the values are 4 bytes long.
+        let header_len = 4 + 4 + 4 * lens.len() as u64;
+        let num_values = (ncoords as u64 + ncolors as u64 + nfaces as u64 + nnormals as u64) * 3
+            + nuvs as u64 * 2;
+
+        if header_len + num_values * 4 > file_len {
+            return Err(invalid_data("The mesh file is truncated."));
+        }
+
+        let mut read_point3s = |n: usize| -> IoResult<Vec<Point3<f32>>> {
+            (0..n)
+                .map(|_| {
+                    let mut v = [0.0; 3];
+                    read_f32s(&mut input, &mut v)?;
+                    Ok(Point3::from(v))
+                })
+                .collect()
+        };
+
+        let coords = read_point3s(ncoords)?;
+        let colors = read_point3s(ncolors)?;
+
+        let mut faces = Vec::with_capacity(nfaces);
+
+        for _ in 0..nfaces {
+            let mut face = Point3::new(0, 0, 0);
+
+            for i in face.iter_mut() {
+                let id = read_u32(&mut input)?;
+
+                if id as usize >= ncoords {
+                    return Err(invalid_data("Face index out of bounds."));
+                }
+
+                *i = VertexIndex::try_from(id)
+                    .map_err(|_| invalid_data("Face index too large for `VertexIndex`."))?;
+            }
+
+            faces.push(face);
+        }
+
+        let normals = (0..nnormals)
+            .map(|_| {
+                let mut v = [0.0; 3];
+                read_f32s(&mut input, &mut v)?;
+                Ok(Vector3::from(v))
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+
+        let uvs = (0..nuvs)
+            .map(|_| {
+                let mut v = [0.0; 2];
+                read_f32s(&mut input, &mut v)?;
+                Ok(Point2::from(v))
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+
+        let mut mesh = Mesh::new(coords, faces, Some(normals), Some(uvs), dynamic_draw);
+
+        if ncolors != 0 {
+            mesh.set_colors(Some(colors));
+        }
+
+        Ok(mesh)
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_u32<W: Write>(out: &mut W, val: u32) -> IoResult<()> {
+    out.write_all(&val.to_le_bytes())
+}
+
+fn write_f32s<W: Write>(out: &mut W, vals: &[f32]) -> IoResult<()> {
+    for val in vals {
+        out.write_all(&val.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn read_u32<R: Read>(input: &mut R) -> IoResult<u32> {
+    let mut bytes = [0u8; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f32s<R: Read>(input: &mut R, out: &mut [f32]) -> IoResult<()> {
+    for val in out.iter_mut() {
+        let mut bytes = [0u8; 4];
+        input.read_exact(&mut bytes)?;
+        *val = f32::from_le_bytes(bytes);
+    }
+
+    Ok(())
 }
@@ -1,5 +1,6 @@
 //! A resource manager to load meshes.
 
+use crate::loader::mtl;
 use crate::loader::mtl::MtlMaterial;
 use crate::loader::obj;
 use crate::resource::Mesh;
@@ -48,6 +49,8 @@ impl MeshManager {
     }
 
     /// Adds a mesh with the specified name to this cache.
+    ///
+    /// A mesh previously added with the same name is replaced.
     pub fn add(&mut self, mesh: Rc<RefCell<Mesh>>, name: &str) {
         let _ = self.meshes.insert(name.to_string(), mesh);
     }
@@ -91,4 +94,21 @@ impl MeshManager {
             res
         })
     }
+
+    /// Loads the meshes described by the content of an obj file and, optionally, of its mtl
+    /// file.
+    ///
+    /// The `mtllib` statements of the obj file are ignored.
+    pub fn load_obj_from_memory(
+        obj_data: &str,
+        mtl_data: Option<&str>,
+        geometry_name: &str,
+    ) -> Vec<(String, Rc<RefCell<Mesh>>, Option<MtlMaterial>)> {
+        let materials = mtl_data.map(mtl::parse).unwrap_or_default();
+
+        obj::parse_with_materials(obj_data, materials, geometry_name)
+            .into_iter()
+            .map(|(n, m, mat)| (n, Rc::new(RefCell::new(m)), mat))
+            .collect()
+    }
 }
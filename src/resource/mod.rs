@@ -2,6 +2,7 @@
 
 pub use crate::context::Texture;
 pub use crate::resource::effect::{Effect, ShaderAttribute, ShaderUniform};
+pub use crate::resource::environment_map::EnvironmentMap;
 pub use crate::resource::framebuffer_manager::{
     FramebufferManager, OffscreenBuffers, RenderTarget,
 };
@@ -14,9 +15,11 @@ pub use crate::resource::mesh_manager::MeshManager;
 pub use crate::resource::planar_material_manager::PlanarMaterialManager;
 pub use crate::resource::planar_mesh::PlanarMesh;
 pub use crate::resource::planar_mesh_manager::PlanarMeshManager;
-pub use crate::resource::texture_manager::{TextureManager, TextureWrapping};
+pub(crate) use crate::resource::texture_manager::TEXTURE_ARRAY_PADDING;
+pub use crate::resource::texture_manager::{TextureManager, TextureOptions, TextureWrapping};
 
 mod effect;
+mod environment_map;
 mod framebuffer_manager;
 mod gl_primitive;
 mod gpu_vector;
@@ -6,7 +6,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-thread_local!(static KEY_MATERIAL_MANAGER: RefCell<PlanarMaterialManager> = RefCell::new(PlanarMaterialManager::new()));
+thread_local!(static KEY_MATERIAL_MANAGER: RefCell<Option<PlanarMaterialManager>> = RefCell::new(None));
 
 /// The material manager.
 ///
@@ -40,7 +40,18 @@ impl PlanarMaterialManager {
 
     /// Mutably applies a function to the material manager.
     pub fn get_global_manager<T, F: FnMut(&mut PlanarMaterialManager) -> T>(mut f: F) -> T {
-        KEY_MATERIAL_MANAGER.with(|manager| f(&mut *manager.borrow_mut()))
+        KEY_MATERIAL_MANAGER.with(|manager| {
+            f(manager
+                .borrow_mut()
+                .get_or_insert_with(PlanarMaterialManager::new))
+        })
+    }
+
+    /// Drops the global material manager, e.g., once the OpenGL context it was created with is
+    /// destroyed. It is created again the next time it is used.
+    pub(crate) fn clear_global_manager() {
+        let manager = KEY_MATERIAL_MANAGER.with(|manager| manager.borrow_mut().take());
+        drop(manager);
     }
 
     /// Gets the default material to draw objects.
@@ -9,7 +9,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-thread_local!(static KEY_MESH_MANAGER: RefCell<PlanarMeshManager> = RefCell::new(PlanarMeshManager::new()));
+thread_local!(static KEY_MESH_MANAGER: RefCell<Option<PlanarMeshManager>> = RefCell::new(None));
 
 /// The mesh manager.
 ///
@@ -74,7 +74,18 @@ impl PlanarMeshManager {
 
     /// Mutably applies a function to the mesh manager.
     pub fn get_global_manager<T, F: FnMut(&mut PlanarMeshManager) -> T>(mut f: F) -> T {
-        KEY_MESH_MANAGER.with(|manager| f(&mut *manager.borrow_mut()))
+        KEY_MESH_MANAGER.with(|manager| {
+            f(manager
+                .borrow_mut()
+                .get_or_insert_with(PlanarMeshManager::new))
+        })
+    }
+
+    /// Drops the global mesh manager, e.g., once the OpenGL context it was created with is
+    /// destroyed. It is created again the next time it is used.
+    pub(crate) fn clear_global_manager() {
+        let manager = KEY_MESH_MANAGER.with(|manager| manager.borrow_mut().take());
+        drop(manager);
     }
 
     /// Get a mesh with the specified name. Returns `None` if the mesh is not registered.
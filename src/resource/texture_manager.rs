@@ -1,6 +1,6 @@
 //! A resource manager to load textures.
 
-use image::{self, imageops::FilterType, DynamicImage, GenericImageView};
+use image::{self, imageops::FilterType, DynamicImage, GenericImage, GenericImageView};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::path::Path;
@@ -57,6 +57,26 @@ impl Texture {
         let wrap: u32 = wrapping.into();
         verify!(ctxt.tex_parameteri(Context::TEXTURE_2D, Context::TEXTURE_WRAP_T, wrap as i32));
     }
+
+    /// Sets the anisotropic filtering level of this texture.
+    ///
+    /// The level is clamped to the maximum supported by the graphics driver. A level of `0` or
+    /// `1` disables anisotropic filtering. This does nothing if the driver does not support the
+    /// `EXT_texture_filter_anisotropic` extension.
+    pub fn set_anisotropy(&self, level: u8) {
+        let ctxt = Context::get();
+
+        if !ctxt.supports_anisotropic_filtering() {
+            return;
+        }
+
+        let mut max = [1.0];
+        verify!(ctxt.get_parameter_f32_slice(Context::MAX_TEXTURE_MAX_ANISOTROPY, &mut max));
+        let level = (level as f32).max(1.0).min(max[0]);
+
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(self)));
+        verify!(ctxt.tex_parameterf(Context::TEXTURE_2D, Context::TEXTURE_MAX_ANISOTROPY, level));
+    }
 }
 
 impl Drop for Texture {
@@ -70,17 +90,41 @@ impl Drop for Texture {
     }
 }
 
+/// Options for loading a texture, see `TextureManager::add_with_options`.
+///
+/// Mipmap generation and anisotropic filtering follow the settings of the `TextureManager`.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct TextureOptions {
+    /// Whether the color channels are multiplied by the alpha channel when the texture is
+    /// uploaded. This has no effect on images without alpha channel.
+    ///
+    /// Premultiplied alpha avoids the dark fringes around the transparent parts of a texture
+    /// caused by filtering and mipmapping. The objects using such a texture should be rendered
+    /// with `BlendMode::PremultipliedAlpha`.
+    pub premultiply_alpha: bool,
+}
+
 // thread_local!(static KEY_TEXTURE_MANAGER: RefCell<Option<TextureManager>> = RefCell::new(Some(TextureManager::new())));
 
+/// The number of rows, in texels, added above and below each layer of a texture array.
+///
+/// They repeat the edge rows of the layer so the linear filtering and the first mipmap levels do
+/// not mix adjacent layers.
+pub(crate) const TEXTURE_ARRAY_PADDING: u32 = 4;
+
 /// The texture manager.
 ///
 /// It keeps a cache of already-loaded textures, and can load new textures.
 pub struct TextureManager {
     default_texture: Rc<Texture>,
     textures: HashMap<String, (Rc<Texture>, (u32, u32))>,
+    // The number of layers of the textures added with `add_array`.
+    layer_counts: HashMap<String, u32>,
     // If generate_mipmaps is true, mipmaps are generated for textures when they
     // are loaded.
     generate_mipmaps: bool,
+    // The anisotropic filtering level of the textures when they are loaded.
+    default_anisotropy: u8,
 }
 
 impl TextureManager {
@@ -127,8 +171,10 @@ impl TextureManager {
 
         TextureManager {
             textures: HashMap::new(),
+            layer_counts: HashMap::new(),
             default_texture: default_tex,
             generate_mipmaps: false,
+            default_anisotropy: 1,
         }
     }
 
@@ -148,8 +194,12 @@ impl TextureManager {
         self.textures.get(&name.to_string()).map(|t| t.0.clone())
     }
 
-    /// Get a texture (and its size) with the specified name. Returns `None` if the texture is not registered.
-    pub fn get_with_size(&mut self, name: &str) -> Option<(Rc<Texture>, (u32, u32))> {
+    /// Get a texture and its size with the specified name. Returns `None` if the texture is not
+    /// registered.
+    ///
+    /// The size is the width and height of the texture in pixels, or `(0, 0)` for textures
+    /// created with `add_empty`. For texture arrays, this is the size of one layer.
+    pub fn get_with_size(&self, name: &str) -> Option<(Rc<Texture>, (u32, u32))> {
         self.textures
             .get(&name.to_string())
             .map(|t| (t.0.clone(), t.1))
@@ -169,11 +219,29 @@ impl TextureManager {
     ///
     /// If a texture with same name exists, nothing is created and the old texture is returned.
     pub fn add_image(&mut self, image: DynamicImage, name: &str) -> Rc<Texture> {
-        let generate_mipmaps = self.generate_mipmaps;
+        self.add_image_with_options(image, name, TextureOptions::default())
+    }
+
+    /// Allocates a new texture read from a `DynamicImage` object, with the given options.
+    ///
+    /// If a texture with same name exists, nothing is created and the old texture is returned.
+    pub fn add_image_with_options(
+        &mut self,
+        image: DynamicImage,
+        name: &str,
+        options: TextureOptions,
+    ) -> Rc<Texture> {
+        let (generate_mipmaps, anisotropy) = (self.generate_mipmaps, self.default_anisotropy);
         self.textures
             .entry(name.to_string())
             .or_insert_with(|| {
-                TextureManager::load_texture_into_context(image, generate_mipmaps).unwrap()
+                TextureManager::load_texture_into_context(
+                    image,
+                    generate_mipmaps,
+                    anisotropy,
+                    options,
+                )
+                .unwrap()
             })
             .0
             .clone()
@@ -183,26 +251,70 @@ impl TextureManager {
     /// Panics if unable to do so
     /// If a texture with same name exists, nothing is created and the old texture is returned.
     pub fn add_image_from_memory(&mut self, image_data: &[u8], name: &str) -> Rc<Texture> {
-        self.add_image(
+        self.add_image_from_memory_with_options(image_data, name, TextureOptions::default())
+    }
+
+    /// Allocates a new texture decoded from a bytes array, with the given options.
+    /// Panics if unable to do so
+    /// If a texture with same name exists, nothing is created and the old texture is returned.
+    pub fn add_image_from_memory_with_options(
+        &mut self,
+        image_data: &[u8],
+        name: &str,
+        options: TextureOptions,
+    ) -> Rc<Texture> {
+        self.add_image_with_options(
             image::load_from_memory(image_data).expect("Invalid data"),
             name,
+            options,
         )
     }
 
     /// Allocates a new texture read from a file.
-    fn load_texture_from_file(path: &Path, generate_mipmaps: bool) -> (Rc<Texture>, (u32, u32)) {
+    fn load_texture_from_file(
+        path: &Path,
+        generate_mipmaps: bool,
+        anisotropy: u8,
+        options: TextureOptions,
+    ) -> (Rc<Texture>, (u32, u32)) {
         let image = image::open(path)
             .unwrap_or_else(|e| panic!("Unable to load texture from file {:?}: {:?}", path, e));
-        TextureManager::load_texture_into_context(image, generate_mipmaps)
+        TextureManager::load_texture_into_context(image, generate_mipmaps, anisotropy, options)
             .unwrap_or_else(|e| panic!("Unable to upload texture {:?}: {:?}", path, e))
     }
 
+    /// Multiplies the color channels of `image` by its alpha channel.
+    fn premultiply_alpha(image: DynamicImage) -> DynamicImage {
+        if !image.color().has_alpha() {
+            return image;
+        }
+
+        let mut image = image.into_rgba8();
+
+        for pixel in image.pixels_mut() {
+            let alpha = pixel[3] as u16;
+
+            for c in pixel.0.iter_mut().take(3) {
+                *c = ((*c as u16 * alpha + 127) / 255) as u8;
+            }
+        }
+
+        DynamicImage::ImageRgba8(image)
+    }
+
     fn load_texture_into_context(
         image: DynamicImage,
         generate_mipmaps: bool,
+        anisotropy: u8,
+        options: TextureOptions,
     ) -> Result<(Rc<Texture>, (u32, u32)), &'static str> {
         let ctxt = Context::get();
         let tex = Texture::new();
+        let image = if options.premultiply_alpha {
+            TextureManager::premultiply_alpha(image)
+        } else {
+            image
+        };
         let (width, height) = image.dimensions();
 
         unsafe {
@@ -248,6 +360,11 @@ impl TextureManager {
                 Context::LINEAR as i32
             ));
         }
+
+        if anisotropy > 1 {
+            tex.set_anisotropy(anisotropy);
+        }
+
         Ok((tex, (width, height)))
     }
 
@@ -281,18 +398,140 @@ impl TextureManager {
     /// Allocates a new texture read from a file. If a texture with same name exists, nothing is
     /// created and the old texture is returned.
     pub fn add(&mut self, path: &Path, name: &str) -> Rc<Texture> {
-        let generate_mipmaps = self.generate_mipmaps;
+        self.add_with_options(path, name, TextureOptions::default())
+    }
+
+    /// Allocates a new texture read from a file, with the given options. If a texture with same
+    /// name exists, nothing is created and the old texture is returned.
+    ///
+    /// For example, this loads a texture with premultiplied alpha:
+    ///
+    /// ```no_run
+    /// # use kiss3d::resource::{TextureManager, TextureOptions};
+    /// # use std::path::Path;
+    /// # fn load(tm: &mut TextureManager) {
+    /// let options = TextureOptions {
+    ///     premultiply_alpha: true,
+    /// };
+    /// let leaves = tm.add_with_options(Path::new("leaves.png"), "leaves", options);
+    /// # }
+    /// ```
+    pub fn add_with_options(
+        &mut self,
+        path: &Path,
+        name: &str,
+        options: TextureOptions,
+    ) -> Rc<Texture> {
+        let (generate_mipmaps, anisotropy) = (self.generate_mipmaps, self.default_anisotropy);
         self.textures
             .entry(name.to_string())
-            .or_insert_with(|| TextureManager::load_texture_from_file(path, generate_mipmaps))
+            .or_insert_with(|| {
+                TextureManager::load_texture_from_file(path, generate_mipmaps, anisotropy, options)
+            })
             .0
             .clone()
     }
 
+    /// Allocates a new texture array whose layers are read from files. If a texture with same
+    /// name exists, nothing is created and the old texture is returned.
+    ///
+    /// All the images must have the same size. The layer sampled by an object is selected with
+    /// `SceneNode::set_texture_layer`, e.g., to animate sprites without uploading new textures.
+    ///
+    /// Because WebGL 1 and OpenGL ES 2 have no array textures, the layers are stacked
+    /// vertically into a single 2D texture, each padded with a copy of its top and bottom rows.
+    /// Its height, i.e., the height of one padded image times the number of layers, must not
+    /// exceed the maximum texture size of the graphics driver. The texture coordinates are
+    /// clamped to the layer so wrapping (see `TextureWrapping`) is not supported, and the
+    /// smallest mipmap levels, if any, mix adjacent layers.
+    pub fn add_array(&mut self, paths: &[&Path], name: &str) -> Rc<Texture> {
+        if let Some(texture) = self.textures.get(name) {
+            return texture.0.clone();
+        }
+
+        assert!(
+            !paths.is_empty(),
+            "A texture array needs at least one layer."
+        );
+
+        let layers: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                image::open(path)
+                    .unwrap_or_else(|e| {
+                        panic!("Unable to load texture from file {:?}: {:?}", path, e)
+                    })
+                    .to_rgba8()
+            })
+            .collect();
+        let (width, height) = layers[0].dimensions();
+        let slot_height = height + 2 * TEXTURE_ARRAY_PADDING;
+
+        let mut stacked = image::RgbaImage::new(width, slot_height * layers.len() as u32);
+
+        for (i, (layer, path)) in layers.iter().zip(paths.iter()).enumerate() {
+            assert!(
+                layer.dimensions() == (width, height),
+                "The layer {:?} of the texture array {} does not have the size of the first one.",
+                path,
+                name
+            );
+
+            let top = i as u32 * slot_height;
+            stacked
+                .copy_from(layer, 0, top + TEXTURE_ARRAY_PADDING)
+                .expect("The texture array layers must fit in the stacked image.");
+
+            for x in 0..width {
+                for p in 0..TEXTURE_ARRAY_PADDING {
+                    stacked.put_pixel(x, top + p, *layer.get_pixel(x, 0));
+                    stacked.put_pixel(
+                        x,
+                        top + TEXTURE_ARRAY_PADDING + height + p,
+                        *layer.get_pixel(x, height - 1),
+                    );
+                }
+            }
+        }
+
+        let texture = TextureManager::load_texture_into_context(
+            DynamicImage::ImageRgba8(stacked),
+            self.generate_mipmaps,
+            self.default_anisotropy,
+            TextureOptions::default(),
+        )
+        .unwrap_or_else(|e| panic!("Unable to upload the texture array {}: {:?}", name, e));
+
+        let _ = self
+            .layer_counts
+            .insert(name.to_string(), layers.len() as u32);
+        let _ = self
+            .textures
+            .insert(name.to_string(), (texture.0.clone(), (width, height)));
+        texture.0
+    }
+
+    /// The number of layers of the texture with the specified name.
+    ///
+    /// This is `1` for textures not added with `add_array`.
+    pub fn layer_count(&self, name: &str) -> u32 {
+        self.layer_counts.get(name).cloned().unwrap_or(1)
+    }
+
     /// Changes whether textures will have mipmaps generated when they are
     /// loaded; does not affect already loaded textures.
     /// Mipmap generation is disabled by default.
     pub fn set_generate_mipmaps(&mut self, enabled: bool) {
         self.generate_mipmaps = enabled;
     }
+
+    /// Sets the anisotropic filtering level of the textures loaded afterwards; does not affect
+    /// already loaded textures, see `Texture::set_anisotropy` for those.
+    ///
+    /// Anisotropic filtering keeps textures sharp when they are viewed at grazing angles. It is
+    /// most effective together with mipmaps, see `set_generate_mipmaps`. A level of `0` or `1`
+    /// disables it, which is the default.
+    pub fn set_default_anisotropy(&mut self, level: u8) {
+        self.default_anisotropy = level;
+    }
 }
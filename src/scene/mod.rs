@@ -1,9 +1,10 @@
 //! Everything related to the scene graph.
 
-pub use self::object::{Object, ObjectData};
+pub(crate) use self::object::{apply_cull_mode, custom_render_priority_used};
+pub use self::object::{BlendMode, CullMode, Object, ObjectData};
 pub use self::planar_object::{PlanarObject, PlanarObjectData};
 pub use self::planar_scene_node::{PlanarSceneNode, PlanarSceneNodeData};
-pub use self::scene_node::{SceneNode, SceneNodeData};
+pub use self::scene_node::{NodeId, SceneNode, SceneNodeData, SceneStats};
 
 mod object;
 mod planar_object;
@@ -1,25 +1,97 @@
 //! Data structure of a scene node.
 
 use crate::camera::Camera;
+use crate::context::Context;
 use crate::light::Light;
 use crate::resource::vertex_index::VertexIndex;
-use crate::resource::{Material, Mesh, Texture, TextureManager};
+use crate::resource::{EnvironmentMap, Material, Mesh, Texture, TextureManager};
+use crate::verify;
 use na::{Isometry3, Point2, Point3, Vector3};
 use std::any::Any;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::path::Path;
 use std::rc::Rc;
 
+/// The faces of an object discarded during rendering.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CullMode {
+    /// Both front and back faces are drawn.
+    None,
+    /// Back faces are not drawn.
+    Back,
+    /// Front faces are not drawn, e.g., to look at the inside of a closed shell.
+    Front,
+}
+
+thread_local!(static CUSTOM_RENDER_PRIORITY: Cell<bool> = Cell::new(false));
+
+/// Whether a rendering priority other than `0` was set on any object.
+///
+/// If not, the scene is rendered with a single traversal.
+pub(crate) fn custom_render_priority_used() -> bool {
+    CUSTOM_RENDER_PRIORITY.with(|p| p.get())
+}
+
+/// Enables or disables the face culling of the current context according to `mode`.
+///
+/// The default state, restored after each object is rendered, is `CullMode::Back`.
+pub(crate) fn apply_cull_mode(mode: CullMode) {
+    let ctxt = Context::get();
+
+    match mode {
+        CullMode::None => verify!(ctxt.disable(Context::CULL_FACE)),
+        CullMode::Back => {
+            verify!(ctxt.enable(Context::CULL_FACE));
+            verify!(ctxt.cull_face(Context::BACK));
+        }
+        CullMode::Front => {
+            verify!(ctxt.enable(Context::CULL_FACE));
+            verify!(ctxt.cull_face(Context::FRONT));
+        }
+    }
+}
+
+/// How the color of an object is combined with the color of what is behind it.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Regular alpha blending: the object is transparent if its alpha is smaller than 1.
+    Alpha,
+    /// The color of the object, weighted by its alpha, is added to the color behind it, e.g.,
+    /// for glows or particles.
+    Additive,
+    /// The color behind the object is multiplied by the color of the object, e.g., for stains
+    /// or fake shadows. The alpha of the object is ignored.
+    Multiply,
+    /// No blending: the object is always opaque and its alpha is ignored.
+    None,
+    /// Alpha blending for objects whose colors are already multiplied by their alpha, e.g.,
+    /// textured with `TextureOptions::premultiply_alpha`. The object is always drawn with the
+    /// transparent objects, and its alpha should be kept to 1.
+    PremultipliedAlpha,
+}
+
 /// Set of data identifying a scene node.
 pub struct ObjectData {
     material: Rc<RefCell<Box<dyn Material + 'static>>>,
     texture: Rc<Texture>,
+    texture_layer: u32,
+    texture_layer_count: u32,
+    texture_layer_height: u32,
     color: Point3<f32>,
+    alpha: f32,
     lines_color: Option<Point3<f32>>,
+    backface_color: Option<Point3<f32>>,
     wlines: f32,
     wpoints: f32,
+    outline_color: Option<Point3<f32>>,
+    woutline: f32,
     draw_surface: bool,
-    cull: bool,
+    cull_mode: CullMode,
+    blend_mode: BlendMode,
+    depth_test: bool,
+    render_priority: i32,
+    environment_map: Option<Rc<EnvironmentMap>>,
+    reflectivity: f32,
     user_data: Box<dyn Any + 'static>,
 }
 
@@ -30,12 +102,36 @@ impl ObjectData {
         &self.texture
     }
 
+    /// The layer sampled by this object if its texture is a texture array, `0` otherwise.
+    #[inline]
+    pub fn texture_layer(&self) -> u32 {
+        self.texture_layer
+    }
+
+    /// The number of layers of the texture of this object, `1` if it is not a texture array.
+    #[inline]
+    pub fn texture_layer_count(&self) -> u32 {
+        self.texture_layer_count
+    }
+
+    /// The height, in texels, of one layer of the texture array of this object.
+    #[inline]
+    pub fn texture_layer_height(&self) -> u32 {
+        self.texture_layer_height
+    }
+
     /// The color of this object.
     #[inline]
     pub fn color(&self) -> &Point3<f32> {
         &self.color
     }
 
+    /// The opacity of this object, between `0.0` (invisible) and `1.0` (opaque).
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
     /// The width of the lines draw for this object.
     #[inline]
     pub fn lines_width(&self) -> f32 {
@@ -54,6 +150,48 @@ impl ObjectData {
         self.wpoints
     }
 
+    /// The color of the back faces of this object, if it differs from its color.
+    #[inline]
+    pub fn backface_color(&self) -> Option<&Point3<f32>> {
+        self.backface_color.as_ref()
+    }
+
+    /// Whether this object is tested against the depth buffer when drawn.
+    #[inline]
+    pub fn depth_test(&self) -> bool {
+        self.depth_test
+    }
+
+    /// The priority of this object in the rendering order.
+    #[inline]
+    pub fn render_priority(&self) -> i32 {
+        self.render_priority
+    }
+
+    /// The environment map reflected by this object, if any.
+    #[inline]
+    pub fn environment_map(&self) -> Option<&Rc<EnvironmentMap>> {
+        self.environment_map.as_ref()
+    }
+
+    /// The fraction of the color of this object replaced by the reflected environment.
+    #[inline]
+    pub fn reflectivity(&self) -> f32 {
+        self.reflectivity
+    }
+
+    /// The color of the outline drawn around this object, if any.
+    #[inline]
+    pub fn outline_color(&self) -> Option<&Point3<f32>> {
+        self.outline_color.as_ref()
+    }
+
+    /// The width of the outline drawn around this object.
+    #[inline]
+    pub fn outline_width(&self) -> f32 {
+        self.woutline
+    }
+
     /// Whether this object has its surface rendered or not.
     #[inline]
     pub fn surface_rendering_active(&self) -> bool {
@@ -63,7 +201,19 @@ impl ObjectData {
     /// Whether this object uses backface culling or not.
     #[inline]
     pub fn backface_culling_enabled(&self) -> bool {
-        self.cull
+        self.cull_mode == CullMode::Back
+    }
+
+    /// The faces of this object discarded during rendering.
+    #[inline]
+    pub fn cull_mode(&self) -> CullMode {
+        self.cull_mode
+    }
+
+    /// How the color of this object is combined with the color of what is behind it.
+    #[inline]
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
     }
 
     /// An user-defined data.
@@ -98,12 +248,24 @@ impl Object {
         let user_data = ();
         let data = ObjectData {
             color: Point3::new(r, g, b),
+            alpha: 1.0,
             lines_color: None,
+            backface_color: None,
             texture,
+            texture_layer: 0,
+            texture_layer_count: 1,
+            texture_layer_height: 0,
             wlines: 0.0,
             wpoints: 0.0,
+            outline_color: None,
+            woutline: 0.0,
             draw_surface: true,
-            cull: true,
+            cull_mode: CullMode::Back,
+            blend_mode: BlendMode::Alpha,
+            depth_test: true,
+            render_priority: 0,
+            environment_map: None,
+            reflectivity: 0.5,
             material,
             user_data: Box::new(user_data),
         };
@@ -120,6 +282,10 @@ impl Object {
         camera: &mut dyn Camera,
         light: &Light,
     ) {
+        if !self.data.depth_test {
+            verify!(Context::get().disable(Context::DEPTH_TEST));
+        }
+
         self.data.material.borrow_mut().render(
             pass,
             transform,
@@ -129,6 +295,13 @@ impl Object {
             &self.data,
             &mut *self.mesh.borrow_mut(),
         );
+
+        // Restore the default culling for the materials that do not set it themselves.
+        apply_cull_mode(CullMode::Back);
+
+        if !self.data.depth_test {
+            verify!(Context::get().enable(Context::DEPTH_TEST));
+        }
     }
 
     /// Gets the data of this object.
@@ -146,7 +319,73 @@ impl Object {
     /// Enables or disables backface culling for this object.
     #[inline]
     pub fn enable_backface_culling(&mut self, active: bool) {
-        self.data.cull = active;
+        self.data.cull_mode = if active {
+            CullMode::Back
+        } else {
+            CullMode::None
+        };
+    }
+
+    /// Sets the faces of this object discarded during rendering.
+    #[inline]
+    pub fn set_cull_mode(&mut self, mode: CullMode) {
+        self.data.cull_mode = mode;
+    }
+
+    /// Sets how the color of this object is combined with the color of what is behind it.
+    ///
+    /// Objects with additive or multiplicative blending are drawn with the transparent objects.
+    /// With `TransparencyMode::WeightedBlended`, they are composited like the other transparent
+    /// objects instead.
+    #[inline]
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.data.blend_mode = mode;
+    }
+
+    /// Enables or disables depth testing for this object.
+    ///
+    /// An object without depth testing is drawn over everything rendered before it.
+    #[inline]
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        self.data.depth_test = enabled;
+    }
+
+    /// Sets the opacity of this object, between `0.0` (invisible) and `1.0` (opaque).
+    ///
+    /// Objects with an opacity smaller than `1.0` are drawn after the opaque ones, with
+    /// blending. See `Window::set_transparency_mode` for how they are composited.
+    #[inline]
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.data.alpha = alpha.max(0.0).min(1.0);
+    }
+
+    /// Returns the opacity of this object.
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        self.data.alpha
+    }
+
+    /// Whether this object is drawn with the transparent objects.
+    #[inline]
+    pub fn is_transparent(&self) -> bool {
+        match self.data.blend_mode {
+            BlendMode::Alpha => self.data.alpha < 1.0,
+            BlendMode::Additive | BlendMode::Multiply | BlendMode::PremultipliedAlpha => true,
+            BlendMode::None => false,
+        }
+    }
+
+    /// Sets the priority of this object in the rendering order.
+    ///
+    /// Objects with a higher priority are drawn after objects with a lower priority. Objects
+    /// with the same priority are drawn in the scene graph order. The default priority is `0`.
+    #[inline]
+    pub fn set_render_priority(&mut self, priority: i32) {
+        if priority != 0 {
+            CUSTOM_RENDER_PRIORITY.with(|p| p.set(true));
+        }
+
+        self.data.render_priority = priority;
     }
 
     /// Attaches user-defined data to this object.
@@ -191,6 +430,35 @@ impl Object {
         self.data.lines_color
     }
 
+    /// Draws the edges of this object on top of its surface, with the given color and width.
+    ///
+    /// This is a shorthand for `set_lines_color` and `set_lines_width`. Set the color to `None`
+    /// to disable the overlay.
+    #[inline]
+    pub fn set_wireframe_overlay(&mut self, color: Option<Point3<f32>>, width: f32) {
+        match color {
+            Some(color) => {
+                self.data.lines_color = Some(color);
+                self.data.wlines = width;
+            }
+            None => self.data.wlines = 0.0,
+        }
+    }
+
+    /// Sets the color of the back faces of this object.
+    ///
+    /// Set to `None` to shade back faces with the same color as front faces.
+    #[inline]
+    pub fn set_backface_color(&mut self, color: Option<Point3<f32>>) {
+        self.data.backface_color = color
+    }
+
+    /// Returns the color of the back faces of this object, if it differs from its color.
+    #[inline]
+    pub fn backface_color(&self) -> Option<Point3<f32>> {
+        self.data.backface_color
+    }
+
     /// Sets the size of the points drawn for this object.
     #[inline]
     pub fn set_points_size(&mut self, size: f32) {
@@ -203,6 +471,57 @@ impl Object {
         self.data.wpoints
     }
 
+    /// Sets the color and width of the outline drawn around this object.
+    ///
+    /// Set the color to `None` to disable the outline.
+    #[inline]
+    pub fn set_outline(&mut self, color: Option<Point3<f32>>, width: f32) {
+        self.data.outline_color = color;
+        self.data.woutline = width;
+    }
+
+    /// Returns the color of the outline drawn around this object.
+    #[inline]
+    pub fn outline_color(&self) -> Option<Point3<f32>> {
+        self.data.outline_color
+    }
+
+    /// Returns the width of the outline drawn around this object.
+    #[inline]
+    pub fn outline_width(&self) -> f32 {
+        self.data.woutline
+    }
+
+    /// Sets the environment map reflected by this object.
+    ///
+    /// Set to `None` to disable reflections. Only the default object material supports
+    /// environment maps.
+    #[inline]
+    pub fn set_environment_map(&mut self, map: Option<Rc<EnvironmentMap>>) {
+        self.data.environment_map = map
+    }
+
+    /// Returns the environment map reflected by this object, if any.
+    #[inline]
+    pub fn environment_map(&self) -> Option<Rc<EnvironmentMap>> {
+        self.data.environment_map.clone()
+    }
+
+    /// Sets the fraction of the color of this object replaced by the reflected environment.
+    ///
+    /// The reflectivity is clamped to `[0.0, 1.0]` and defaults to `0.5`. It has no effect
+    /// unless an environment map is set.
+    #[inline]
+    pub fn set_reflectivity(&mut self, reflectivity: f32) {
+        self.data.reflectivity = reflectivity.max(0.0).min(1.0)
+    }
+
+    /// Returns the fraction of the color of this object replaced by the reflected environment.
+    #[inline]
+    pub fn reflectivity(&self) -> f32 {
+        self.data.reflectivity
+    }
+
     /// Activate or deactivate the rendering of this object surface.
     #[inline]
     pub fn set_surface_rendering_activation(&mut self, active: bool) {
@@ -221,6 +540,12 @@ impl Object {
         &self.mesh
     }
 
+    /// Replaces this object's mesh, keeping its other properties like its color and material.
+    #[inline]
+    pub fn set_mesh(&mut self, mesh: Rc<RefCell<Mesh>>) {
+        self.mesh = mesh
+    }
+
     /// Mutably access the object's vertices.
     #[inline(always)]
     pub fn modify_vertices<F: FnMut(&mut Vec<Point3<f32>>)>(&mut self, f: &mut F) {
@@ -234,6 +559,36 @@ impl Object {
             .map(|coords| f(coords));
     }
 
+    /// Replaces the object's vertices starting at index `start` by `coords`.
+    ///
+    /// See `Mesh::update_vertices_range` for details.
+    #[inline]
+    pub fn update_vertices_range(&mut self, start: usize, coords: &[Point3<f32>]) {
+        self.mesh.borrow_mut().update_vertices_range(start, coords)
+    }
+
+    /// Mutably access the object's vertices and vertex colors.
+    ///
+    /// Does nothing if the object's mesh has no vertex colors.
+    #[inline(always)]
+    pub fn modify_points<F: FnMut(&mut Vec<Point3<f32>>, &mut Vec<Point3<f32>>)>(
+        &mut self,
+        f: &mut F,
+    ) {
+        let bmesh = self.mesh.borrow_mut();
+
+        if let Some(colors) = bmesh.colors() {
+            let mut coords = bmesh.coords().write().unwrap();
+            let mut colors = colors.write().unwrap();
+
+            if let (Some(coords), Some(colors)) =
+                (coords.data_mut().as_mut(), colors.data_mut().as_mut())
+            {
+                f(coords, colors)
+            }
+        }
+    }
+
     /// Access the object's vertices.
     #[inline(always)]
     pub fn read_vertices<F: FnMut(&[Point3<f32>])>(&self, f: &mut F) {
@@ -359,18 +714,40 @@ impl Object {
     /// The texture must already have been registered as `name`.
     #[inline]
     pub fn set_texture_with_name(&mut self, name: &str) {
-        let texture = TextureManager::get_global_manager(|tm| {
-            tm.get(name).unwrap_or_else(|| {
+        let (texture, (_, layer_height), layer_count) = TextureManager::get_global_manager(|tm| {
+            let (texture, size) = tm.get_with_size(name).unwrap_or_else(|| {
                 panic!("Invalid attempt to use the unregistered texture: {}", name)
-            })
+            });
+            (texture, size, tm.layer_count(name))
         });
 
-        self.set_texture(texture)
+        self.set_texture_array(texture, layer_count, layer_height)
     }
 
     /// Sets the texture of the object.
     #[inline]
     pub fn set_texture(&mut self, texture: Rc<Texture>) {
-        self.data.texture = texture
+        self.set_texture_array(texture, 1, 0)
+    }
+
+    /// Sets the texture of the object to a texture array with `layer_count` layers of
+    /// `layer_height` texels stacked vertically, as created by `TextureManager::add_array`.
+    ///
+    /// The sampled layer is reset to `0`.
+    #[inline]
+    pub fn set_texture_array(&mut self, texture: Rc<Texture>, layer_count: u32, layer_height: u32) {
+        self.data.texture = texture;
+        self.data.texture_layer = 0;
+        self.data.texture_layer_count = layer_count.max(1);
+        self.data.texture_layer_height = layer_height;
+    }
+
+    /// Selects the layer sampled by this object if its texture is a texture array.
+    ///
+    /// The layer wraps around the number of layers, so incrementing it each frame loops through
+    /// an animation.
+    #[inline]
+    pub fn set_texture_layer(&mut self, layer: u32) {
+        self.data.texture_layer = layer % self.data.texture_layer_count
     }
 }
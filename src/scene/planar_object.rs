@@ -13,6 +13,7 @@ use std::rc::Rc;
 pub struct PlanarObjectData {
     material: Rc<RefCell<Box<dyn PlanarMaterial + 'static>>>,
     texture: Rc<Texture>,
+    texture_rect: (Point2<f32>, Point2<f32>),
     color: Point3<f32>,
     lines_color: Option<Point3<f32>>,
     wlines: f32,
@@ -29,6 +30,12 @@ impl PlanarObjectData {
         &self.texture
     }
 
+    /// The `(min, max)` normalized rectangle of the texture displayed on this object.
+    #[inline]
+    pub fn texture_rect(&self) -> (Point2<f32>, Point2<f32>) {
+        self.texture_rect
+    }
+
     /// The color of this object.
     #[inline]
     pub fn color(&self) -> &Point3<f32> {
@@ -99,6 +106,7 @@ impl PlanarObject {
             color: Point3::new(r, g, b),
             lines_color: None,
             texture,
+            texture_rect: (Point2::origin(), Point2::new(1.0, 1.0)),
             wlines: 0.0,
             wpoints: 0.0,
             draw_surface: true,
@@ -336,4 +344,14 @@ impl PlanarObject {
     pub fn set_texture(&mut self, texture: Rc<Texture>) {
         self.data.texture = texture
     }
+
+    /// Sets the `(min, max)` normalized rectangle of the texture displayed on this object.
+    ///
+    /// The texture coordinates of the mesh are mapped into this rectangle when rendering, so
+    /// the mesh itself is left unchanged. Defaults to the whole texture, i.e., `(0, 0)` to
+    /// `(1, 1)`.
+    #[inline]
+    pub fn set_texture_rect(&mut self, min: Point2<f32>, max: Point2<f32>) {
+        self.data.texture_rect = (min, max)
+    }
 }
@@ -1,4 +1,4 @@
-use na::{self, Isometry2, Point2, Point3, Translation2, UnitComplex, Vector2};
+use na::{self, Isometry2, Matrix3, Point2, Point3, Translation2, UnitComplex, Vector2};
 
 use crate::planar_camera::PlanarCamera;
 use crate::resource::vertex_index::VertexIndex;
@@ -6,12 +6,22 @@ use crate::resource::{
     PlanarMaterial, PlanarMaterialManager, PlanarMesh, PlanarMeshManager, Texture, TextureManager,
 };
 use crate::scene::PlanarObject;
+use crate::text::{Font, TextRenderer};
+use std::any::Any;
 use std::cell::{Ref, RefCell, RefMut};
 use std::f32;
 use std::mem;
 use std::path::Path;
 use std::rc::Rc;
 
+// A piece of text attached to a node.
+struct PlanarText {
+    text: String,
+    scale: f32,
+    color: Point3<f32>,
+    font: Rc<Font>,
+}
+
 // XXX: once something like `fn foo(self: Rc<RefCell<PlanarSceneNode>>)` is allowed, this extra struct
 // will not be needed any more.
 /// The datas contained by a `PlanarSceneNode`.
@@ -21,11 +31,14 @@ pub struct PlanarSceneNodeData {
     world_scale: Vector2<f32>,
     world_transform: Isometry2<f32>,
     visible: bool,
+    render_order: i32,
     up_to_date: bool,
     children: Vec<PlanarSceneNode>,
     object: Option<PlanarObject>,
+    text: Option<PlanarText>,
     // FIXME: use Weak pointers instead of the raw pointer.
     parent: Option<*const RefCell<PlanarSceneNodeData>>,
+    user_data: Option<Box<dyn Any>>,
 }
 
 /// A node of the scene graph.
@@ -64,7 +77,7 @@ impl PlanarSceneNodeData {
             .iter()
             .rposition(|e| std::ptr::eq(&*o.data, &*e.data))
         {
-            let _ = self.children.swap_remove(i);
+            let _ = self.children.remove(i);
         }
     }
 
@@ -82,8 +95,18 @@ impl PlanarSceneNodeData {
 
     /// Render the scene graph rooted by this node.
     pub fn render(&mut self, camera: &mut dyn PlanarCamera) {
+        self.render_with_scratch(camera, &mut Vec::new())
+    }
+
+    /// Render the scene graph rooted by this node, using `order` as scratch space to sort the
+    /// children so it can be reused from one frame to the next.
+    pub(crate) fn render_with_scratch(
+        &mut self,
+        camera: &mut dyn PlanarCamera,
+        order: &mut Vec<usize>,
+    ) {
         if self.visible {
-            self.do_render(&na::one(), &Vector2::from_element(1.0), camera)
+            self.do_render(&na::one(), &Vector2::from_element(1.0), camera, order)
         }
     }
 
@@ -92,6 +115,7 @@ impl PlanarSceneNodeData {
         transform: &Isometry2<f32>,
         scale: &Vector2<f32>,
         camera: &mut dyn PlanarCamera,
+        order: &mut Vec<usize>,
     ) {
         if !self.up_to_date {
             self.up_to_date = true;
@@ -103,12 +127,21 @@ impl PlanarSceneNodeData {
             o.render(&self.world_transform, &self.world_scale, camera)
         }
 
-        for c in self.children.iter_mut() {
-            let mut bc = c.data_mut();
+        // Siblings with the same render order are drawn in insertion order. The children are
+        // not reordered themselves so their insertion order is preserved. The indices of the
+        // children are stacked on top of the ones of the ancestors, and popped once drawn.
+        let start = order.len();
+        order.extend(0..self.children.len());
+        order[start..].sort_by_key(|&i| (self.children[i].data().render_order, i));
+
+        for k in start..order.len() {
+            let mut bc = self.children[order[k]].data_mut();
             if bc.visible {
-                bc.do_render(&self.world_transform, &self.world_scale, camera)
+                bc.do_render(&self.world_transform, &self.world_scale, camera, order)
             }
         }
+
+        order.truncate(start);
     }
 
     /// A reference to the object possibly contained by this node.
@@ -143,14 +176,102 @@ impl PlanarSceneNodeData {
             .expect("This scene node does not contain an PlanarObject.")
     }
 
-    ///////////////////~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ HERE
-    /* FIXME: the ~Any is kind of problematic here…
-    /// Attaches user-defined data to the objects contained by this node and its children.
+    /// The text attached to this node, if it was created with `PlanarSceneNode::add_text`.
     #[inline]
-    pub fn set_user_data(&mut self, user_data: ~Any) {
-        self.apply_to_objects_mut(&mut |o| o.set_user_data(user_data))
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_ref().map(|t| &t.text[..])
+    }
+
+    /// Replaces the text attached to this node.
+    ///
+    /// This has no effect if this node was not created with `PlanarSceneNode::add_text`.
+    #[inline]
+    pub fn set_text(&mut self, text: String) {
+        if let Some(t) = &mut self.text {
+            t.text = text;
+        }
+    }
+
+    /// Sets the color of the text attached to this node.
+    ///
+    /// This has no effect if this node was not created with `PlanarSceneNode::add_text`.
+    #[inline]
+    pub fn set_text_color(&mut self, color: Point3<f32>) {
+        if let Some(t) = &mut self.text {
+            t.color = color;
+        }
+    }
+
+    /// Queues the texts attached to the visible nodes of the scene graph rooted by this node.
+    ///
+    /// The world transformations must have been updated by `render` beforehand. `width` and
+    /// `height` are the size of the framebuffer, in pixels.
+    pub(crate) fn queue_texts(
+        &self,
+        renderer: &mut TextRenderer,
+        camera: &dyn PlanarCamera,
+        width: f32,
+        height: f32,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        if let Some(text) = &self.text {
+            // The planar cameras are affine, so three points are enough to recover the mapping
+            // from the screen to the world.
+            let size = Vector2::new(width, height);
+            let origin = camera.unproject(&Point2::origin(), &size);
+            let x = camera.unproject(&Point2::new(1.0, 0.0), &size) - origin;
+            let y = camera.unproject(&Point2::new(0.0, 1.0), &size) - origin;
+            let screen_to_world =
+                Matrix3::new(x.x, y.x, origin.x, x.y, y.y, origin.y, 0.0, 0.0, 1.0);
+
+            if let Some(world_to_screen) = screen_to_world.try_inverse() {
+                // The text coordinates have their y axis pointing downward.
+                let local = Matrix3::new_nonuniform_scaling(&Vector2::new(
+                    self.world_scale.x,
+                    -self.world_scale.y,
+                ));
+                let transform = world_to_screen * self.world_transform.to_homogeneous() * local;
+
+                renderer.draw_text_transformed(
+                    &text.text,
+                    &transform,
+                    text.scale,
+                    &text.font,
+                    &text.color,
+                );
+            }
+        }
+
+        for c in self.children.iter() {
+            c.data().queue_texts(renderer, camera, width, height)
+        }
+    }
+
+    /// Attaches user-defined data to this node, replacing the previous one.
+    ///
+    /// Unlike the user data of objects, this is stored on the node itself so it is available for
+    /// group nodes as well.
+    #[inline]
+    pub fn set_user_data(&mut self, user_data: Box<dyn Any>) {
+        self.user_data = Some(user_data)
+    }
+
+    /// The user-defined data attached to this node, if any.
+    ///
+    /// Use dynamic typing capabilities of the `Any` type to recover the actual data.
+    #[inline]
+    pub fn user_data(&self) -> Option<&dyn Any> {
+        self.user_data.as_deref()
+    }
+
+    /// Removes the user-defined data attached to this node, and returns it.
+    #[inline]
+    pub fn take_user_data(&mut self) -> Option<Box<dyn Any>> {
+        self.user_data.take()
     }
-    */
 
     // FIXME: for all those set_stuff, would it be more per formant to add a special case for when
     // we are on a leaf? (to avoid the call to a closure required by the apply_to_*).
@@ -269,6 +390,21 @@ impl PlanarSceneNodeData {
         self.visible = visible;
     }
 
+    /// The render order of this node relative to its siblings.
+    #[inline]
+    pub fn render_order(&self) -> i32 {
+        self.render_order
+    }
+
+    /// Sets the render order of this node relative to its siblings.
+    ///
+    /// Siblings with a greater render order are drawn later, i.e., on top of the others.
+    /// Siblings with equal render orders are drawn in insertion order. Defaults to `0`.
+    #[inline]
+    pub fn set_render_order(&mut self, order: i32) {
+        self.render_order = order;
+    }
+
     /// Sets the color of the objects contained by this node and its children.
     ///
     /// Colors components must be on the range `[0.0, 1.0]`.
@@ -325,6 +461,13 @@ impl PlanarSceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.set_texture(texture.clone()))
     }
 
+    /// Sets the normalized rectangle of the texture displayed on the objects contained by this
+    /// node and its children.
+    #[inline]
+    pub fn set_texture_rect(&mut self, min: Point2<f32>, max: Point2<f32>) {
+        self.apply_to_objects_mut(&mut |o| o.set_texture_rect(min, max))
+    }
+
     /// Applies a closure to each object contained by this node and its children.
     #[inline]
     pub fn apply_to_objects_mut<F: FnMut(&mut PlanarObject)>(&mut self, f: &mut F) {
@@ -550,10 +693,13 @@ impl PlanarSceneNode {
             world_transform: local_transform,
             world_scale: local_scale,
             visible: true,
+            render_order: 0,
             up_to_date: false,
             children: Vec::new(),
             object,
+            text: None,
             parent: None,
+            user_data: None,
         };
 
         PlanarSceneNode {
@@ -696,6 +842,64 @@ impl PlanarSceneNode {
         self.add_mesh(mesh, Vector2::repeat(1.0))
     }
 
+    /// Adds a rectangle with rounded corners as a children of this node. The rectangle is
+    /// initially axis-aligned and centered at (0, 0).
+    ///
+    /// The texture coordinates span the whole rectangle, like for `add_rectangle`.
+    ///
+    /// # Arguments
+    /// * `wx` - the rectangle extent along the x axis
+    /// * `wy` - the rectangle extent along the y axis
+    /// * `corner_radius` - the radius of the corners, clamped to half the smallest extent
+    pub fn add_rounded_rectangle(
+        &mut self,
+        wx: f32,
+        wy: f32,
+        corner_radius: f32,
+    ) -> PlanarSceneNode {
+        let r = corner_radius.max(0.0).min(wx.min(wy) / 2.0);
+        let name = format!("rounded_rectangle_{}_{}_{}", wx, wy, r);
+
+        let mesh = PlanarMeshManager::get_global_manager(|mm| {
+            if let Some(geom) = mm.get(&name) {
+                geom
+            } else {
+                let mut vtx = vec![Point2::origin()];
+                let mut ids = Vec::new();
+                let nsamples = 12;
+                let (hx, hy) = (wx / 2.0 - r, wy / 2.0 - r);
+                // The corner centers, counterclockwise from the top-right one.
+                let centers = [(hx, hy), (-hx, hy), (-hx, -hy), (hx, -hy)];
+
+                for (k, (cx, cy)) in centers.iter().enumerate() {
+                    for i in 0..=nsamples {
+                        let ang =
+                            (k as f32 + (i as f32) / (nsamples as f32)) * f32::consts::FRAC_PI_2;
+                        vtx.push(Point2::new(cx + ang.cos() * r, cy + ang.sin() * r));
+                    }
+                }
+
+                let nboundary = vtx.len() as VertexIndex - 1;
+
+                for i in 0..nboundary {
+                    ids.push(Point3::new(0, i + 1, (i + 1) % nboundary + 1));
+                }
+
+                let uvs = vtx
+                    .iter()
+                    .map(|p| Point2::new(p.x / wx + 0.5, 0.5 - p.y / wy))
+                    .collect();
+
+                let rounded_rectangle = PlanarMesh::new(vtx, ids, Some(uvs), false);
+                let mesh = Rc::new(RefCell::new(rounded_rectangle));
+                mm.add(mesh.clone(), &name);
+                mesh
+            }
+        });
+
+        self.add_mesh(mesh, Vector2::repeat(1.0))
+    }
+
     /// Creates and adds a new object using the geometry registered as `geometry_name`.
     pub fn add_geom_with_name(
         &mut self,
@@ -719,6 +923,30 @@ impl PlanarSceneNode {
         self.add_object(scale, na::one(), object)
     }
 
+    /// Adds a piece of text as a children of this node.
+    ///
+    /// The top-left corner of the text is at the origin of the new node, and the text follows
+    /// the world transformation of the node: one text pixel spans one world unit before scaling.
+    /// Texts are drawn on top of the planar scene, with the default font.
+    ///
+    /// # Arguments
+    /// * `text` - the displayed text
+    /// * `scale` - the scale of the text, i.e., its height in text pixels
+    /// * `color` - the color of the text
+    pub fn add_text(&mut self, text: String, scale: f32, color: Point3<f32>) -> PlanarSceneNode {
+        let node = PlanarSceneNode::new_empty();
+        node.data.borrow_mut().text = Some(PlanarText {
+            text,
+            scale,
+            color,
+            font: Font::default(),
+        });
+
+        self.add_child(node.clone());
+
+        node
+    }
+
     /// Creates and adds a new object to this node children using a convex polyline
     pub fn add_convex_polygon(
         &mut self,
@@ -770,6 +998,35 @@ impl PlanarSceneNode {
         self.data_mut().render(camera)
     }
 
+    /// Attaches user-defined data to this node, replacing the previous one.
+    ///
+    /// This is typically used to associate an application-specific identifier or metadata to
+    /// a node, e.g., to find what was picked in an editor.
+    #[inline]
+    pub fn set_user_data(&mut self, user_data: Box<dyn Any>) {
+        self.data_mut().set_user_data(user_data)
+    }
+
+    /// The user-defined data attached to this node, if any.
+    ///
+    /// Use dynamic typing capabilities of the `Any` type to recover the actual data.
+    #[inline]
+    pub fn user_data(&self) -> Option<Ref<dyn Any>> {
+        let data = self.data();
+
+        if data.user_data.is_some() {
+            Some(Ref::map(data, |d| d.user_data().unwrap()))
+        } else {
+            None
+        }
+    }
+
+    /// Removes the user-defined data attached to this node, and returns it.
+    #[inline]
+    pub fn take_user_data(&mut self) -> Option<Box<dyn Any>> {
+        self.data_mut().take_user_data()
+    }
+
     /// Sets the material of the objects contained by this node and its children.
     #[inline]
     pub fn set_material(&mut self, material: Rc<RefCell<Box<dyn PlanarMaterial + 'static>>>) {
@@ -877,6 +1134,21 @@ impl PlanarSceneNode {
         self.data_mut().set_visible(visible)
     }
 
+    /// The render order of this node relative to its siblings.
+    #[inline]
+    pub fn render_order(&self) -> i32 {
+        self.data().render_order()
+    }
+
+    /// Sets the render order of this node relative to its siblings.
+    ///
+    /// Siblings with a greater render order are drawn later, i.e., on top of the others.
+    /// Siblings with equal render orders are drawn in insertion order. Defaults to `0`.
+    #[inline]
+    pub fn set_render_order(&mut self, order: i32) {
+        self.data_mut().set_render_order(order)
+    }
+
     /// Sets the color of the objects contained by this node and its children.
     ///
     /// Colors components must be on the range `[0.0, 1.0]`.
@@ -919,6 +1191,18 @@ impl PlanarSceneNode {
         self.data_mut().set_texture(texture)
     }
 
+    /// Sets the normalized rectangle of the texture displayed on the objects contained by this
+    /// node and its children.
+    ///
+    /// This is typically used to display a single tile of a sprite sheet. The texture
+    /// coordinates of the meshes are mapped into the rectangle going from `min` to `max` at
+    /// render time, so meshes shared with other nodes are not affected. Use `(0, 0)` and
+    /// `(1, 1)` to display the whole texture again.
+    #[inline]
+    pub fn set_texture_rect(&mut self, min: Point2<f32>, max: Point2<f32>) {
+        self.data_mut().set_texture_rect(min, max)
+    }
+
     /// Sets the local scaling factors of the object.
     #[inline]
     pub fn set_local_scale(&mut self, sx: f32, sy: f32) {
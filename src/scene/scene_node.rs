@@ -1,17 +1,28 @@
 use crate::camera::Camera;
+use crate::context::Context;
 use crate::light::Light;
+use crate::loader::mtl::MtlMaterial;
+use crate::loader::ply;
 use crate::resource::vertex_index::VertexIndex;
-use crate::resource::{Material, MaterialManager, Mesh, MeshManager, Texture, TextureManager};
-use crate::scene::Object;
+use crate::resource::{
+    EnvironmentMap, Material, MaterialManager, Mesh, MeshManager, Texture, TextureManager,
+};
+use crate::scene::{custom_render_priority_used, BlendMode, CullMode, Object};
+use crate::verify;
 use na;
-use na::{Isometry3, Point2, Point3, Translation3, UnitQuaternion, Vector3};
+use na::{Isometry3, Point2, Point3, Translation3, UnitQuaternion, Vector2, Vector3};
 use ncollide3d::procedural;
 use ncollide3d::procedural::TriMesh;
+use std::any::Any;
 use std::cell::{Ref, RefCell, RefMut};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::mem;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::rc::Weak;
+use std::sync::Arc;
 
 // XXX: once something like `fn foo(self: Rc<RefCell<SceneNode>>)` is allowed, this extra struct
 // will not be needed any more.
@@ -26,6 +37,7 @@ pub struct SceneNodeData {
     children: Vec<SceneNode>,
     object: Option<Object>,
     parent: Option<Weak<RefCell<SceneNodeData>>>,
+    user_data: Option<Box<dyn Any>>,
 }
 
 /// A node of the scene graph.
@@ -36,6 +48,26 @@ pub struct SceneNode {
     data: Rc<RefCell<SceneNodeData>>,
 }
 
+/// A stable identifier of a scene node, as given by `SceneNode::id`.
+///
+/// All the clones of a `SceneNode` share the same identifier. It remains valid as long as the node
+/// is alive, but may be reused by another node once it has been dropped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// Statistics about a scene graph, as given by `SceneNode::stats`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SceneStats {
+    /// The number of nodes, including the root.
+    pub nodes: usize,
+    /// The number of nodes containing an object.
+    pub objects: usize,
+    /// The total number of triangles of the objects.
+    pub triangles: usize,
+    /// The total number of vertices of the objects.
+    pub vertices: usize,
+}
+
 impl SceneNodeData {
     // XXX: Because `node.borrow_mut().parent = Some(self.data.downgrade())`
     // causes a weird compiler error:
@@ -82,20 +114,140 @@ impl SceneNodeData {
     }
 
     /// Render the scene graph rooted by this node.
+    ///
+    /// The opaque objects are rendered first. The transparent objects are then blended on top of
+    /// them, from the farthest to the closest.
     pub fn render(&mut self, pass: usize, camera: &mut dyn Camera, light: &Light) {
-        if self.visible {
-            self.do_render(&na::one(), &Vector3::from_element(1.0), pass, camera, light)
+        self.render_opaque(pass, camera, light);
+
+        let ctxt = Context::get();
+        verify!(ctxt.enable(Context::BLEND));
+        verify!(ctxt.depth_mask(false));
+
+        self.render_transparent(pass, camera, light, true);
+
+        verify!(ctxt.depth_mask(true));
+        verify!(ctxt.disable(Context::BLEND));
+    }
+
+    /// Renders the opaque objects of the scene graph rooted by this node.
+    pub(crate) fn render_opaque(&mut self, pass: usize, camera: &mut dyn Camera, light: &Light) {
+        if !self.visible {
+            return;
+        }
+
+        // Almost every scene only uses the default priority.
+        if !custom_render_priority_used() {
+            self.do_render(
+                &na::one(),
+                &Vector3::from_element(1.0),
+                0,
+                pass,
+                camera,
+                light,
+            );
+            return;
+        }
+
+        let mut priorities = Vec::new();
+        self.apply_to_objects(&mut |o| priorities.push(o.data().render_priority()));
+        priorities.sort_unstable();
+        priorities.dedup();
+
+        for priority in priorities {
+            self.do_render(
+                &na::one(),
+                &Vector3::from_element(1.0),
+                priority,
+                pass,
+                camera,
+                light,
+            )
         }
     }
 
-    fn do_render(
+    /// Renders the transparent objects of the scene graph rooted by this node.
+    ///
+    /// The world transformations must be up to date, i.e., `render_opaque` must have been called
+    /// first. If `sort` is `true`, the objects with the same rendering priority are rendered from
+    /// the farthest to the closest to the camera, with the blending function of their blend mode.
+    /// Otherwise, the blending state is left unchanged.
+    pub(crate) fn render_transparent(
         &mut self,
-        transform: &Isometry3<f32>,
-        scale: &Vector3<f32>,
         pass: usize,
         camera: &mut dyn Camera,
         light: &Light,
+        sort: bool,
     ) {
+        if !self.visible {
+            return;
+        }
+
+        let eye = camera.eye();
+        let mut objects = Vec::new();
+        self.collect_transparent(None, &eye, &mut objects);
+
+        if sort {
+            objects.sort_by(|a, b| {
+                a.0.cmp(&b.0)
+                    .then(b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal))
+            });
+        } else {
+            objects.sort_by_key(|o| o.0);
+        }
+
+        for (_, _, node) in objects {
+            match node {
+                Some(node) => {
+                    let data = node.data();
+
+                    if let Some(o) = &data.object {
+                        if sort {
+                            set_blend_func(o.data().blend_mode());
+                        }
+
+                        o.render(
+                            &data.world_transform,
+                            &data.world_scale,
+                            pass,
+                            camera,
+                            light,
+                        )
+                    }
+                }
+                None => {
+                    if let Some(o) = &self.object {
+                        if sort {
+                            set_blend_func(o.data().blend_mode());
+                        }
+
+                        o.render(
+                            &self.world_transform,
+                            &self.world_scale,
+                            pass,
+                            camera,
+                            light,
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies a closure to each visible object of the scene graph rooted by this node, with its
+    /// world transformation and scale.
+    ///
+    /// The world transformations are updated like during rendering.
+    pub(crate) fn apply_to_visible_objects_with_transform(
+        &mut self,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        f: &mut dyn FnMut(&Object, &Isometry3<f32>, &Vector3<f32>),
+    ) {
+        if !self.visible {
+            return;
+        }
+
         if !self.up_to_date {
             self.up_to_date = true;
             self.world_transform = *transform * self.local_transform;
@@ -103,14 +255,72 @@ impl SceneNodeData {
         }
 
         if let Some(ref o) = self.object {
-            o.render(
+            f(o, &self.world_transform, &self.world_scale)
+        }
+
+        for c in self.children.iter_mut() {
+            c.data_mut().apply_to_visible_objects_with_transform(
                 &self.world_transform,
                 &self.world_scale,
-                pass,
-                camera,
-                light,
+                f,
             )
         }
+    }
+
+    // Collects the transparent objects with their rendering priority and distance to `eye`.
+    // The node is `None` for `self`.
+    fn collect_transparent(
+        &self,
+        node: Option<&SceneNode>,
+        eye: &Point3<f32>,
+        out: &mut Vec<(i32, f32, Option<SceneNode>)>,
+    ) {
+        if let Some(ref o) = self.object {
+            if o.is_transparent() {
+                let dist = na::distance_squared(
+                    &Point3::from(self.world_transform.translation.vector),
+                    eye,
+                );
+                out.push((o.data().render_priority(), dist, node.cloned()));
+            }
+        }
+
+        for c in self.children.iter() {
+            let bc = c.data();
+
+            if bc.visible {
+                bc.collect_transparent(Some(c), eye, out)
+            }
+        }
+    }
+
+    // Renders the opaque objects with the given render priority.
+    fn do_render(
+        &mut self,
+        transform: &Isometry3<f32>,
+        scale: &Vector3<f32>,
+        priority: i32,
+        pass: usize,
+        camera: &mut dyn Camera,
+        light: &Light,
+    ) {
+        if !self.up_to_date {
+            self.up_to_date = true;
+            self.world_transform = *transform * self.local_transform;
+            self.world_scale = scale.component_mul(&self.local_scale);
+        }
+
+        if let Some(ref o) = self.object {
+            if o.data().render_priority() == priority && !o.is_transparent() {
+                o.render(
+                    &self.world_transform,
+                    &self.world_scale,
+                    pass,
+                    camera,
+                    light,
+                )
+            }
+        }
 
         for c in self.children.iter_mut() {
             let mut bc = c.data_mut();
@@ -118,6 +328,7 @@ impl SceneNodeData {
                 bc.do_render(
                     &self.world_transform,
                     &self.world_scale,
+                    priority,
                     pass,
                     camera,
                     light,
@@ -158,14 +369,68 @@ impl SceneNodeData {
             .expect("This scene node does not contain an Object.")
     }
 
-    ///////////////////~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ HERE
-    /* FIXME: the ~Any is kind of problematic here…
-    /// Attaches user-defined data to the objects contained by this node and its children.
+    /// Replaces the mesh of the object contained by this node.
+    ///
+    /// # Failure
+    /// Fails of this node does not contains an object.
+    #[inline]
+    pub fn set_mesh(&mut self, mesh: Rc<RefCell<Mesh>>) {
+        self.get_object_mut().set_mesh(mesh)
+    }
+
+    /// Replaces the mesh of the object contained by this node by a smoother one, with
+    /// `iterations` steps of Loop subdivision.
+    ///
+    /// See `procedural::utils::subdivide_loop` for details. The original mesh is left unchanged
+    /// so other objects sharing it are not affected.
+    ///
+    /// # Failure
+    /// Fails of this node does not contains an object, or if the data of its mesh is not
+    /// available on the CPU.
+    pub fn subdivide(&mut self, iterations: u32) {
+        let trimesh = self
+            .get_object_mut()
+            .mesh()
+            .borrow()
+            .to_trimesh()
+            .expect("The mesh data is not available on the CPU.");
+        let trimesh = crate::procedural::utils::subdivide_loop(&trimesh, iterations);
+
+        self.set_mesh(Rc::new(RefCell::new(Mesh::from_trimesh(trimesh, false))))
+    }
+
+    /// Attaches user-defined data to this node, replacing the previous one.
+    ///
+    /// Unlike the user data of objects, this is stored on the node itself so it is available for
+    /// group nodes as well.
+    #[inline]
+    pub fn set_user_data(&mut self, user_data: Box<dyn Any>) {
+        self.user_data = Some(user_data)
+    }
+
+    /// The user-defined data attached to this node, if any.
+    ///
+    /// Use dynamic typing capabilities of the `Any` type to recover the actual data.
     #[inline]
-    pub fn set_user_data(&mut self, user_data: ~Any) {
-        self.apply_to_objects_mut(&mut |o| o.set_user_data(user_data))
+    pub fn user_data(&self) -> Option<&dyn Any> {
+        self.user_data.as_deref()
+    }
+
+    /// Removes the user-defined data attached to this node, and returns it.
+    #[inline]
+    pub fn take_user_data(&mut self) -> Option<Box<dyn Any>> {
+        self.user_data.take()
+    }
+
+    /// The material of the first object contained by this node or its children.
+    ///
+    /// Returns `None` if neither this node nor its children contain an object.
+    pub fn material(&self) -> Option<Rc<RefCell<Box<dyn Material + 'static>>>> {
+        match self.object {
+            Some(ref o) => Some(o.material()),
+            None => self.children.iter().find_map(|c| c.data().material()),
+        }
     }
-    */
 
     // FIXME: for all those set_stuff, would it be more per formant to add a special case for when
     // we are on a leaf? (to avoid the call to a closure required by the apply_to_*).
@@ -201,12 +466,47 @@ impl SceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.set_lines_color(color))
     }
 
+    /// Draws the edges of the objects contained by this node and its children on top of their
+    /// surface.
+    #[inline]
+    pub fn set_wireframe_overlay(&mut self, color: Option<Point3<f32>>, width: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_wireframe_overlay(color, width))
+    }
+
+    /// Sets the color of the back faces of the objects contained by this node and its children.
+    ///
+    /// Set to `None` to shade back faces with the same color as front faces.
+    #[inline]
+    pub fn set_backface_color(&mut self, color: Option<Point3<f32>>) {
+        self.apply_to_objects_mut(&mut |o| o.set_backface_color(color))
+    }
+
     /// Sets the size of the points drawn for the objects contained by this node and its children.
     #[inline]
     pub fn set_points_size(&mut self, size: f32) {
         self.apply_to_objects_mut(&mut |o| o.set_points_size(size))
     }
 
+    /// Sets the outline drawn around the objects contained by this node and its children.
+    ///
+    /// Set the color to `None` to disable the outline.
+    #[inline]
+    pub fn set_outline(&mut self, color: Option<Point3<f32>>, width: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_outline(color, width))
+    }
+
+    /// Sets the environment map reflected by the objects contained by this node and its children.
+    #[inline]
+    pub fn set_environment_map(&mut self, map: Option<Rc<EnvironmentMap>>) {
+        self.apply_to_objects_mut(&mut |o| o.set_environment_map(map.clone()))
+    }
+
+    /// Sets the reflectivity of the objects contained by this node and its children.
+    #[inline]
+    pub fn set_reflectivity(&mut self, reflectivity: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_reflectivity(reflectivity))
+    }
+
     /// Activates or deactivates the rendering of the surfaces of the objects contained by this node and its
     /// children.
     #[inline]
@@ -221,6 +521,37 @@ impl SceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.enable_backface_culling(active))
     }
 
+    /// Sets the faces discarded during the rendering of the objects contained by this node and
+    /// its children.
+    #[inline]
+    pub fn set_cull_mode(&mut self, mode: CullMode) {
+        self.apply_to_objects_mut(&mut |o| o.set_cull_mode(mode))
+    }
+
+    /// Sets the blend mode of the objects contained by this node and its children.
+    #[inline]
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.apply_to_objects_mut(&mut |o| o.set_blend_mode(mode))
+    }
+
+    /// Enables or disables depth testing for the objects contained by this node and its children.
+    #[inline]
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        self.apply_to_objects_mut(&mut |o| o.set_depth_test(enabled))
+    }
+
+    /// Sets the rendering priority of the objects contained by this node and its children.
+    #[inline]
+    pub fn set_render_priority(&mut self, priority: i32) {
+        self.apply_to_objects_mut(&mut |o| o.set_render_priority(priority))
+    }
+
+    /// Sets the opacity of the objects contained by this node and its children.
+    #[inline]
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.apply_to_objects_mut(&mut |o| o.set_alpha(alpha))
+    }
+
     /// Mutably accesses the vertices of the objects contained by this node and its children.
     ///
     /// The provided closure is called once per object.
@@ -229,6 +560,15 @@ impl SceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.modify_vertices(f))
     }
 
+    /// Replaces the vertices starting at index `start` of the objects contained by this node
+    /// and its children by `coords`.
+    ///
+    /// See `Mesh::update_vertices_range` for details.
+    #[inline]
+    pub fn update_vertices_range(&mut self, start: usize, coords: &[Point3<f32>]) {
+        self.apply_to_objects_mut(&mut |o| o.update_vertices_range(start, coords))
+    }
+
     /// Accesses the vertices of the objects contained by this node and its children.
     ///
     /// The provided closure is called once per object.
@@ -237,6 +577,18 @@ impl SceneNodeData {
         self.apply_to_objects(&mut |o| o.read_vertices(f))
     }
 
+    /// Mutably accesses the points and colors of the point clouds contained by this node and its
+    /// children.
+    ///
+    /// The provided closure is called once per object having vertex colors.
+    #[inline(always)]
+    pub fn modify_points<F: FnMut(&mut Vec<Point3<f32>>, &mut Vec<Point3<f32>>)>(
+        &mut self,
+        f: &mut F,
+    ) {
+        self.apply_to_objects_mut(&mut |o| o.modify_points(f))
+    }
+
     /// Recomputes the normals of the meshes of the objects contained by this node and its
     /// children.
     #[inline]
@@ -285,6 +637,39 @@ impl SceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.modify_uvs(f))
     }
 
+    /// Rescales and offsets the texture coordinates of the objects contained by this node and
+    /// its children.
+    ///
+    /// Each texture coordinate `uv` is replaced by `offset + scale * uv` (component-wise). This
+    /// is useful to map a primitive onto a sub-region of a texture atlas. Successive calls
+    /// compose.
+    ///
+    /// Objects sharing their texture coordinates with other objects, like the primitives of the
+    /// `MeshManager`, are given their own copy first so the other objects are not affected.
+    /// Objects whose texture coordinates are not available on the CPU are left unchanged.
+    pub fn remap_uvs(&mut self, offset: Vector2<f32>, scale: Vector2<f32>) {
+        self.apply_to_objects_mut(&mut |o| {
+            let shared =
+                Rc::strong_count(o.mesh()) > 1 || Arc::strong_count(o.mesh().borrow().uvs()) > 1;
+
+            if shared {
+                let mesh = o.mesh().borrow().with_own_uvs();
+
+                match mesh {
+                    Some(mesh) => o.set_mesh(Rc::new(RefCell::new(mesh))),
+                    // Never modify the texture coordinates of the other objects.
+                    None => return,
+                }
+            }
+
+            o.modify_uvs(&mut |uvs| {
+                for uv in uvs.iter_mut() {
+                    *uv = Point2::from(offset + uv.coords.component_mul(&scale));
+                }
+            })
+        })
+    }
+
     /// Accesses the texture coordinates of the objects contained by this node and its children.
     ///
     /// The provided closure is called once per object.
@@ -349,13 +734,16 @@ impl SceneNodeData {
     /// The texture must already have been registered as `name`.
     #[inline]
     pub fn set_texture_with_name(&mut self, name: &str) {
-        let texture = TextureManager::get_global_manager(|tm| {
-            tm.get(name).unwrap_or_else(|| {
+        let (texture, (_, layer_height), layer_count) = TextureManager::get_global_manager(|tm| {
+            let (texture, size) = tm.get_with_size(name).unwrap_or_else(|| {
                 panic!("Invalid attempt to use the unregistered texture: {}", name)
-            })
+            });
+            (texture, size, tm.layer_count(name))
         });
 
-        self.set_texture(texture)
+        self.apply_to_objects_mut(&mut |o| {
+            o.set_texture_array(texture.clone(), layer_count, layer_height)
+        })
     }
 
     /// Sets the texture of the objects contained by this node and its children.
@@ -363,6 +751,12 @@ impl SceneNodeData {
         self.apply_to_objects_mut(&mut |o| o.set_texture(texture.clone()))
     }
 
+    /// Selects the layer sampled by the objects contained by this node and its children, if
+    /// their texture is a texture array.
+    pub fn set_texture_layer(&mut self, layer: u32) {
+        self.apply_to_objects_mut(&mut |o| o.set_texture_layer(layer))
+    }
+
     /// Applies a closure to each object contained by this node and its children.
     #[inline]
     pub fn apply_to_objects_mut<F: FnMut(&mut Object)>(&mut self, f: &mut F) {
@@ -387,7 +781,61 @@ impl SceneNodeData {
         }
     }
 
-    // FIXME: add folding?
+    /// Folds over each object contained by this node and its children.
+    #[inline]
+    pub fn fold_objects<T, F: FnMut(T, &Object) -> T>(&self, init: T, f: &mut F) -> T {
+        let mut acc = match self.object {
+            Some(ref o) => f(init, o),
+            None => init,
+        };
+
+        for c in self.children.iter() {
+            acc = c.data().fold_objects(acc, f)
+        }
+
+        acc
+    }
+
+    /// The number of triangles of the objects contained by this node and its children.
+    ///
+    /// Hidden objects are counted too.
+    pub fn triangle_count(&self) -> usize {
+        self.fold_objects(0, &mut |n, o| n + o.mesh().borrow().num_triangles())
+    }
+
+    /// The number of vertices of the objects contained by this node and its children.
+    ///
+    /// Hidden objects are counted too.
+    pub fn vertex_count(&self) -> usize {
+        self.fold_objects(0, &mut |n, o| n + o.mesh().borrow().num_vertices())
+    }
+
+    /// Statistics about the scene graph rooted by this node.
+    ///
+    /// Hidden nodes and objects are counted too.
+    pub fn stats(&self) -> SceneStats {
+        let mut stats = SceneStats {
+            nodes: 1,
+            ..SceneStats::default()
+        };
+
+        if let Some(ref o) = self.object {
+            let mesh = o.mesh().borrow();
+            stats.objects += 1;
+            stats.triangles += mesh.num_triangles();
+            stats.vertices += mesh.num_vertices();
+        }
+
+        for c in self.children.iter() {
+            let child = c.data().stats();
+            stats.nodes += child.nodes;
+            stats.objects += child.objects;
+            stats.triangles += child.triangles;
+            stats.vertices += child.vertices;
+        }
+
+        stats
+    }
 
     /// Sets the local scaling factors of the object.
     #[inline]
@@ -411,6 +859,24 @@ impl SceneNodeData {
         self.local_transform = Isometry3::face_towards(eye, at, up)
     }
 
+    /// Rotates the object in place such that its `z` axis is oriented toward `target`.
+    ///
+    /// The translation of the object is kept. `target` is expressed in the same frame as the
+    /// local transformation of this node. Nothing happens if `target` coincides with the object
+    /// position.
+    #[inline]
+    pub fn look_at(&mut self, target: &Point3<f32>, up: &Vector3<f32>) {
+        let dir = target.coords - self.local_transform.translation.vector;
+
+        if dir.norm_squared() == 0.0 {
+            return;
+        }
+
+        self.invalidate();
+        // FIXME: multiply by the parent's world transform?
+        self.local_transform.rotation = UnitQuaternion::face_towards(&dir, up)
+    }
+
     /// This node local transformation.
     #[inline]
     pub fn local_transformation(&self) -> Isometry3<f32> {
@@ -586,6 +1052,38 @@ impl SceneNodeData {
     }
 }
 
+// Sets the blending function used to draw a transparent object with the given blend mode.
+fn set_blend_func(mode: BlendMode) {
+    let ctxt = Context::get();
+
+    match mode {
+        BlendMode::Alpha | BlendMode::None => verify!(ctxt.blend_func_separate(
+            Context::SRC_ALPHA,
+            Context::ONE_MINUS_SRC_ALPHA,
+            Context::ONE,
+            Context::ONE_MINUS_SRC_ALPHA,
+        )),
+        BlendMode::Additive => verify!(ctxt.blend_func_separate(
+            Context::SRC_ALPHA,
+            Context::ONE,
+            Context::ZERO,
+            Context::ONE,
+        )),
+        BlendMode::Multiply => verify!(ctxt.blend_func_separate(
+            Context::DST_COLOR,
+            Context::ZERO,
+            Context::ZERO,
+            Context::ONE,
+        )),
+        BlendMode::PremultipliedAlpha => verify!(ctxt.blend_func_separate(
+            Context::ONE,
+            Context::ONE_MINUS_SRC_ALPHA,
+            Context::ONE,
+            Context::ONE_MINUS_SRC_ALPHA,
+        )),
+    }
+}
+
 impl Default for SceneNode {
     fn default() -> SceneNode {
         SceneNode::new_empty()
@@ -609,6 +1107,7 @@ impl SceneNode {
             children: Vec::new(),
             object,
             parent: None,
+            user_data: None,
         };
 
         SceneNode {
@@ -638,6 +1137,44 @@ impl SceneNode {
         self.data.borrow_mut()
     }
 
+    /// The stable identifier of this node.
+    #[inline]
+    pub fn id(&self) -> NodeId {
+        NodeId(Rc::as_ptr(&self.data) as usize)
+    }
+
+    /// Sets the local transformations of several nodes of the subtree rooted at this node.
+    ///
+    /// This is equivalent to calling `set_local_transformation` on each node identified by the
+    /// given `NodeId`s, but the world transformations of the subtree are invalidated in a single
+    /// traversal. This is useful to apply a pose to a hierarchy of nodes, e.g., the bones of an
+    /// animated rig. Identifiers of nodes outside of this subtree are ignored.
+    pub fn set_local_transformations(&mut self, transforms: &[(NodeId, Isometry3<f32>)]) {
+        let transforms: HashMap<NodeId, Isometry3<f32>> = transforms.iter().cloned().collect();
+        self.set_local_transformations_rec(&transforms, false)
+    }
+
+    fn set_local_transformations_rec(
+        &self,
+        transforms: &HashMap<NodeId, Isometry3<f32>>,
+        mut invalidate: bool,
+    ) {
+        let mut data = self.data.borrow_mut();
+
+        if let Some(t) = transforms.get(&self.id()) {
+            data.local_transform = *t;
+            invalidate = true;
+        }
+
+        if invalidate {
+            data.up_to_date = false;
+        }
+
+        for child in data.children.iter() {
+            child.set_local_transformations_rec(transforms, invalidate)
+        }
+    }
+
     /*
      *
      * Methods to add objects.
@@ -742,6 +1279,98 @@ impl SceneNode {
         )
     }
 
+    /// Adds a torus as a children of this node. The torus is initially centered at (0, 0, 0) and
+    /// lies on the `xz` plane.
+    ///
+    /// # Arguments
+    /// * `major_r` - the distance from the torus center to the center of its tube
+    /// * `minor_r` - the radius of the tube
+    pub fn add_torus(&mut self, major_r: f32, minor_r: f32) -> SceneNode {
+        self.add_trimesh(
+            crate::procedural::torus(major_r, minor_r, 50, 25),
+            Vector3::from_element(1.0),
+        )
+    }
+
+    /// Adds a prism as a children of this node, obtained by extruding a closed 2D outline along
+    /// the `y` axis.
+    ///
+    /// See `procedural::extrude` for details about how the outline is placed.
+    ///
+    /// # Arguments
+    /// * `outline` - the closed polyline to extrude
+    /// * `height` - the extrusion length along the `y` axis
+    pub fn add_extrusion(&mut self, outline: &[Point2<f32>], height: f32) -> SceneNode {
+        self.add_trimesh(
+            crate::procedural::extrude(outline, height, true),
+            Vector3::from_element(1.0),
+        )
+    }
+
+    /// Adds a double-sided flat disk as a children of this node. The disk is initially centered
+    /// at (0, 0, 0) and lies on the `xz` plane.
+    ///
+    /// # Arguments
+    /// * `r` - the disk radius
+    /// * `segments` - the number of subdivisions of the disk boundary
+    pub fn add_disk(&mut self, r: f32, segments: u32) -> SceneNode {
+        let mut node = self.add_trimesh(
+            crate::procedural::disk(r, segments),
+            Vector3::from_element(1.0),
+        );
+        node.enable_backface_culling(false);
+
+        node
+    }
+
+    /// Adds a geodesic sphere as a children of this node. The sphere is initially centered at
+    /// (0, 0, 0).
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    /// * `subdivisions` - the number of subdivisions of the initial icosahedron
+    pub fn add_icosphere(&mut self, r: f32, subdivisions: u32) -> SceneNode {
+        self.add_trimesh(
+            crate::procedural::icosphere(r, subdivisions),
+            Vector3::from_element(1.0),
+        )
+    }
+
+    /// Adds a double-sided part of a sphere as a children of this node, e.g., a dome. The sphere
+    /// is initially centered at (0, 0, 0).
+    ///
+    /// See `procedural::sphere_sector` for details about the angles.
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    /// * `phi_range` - the range of polar angles from the `+y` axis, in radians
+    /// * `theta_range` - the range of azimuthal angles around the `y` axis, in radians
+    pub fn add_sphere_sector(
+        &mut self,
+        r: f32,
+        phi_range: Range<f32>,
+        theta_range: Range<f32>,
+    ) -> SceneNode {
+        // Keep roughly the resolution of the full sphere.
+        let rings = (25.0 * (phi_range.end - phi_range.start).abs() / std::f32::consts::PI).ceil();
+        let sectors = (50.0 * (theta_range.end - theta_range.start).abs()
+            / (2.0 * std::f32::consts::PI))
+            .ceil();
+        let mut node = self.add_trimesh(
+            crate::procedural::sphere_sector(
+                r,
+                phi_range,
+                theta_range,
+                (rings as u32).max(1),
+                (sectors as u32).max(1),
+            ),
+            Vector3::from_element(1.0),
+        );
+        node.enable_backface_culling(false);
+
+        node
+    }
+
     /// Adds a double-sided quad to this node children. The quad is initially centered at (0, 0,
     /// 0). The quad itself is composed of a user-defined number of triangles regularly spaced on a
     /// grid. This is the main way to draw height maps.
@@ -790,13 +1419,97 @@ impl SceneNode {
 
     /// Creates and adds a new object to this node children using a mesh.
     pub fn add_mesh(&mut self, mesh: Rc<RefCell<Mesh>>, scale: Vector3<f32>) -> SceneNode {
-        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
         let mat = MaterialManager::get_global_manager(|mm| mm.get_default());
-        let object = Object::new(mesh, 1.0, 1.0, 1.0, tex, mat);
+
+        self.add_mesh_with_material(mesh, scale, mat)
+    }
+
+    /// Creates and adds a new object to this node children using a mesh and a custom material.
+    ///
+    /// This is equivalent to `add_mesh` followed by `set_material`, without ever using the
+    /// default material.
+    pub fn add_mesh_with_material(
+        &mut self,
+        mesh: Rc<RefCell<Mesh>>,
+        scale: Vector3<f32>,
+        material: Rc<RefCell<Box<dyn Material + 'static>>>,
+    ) -> SceneNode {
+        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
+        let object = Object::new(mesh, 1.0, 1.0, 1.0, tex, material);
 
         self.add_object(scale, na::one(), object)
     }
 
+    /// Creates and adds a new point cloud as a children of this node.
+    ///
+    /// The points are uploaded once to the GPU and drawn with the `point_cloud` material. Use
+    /// `modify_points` to update them. `points` and `colors` must have the same length.
+    pub fn add_point_cloud(&mut self, points: &[Point3<f32>], colors: &[Point3<f32>]) -> SceneNode {
+        assert_eq!(
+            points.len(),
+            colors.len(),
+            "A point cloud must have exactly one color per point."
+        );
+
+        let mut mesh = Mesh::new(points.to_vec(), Vec::new(), None, None, true);
+        mesh.set_colors(Some(colors.to_vec()));
+
+        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
+        let mat = MaterialManager::get_global_manager(|mm| mm.get("point_cloud"))
+            .expect("The point_cloud material is not registered.");
+        let mut object = Object::new(Rc::new(RefCell::new(mesh)), 1.0, 1.0, 1.0, tex, mat);
+        object.set_points_size(1.0);
+
+        self.add_object(Vector3::repeat(1.0), na::one(), object)
+    }
+
+    /// Creates and adds a new object drawn as line segments as a children of this node.
+    ///
+    /// The segments are given as pairs of indices into `coords`, and drawn with the `wireframe`
+    /// material using the color of the object. Use `set_lines_width` to change their width.
+    pub fn add_wireframe(
+        &mut self,
+        coords: Vec<Point3<f32>>,
+        edges: Vec<Point2<u32>>,
+        scale: Vector3<f32>,
+    ) -> SceneNode {
+        let normals = vec![Vector3::y(); coords.len()];
+        let mut mesh = Mesh::new(coords, Vec::new(), Some(normals), None, false);
+        mesh.set_edges(Some(edges.into_iter().map(na::convert).collect()));
+
+        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
+        let mat = MaterialManager::get_global_manager(|mm| mm.get("wireframe"))
+            .expect("The wireframe material is not registered.");
+        let mut object = Object::new(Rc::new(RefCell::new(mesh)), 1.0, 1.0, 1.0, tex, mat);
+        object.set_lines_width(1.0);
+
+        self.add_object(scale, na::one(), object)
+    }
+
+    /// Adds the edges of a box as a children of this node. The box is initially axis-aligned and
+    /// centered at (0, 0, 0).
+    ///
+    /// Unlike a cube drawn in wireframe mode, the faces diagonals are not drawn.
+    ///
+    /// # Arguments
+    /// * `wx` - the box extent along the x axis
+    /// * `wy` - the box extent along the y axis
+    /// * `wz` - the box extent along the z axis
+    pub fn add_wire_cube(&mut self, wx: f32, wy: f32, wz: f32) -> SceneNode {
+        let (coords, edges) = crate::procedural::wire_cube(1.0, 1.0, 1.0);
+        self.add_wireframe(coords, edges, Vector3::new(wx, wy, wz))
+    }
+
+    /// Adds the meridians and parallels of a sphere as a children of this node. The sphere is
+    /// initially centered at (0, 0, 0).
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    pub fn add_wire_sphere(&mut self, r: f32) -> SceneNode {
+        let (coords, edges) = crate::procedural::wire_sphere(1.0, 24, 12);
+        self.add_wireframe(coords, edges, Vector3::repeat(r * 2.0))
+    }
+
     /// Creates and adds a new object using a mesh descriptor.
     pub fn add_trimesh(&mut self, descr: TriMesh<f32>, scale: Vector3<f32>) -> SceneNode {
         self.add_mesh(
@@ -810,64 +1523,129 @@ impl SceneNode {
     /// This will create a new node serving as a root of the scene described by the obj file. This
     /// newly created node is added to this node's children.
     pub fn add_obj(&mut self, path: &Path, mtl_dir: &Path, scale: Vector3<f32>) -> SceneNode {
-        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
-        let mat = MaterialManager::get_global_manager(|mm| mm.get_default());
+        // FIXME: is there some error-handling stuff to do here instead of the `unwrap`.
+        let objs = MeshManager::load_obj(path, mtl_dir, path.to_str().unwrap()).unwrap();
+
+        self.add_obj_meshes(objs, Some(mtl_dir), scale)
+    }
+
+    /// Creates and adds multiple nodes created from the content of an obj file and,
+    /// optionally, of its mtl file.
+    ///
+    /// The `mtllib` statements of the obj file are ignored: its `usemtl` statements refer to the
+    /// materials of `mtl_data`. Since no file is read, the textures referenced by the materials
+    /// must already have been registered with this exact name on the global `TextureManager`;
+    /// unregistered textures are ignored.
+    ///
+    /// As with `add_obj`, the new nodes are added to a new root node if the obj file describes
+    /// more than one object.
+    pub fn add_obj_from_memory(
+        &mut self,
+        obj_data: &str,
+        mtl_data: Option<&str>,
+        scale: Vector3<f32>,
+    ) -> SceneNode {
+        let objs = MeshManager::load_obj_from_memory(obj_data, mtl_data, "obj_from_memory");
 
-        // FIXME: is there some error-handling stuff to do here instead of the `let _`.
-        let result = MeshManager::load_obj(path, mtl_dir, path.to_str().unwrap()).map(|objs| {
-            let mut root;
+        self.add_obj_meshes(objs, None, scale)
+    }
 
-            let self_root = objs.len() == 1;
-            let child_scale;
+    fn add_obj_meshes(
+        &mut self,
+        objs: Vec<(String, Rc<RefCell<Mesh>>, Option<MtlMaterial>)>,
+        mtl_dir: Option<&Path>,
+        scale: Vector3<f32>,
+    ) -> SceneNode {
+        let tex = TextureManager::get_global_manager(|tm| tm.get_default());
+        let mat = MaterialManager::get_global_manager(|mm| mm.get_default());
 
-            if self_root {
-                root = self.clone();
-                child_scale = scale;
-            } else {
-                root = SceneNode::new(scale, na::one(), None);
-                self.add_child(root.clone());
-                child_scale = Vector3::from_element(1.0);
-            }
+        let mut root;
 
-            for (_, mesh, mtl) in objs.into_iter() {
-                let mut object = Object::new(mesh, 1.0, 1.0, 1.0, tex.clone(), mat.clone());
+        let self_root = objs.len() == 1;
+        let child_scale;
 
-                match mtl {
-                    None => {}
-                    Some(mtl) => {
-                        object.set_color(mtl.diffuse.x, mtl.diffuse.y, mtl.diffuse.z);
+        if self_root {
+            root = self.clone();
+            child_scale = scale;
+        } else {
+            root = SceneNode::new(scale, na::one(), None);
+            self.add_child(root.clone());
+            child_scale = Vector3::from_element(1.0);
+        }
 
-                        for t in mtl.diffuse_texture.iter() {
-                            let mut tpath = PathBuf::new();
-                            tpath.push(mtl_dir);
-                            tpath.push(&t[..]);
-                            object.set_texture_from_file(&tpath, tpath.to_str().unwrap())
-                        }
+        for (_, mesh, mtl) in objs.into_iter() {
+            let mut object = Object::new(mesh, 1.0, 1.0, 1.0, tex.clone(), mat.clone());
 
-                        for t in mtl.ambiant_texture.iter() {
-                            let mut tpath = PathBuf::new();
-                            tpath.push(mtl_dir);
-                            tpath.push(&t[..]);
-                            object.set_texture_from_file(&tpath, tpath.to_str().unwrap())
+            match mtl {
+                None => {}
+                Some(mtl) => {
+                    object.set_color(mtl.diffuse.x, mtl.diffuse.y, mtl.diffuse.z);
+
+                    let textures = mtl.diffuse_texture.iter().chain(mtl.ambiant_texture.iter());
+
+                    for t in textures {
+                        match mtl_dir {
+                            Some(mtl_dir) => {
+                                let mut tpath = PathBuf::new();
+                                tpath.push(mtl_dir);
+                                tpath.push(&t[..]);
+                                object.set_texture_from_file(&tpath, tpath.to_str().unwrap())
+                            }
+                            None => {
+                                let registered =
+                                    TextureManager::get_global_manager(|tm| tm.get(t).is_some());
+
+                                if registered {
+                                    object.set_texture_with_name(t)
+                                }
+                            }
                         }
                     }
                 }
-
-                let _ = root.add_object(child_scale, na::one(), object);
             }
 
-            if self_root {
-                root.data()
-                    .children
-                    .last()
-                    .expect("There was nothing on this obj file.")
-                    .clone()
-            } else {
-                root
-            }
-        });
+            let _ = root.add_object(child_scale, na::one(), object);
+        }
+
+        if self_root {
+            root.data()
+                .children
+                .last()
+                .expect("There was nothing on this obj file.")
+                .clone()
+        } else {
+            root
+        }
+    }
 
-        result.unwrap()
+    /// Creates and adds a new node from a PLY file, in the ASCII or binary format.
+    ///
+    /// If the file has faces, it is loaded as a mesh, and its vertex colors (if any) are stored
+    /// in the mesh but ignored by the default material. Otherwise, it is loaded as a point cloud
+    /// drawn with its vertex colors (white if it has none), as with `add_point_cloud`.
+    ///
+    /// Panics if the mesh has more vertices than `VertexIndex` can index, i.e., more than 65535
+    /// vertices unless the `vertex_index_u32` feature is enabled.
+    pub fn add_ply(&mut self, path: &Path, scale: Vector3<f32>) -> SceneNode {
+        let ply = ply::parse_file(path).expect("Unable to load the PLY file.");
+
+        if ply.faces.is_empty() {
+            let colors = ply
+                .colors
+                .unwrap_or_else(|| vec![Point3::new(1.0, 1.0, 1.0); ply.coords.len()]);
+            let mut node = self.add_point_cloud(&ply.coords, &colors);
+            node.set_local_scale(scale.x, scale.y, scale.z);
+            node
+        } else {
+            assert!(
+                ply.coords.len() <= VertexIndex::max_value() as usize,
+                "The PLY mesh has too many vertices: enable the `vertex_index_u32` feature."
+            );
+            let faces = ply.faces.into_iter().map(na::convert).collect();
+            let mut mesh = Mesh::new(ply.coords, faces, ply.normals, ply.uvs, false);
+            mesh.set_colors(ply.colors);
+            self.add_mesh(Rc::new(RefCell::new(mesh)), scale)
+        }
     }
 
     /// Applies a closure to each object contained by this node and its children.
@@ -890,6 +1668,30 @@ impl SceneNode {
         }
     }
 
+    /// Folds over this node and its children, in depth-first order.
+    ///
+    /// For example, this counts the nodes containing an object:
+    ///
+    /// ```no_run
+    /// # use kiss3d::scene::SceneNode;
+    /// # let node = SceneNode::new_empty();
+    /// let num_objects = node.fold(0, |n, node| n + node.data().has_object() as usize);
+    /// ```
+    #[inline]
+    pub fn fold<T, F: FnMut(T, &SceneNode) -> T>(&self, init: T, mut f: F) -> T {
+        self.do_fold(init, &mut f)
+    }
+
+    fn do_fold<T, F: FnMut(T, &SceneNode) -> T>(&self, init: T, f: &mut F) -> T {
+        let mut acc = f(init, self);
+
+        for c in self.data().children.iter() {
+            acc = c.do_fold(acc, f)
+        }
+
+        acc
+    }
+
     //
     //
     // fwd
@@ -901,6 +1703,67 @@ impl SceneNode {
         self.data_mut().render(pass, camera, light)
     }
 
+    /// The number of triangles of the objects contained by this node and its children.
+    ///
+    /// Hidden objects are counted too.
+    #[inline]
+    pub fn triangle_count(&self) -> usize {
+        self.data().triangle_count()
+    }
+
+    /// The number of vertices of the objects contained by this node and its children.
+    ///
+    /// Hidden objects are counted too.
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.data().vertex_count()
+    }
+
+    /// Statistics about the scene graph rooted by this node.
+    ///
+    /// Hidden nodes and objects are counted too.
+    #[inline]
+    pub fn stats(&self) -> SceneStats {
+        self.data().stats()
+    }
+
+    /// Attaches user-defined data to this node, replacing the previous one.
+    ///
+    /// This is typically used to associate an application-specific identifier or metadata to
+    /// a node, e.g., to find what was picked in an editor.
+    #[inline]
+    pub fn set_user_data(&mut self, user_data: Box<dyn Any>) {
+        self.data_mut().set_user_data(user_data)
+    }
+
+    /// The user-defined data attached to this node, if any.
+    ///
+    /// Use dynamic typing capabilities of the `Any` type to recover the actual data.
+    #[inline]
+    pub fn user_data(&self) -> Option<Ref<dyn Any>> {
+        let data = self.data();
+
+        if data.user_data.is_some() {
+            Some(Ref::map(data, |d| d.user_data().unwrap()))
+        } else {
+            None
+        }
+    }
+
+    /// Removes the user-defined data attached to this node, and returns it.
+    #[inline]
+    pub fn take_user_data(&mut self) -> Option<Box<dyn Any>> {
+        self.data_mut().take_user_data()
+    }
+
+    /// The material of the first object contained by this node or its children.
+    ///
+    /// Returns `None` if neither this node nor its children contain an object.
+    #[inline]
+    pub fn material(&self) -> Option<Rc<RefCell<Box<dyn Material + 'static>>>> {
+        self.data().material()
+    }
+
     /// Sets the material of the objects contained by this node and its children.
     #[inline]
     pub fn set_material(&mut self, material: Rc<RefCell<Box<dyn Material + 'static>>>) {
@@ -925,12 +1788,63 @@ impl SceneNode {
         self.data_mut().set_lines_color(color)
     }
 
+    /// Draws the edges of the objects contained by this node and its children on top of their
+    /// shaded surface, with the given color and width.
+    ///
+    /// The surfaces are drawn with a polygon offset so the edges do not z-fight with them. Set
+    /// the color to `None` to disable the overlay. This overrides the lines color and width set
+    /// with `set_lines_color` and `set_lines_width`.
+    #[inline]
+    pub fn set_wireframe_overlay(&mut self, color: Option<Point3<f32>>, width: f32) {
+        self.data_mut().set_wireframe_overlay(color, width)
+    }
+
+    /// Sets the color of the back faces of the objects contained by this node and its children.
+    ///
+    /// This is useful to check the winding of a mesh, or to make both sides of thin surfaces
+    /// distinguishable. Back faces are only visible if backface culling is disabled. Set to
+    /// `None` to shade back faces with the same color as front faces.
+    #[inline]
+    pub fn set_backface_color(&mut self, color: Option<Point3<f32>>) {
+        self.data_mut().set_backface_color(color)
+    }
+
     /// Sets the size of the points drawn for the objects contained by this node and its children.
     #[inline]
     pub fn set_points_size(&mut self, size: f32) {
         self.data_mut().set_points_size(size)
     }
 
+    /// Sets the outline drawn around the objects contained by this node and its children.
+    ///
+    /// This is typically used to highlight a selected object. The outline is drawn with a
+    /// stencil pass, so it requires a stencil buffer on the current render target. The window
+    /// and the targets created with `FramebufferManager::new_render_target` have one.
+    /// Set the color to `None` to disable the outline.
+    #[inline]
+    pub fn set_outline(&mut self, color: Option<Point3<f32>>, width: f32) {
+        self.data_mut().set_outline(color, width)
+    }
+
+    /// Sets the environment map reflected by the objects contained by this node and its children.
+    ///
+    /// The environment is sampled along the view direction reflected by the surface normal, and
+    /// blended with the lit color of the objects according to their reflectivity. Set to `None`
+    /// to disable reflections.
+    #[inline]
+    pub fn set_environment_map(&mut self, map: Option<Rc<EnvironmentMap>>) {
+        self.data_mut().set_environment_map(map)
+    }
+
+    /// Sets the fraction of the color of the objects contained by this node and its children
+    /// replaced by their reflected environment.
+    ///
+    /// The reflectivity is clamped to `[0.0, 1.0]` and defaults to `0.5`.
+    #[inline]
+    pub fn set_reflectivity(&mut self, reflectivity: f32) {
+        self.data_mut().set_reflectivity(reflectivity)
+    }
+
     /// Activates or deactivates the rendering of the surfaces of the objects contained by this node and its
     /// children.
     #[inline]
@@ -945,6 +1859,55 @@ impl SceneNode {
         self.data_mut().enable_backface_culling(active)
     }
 
+    /// Sets the faces discarded during the rendering of the objects contained by this node and
+    /// its children.
+    ///
+    /// This generalizes `enable_backface_culling`: `CullMode::Front` discards front faces
+    /// instead, which is useful to look at the inside of closed meshes.
+    #[inline]
+    pub fn set_cull_mode(&mut self, mode: CullMode) {
+        self.data_mut().set_cull_mode(mode)
+    }
+
+    /// Sets how the color of the objects contained by this node and its children is combined
+    /// with the color of what is behind them.
+    ///
+    /// `BlendMode::Additive` is useful for glows and particles, and `BlendMode::Multiply` for
+    /// stains or fake shadows. Defaults to `BlendMode::Alpha`.
+    #[inline]
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.data_mut().set_blend_mode(mode)
+    }
+
+    /// Enables or disables depth testing for the objects contained by this node and its children.
+    ///
+    /// Objects without depth testing are drawn over everything rendered before them, which is
+    /// useful for overlays. Combine this with `set_render_priority` to draw them last.
+    #[inline]
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        self.data_mut().set_depth_test(enabled)
+    }
+
+    /// Sets the rendering priority of the objects contained by this node and its children.
+    ///
+    /// Objects with a higher priority are drawn after objects with a lower priority, regardless
+    /// of their position in the scene graph. Objects with the same priority are drawn in the
+    /// scene graph order. The default priority is `0`.
+    #[inline]
+    pub fn set_render_priority(&mut self, priority: i32) {
+        self.data_mut().set_render_priority(priority)
+    }
+
+    /// Sets the opacity of the objects contained by this node and its children, between `0.0`
+    /// (invisible) and `1.0` (opaque).
+    ///
+    /// Transparent objects are drawn after the opaque ones, regardless of their rendering
+    /// priority. See `Window::set_transparency_mode` for how they are composited.
+    #[inline]
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.data_mut().set_alpha(alpha)
+    }
+
     /// Mutably accesses the vertices of the objects contained by this node and its children.
     ///
     /// The provided closure is called once per object.
@@ -953,6 +1916,16 @@ impl SceneNode {
         self.data_mut().modify_vertices(f)
     }
 
+    /// Replaces the vertices starting at index `start` of the objects contained by this node
+    /// and its children by `coords`.
+    ///
+    /// Unlike `modify_vertices`, only the modified range is uploaded to the GPU. See
+    /// `Mesh::update_vertices_range` for details.
+    #[inline]
+    pub fn update_vertices_range(&mut self, start: usize, coords: &[Point3<f32>]) {
+        self.data_mut().update_vertices_range(start, coords)
+    }
+
     /// Accesses the vertices of the objects contained by this node and its children.
     ///
     /// The provided closure is called once per object.
@@ -961,6 +1934,19 @@ impl SceneNode {
         self.data().read_vertices(f)
     }
 
+    /// Mutably accesses the points and colors of the point clouds contained by this node and its
+    /// children.
+    ///
+    /// The provided closure is called once per object having vertex colors. The two vectors
+    /// must keep the same length.
+    #[inline(always)]
+    pub fn modify_points<F: FnMut(&mut Vec<Point3<f32>>, &mut Vec<Point3<f32>>)>(
+        &mut self,
+        f: &mut F,
+    ) {
+        self.data_mut().modify_points(f)
+    }
+
     /// Recomputes the normals of the meshes of the objects contained by this node and its
     /// children.
     #[inline]
@@ -1009,6 +1995,21 @@ impl SceneNode {
         self.data_mut().modify_uvs(f)
     }
 
+    /// Rescales and offsets the texture coordinates of the objects contained by this node and
+    /// its children.
+    ///
+    /// Each texture coordinate `uv` is replaced by `offset + scale * uv` (component-wise). This
+    /// is useful to map a primitive onto a sub-region of a texture atlas. Successive calls
+    /// compose.
+    ///
+    /// Objects sharing their texture coordinates with other objects, like the primitives of the
+    /// `MeshManager`, are given their own copy first so the other objects are not affected.
+    /// Objects whose texture coordinates are not available on the CPU are left unchanged.
+    #[inline]
+    pub fn remap_uvs(&mut self, offset: Vector2<f32>, scale: Vector2<f32>) {
+        self.data_mut().remap_uvs(offset, scale)
+    }
+
     /// Accesses the texture coordinates of the objects contained by this node and its children.
     ///
     /// The provided closure is called once per object.
@@ -1074,6 +2075,15 @@ impl SceneNode {
         self.data_mut().set_texture(texture)
     }
 
+    /// Selects the layer sampled by the objects contained by this node and its children, if
+    /// their texture is a texture array.
+    ///
+    /// Texture arrays are created with `TextureManager::add_array` and assigned with
+    /// `set_texture_with_name`. The layer wraps around the number of layers of the texture.
+    pub fn set_texture_layer(&mut self, layer: u32) {
+        self.data_mut().set_texture_layer(layer)
+    }
+
     /// Sets the local scaling factors of the object.
     #[inline]
     pub fn set_local_scale(&mut self, sx: f32, sy: f32, sz: f32) {
@@ -1087,6 +2097,50 @@ impl SceneNode {
         self.data_mut().reorient(eye, at, up)
     }
 
+    /// Replaces the mesh of the object contained by this node.
+    ///
+    /// This keeps the transformation, color, material and other properties of the object. This
+    /// is useful to change the dimensions of primitives whose mesh depends on them, e.g., for a
+    /// capsule:
+    ///
+    /// ```no_run
+    /// # use kiss3d::window::Window;
+    /// # use kiss3d::resource::Mesh;
+    /// # use kiss3d::ncollide3d::procedural;
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # let mut window = Window::new("Example");
+    /// let mut capsule = window.add_capsule(0.5, 1.0);
+    /// let mesh = Mesh::from_trimesh(procedural::capsule(&2.0, &3.0, 50, 50), false);
+    /// capsule.set_mesh(Rc::new(RefCell::new(mesh)));
+    /// ```
+    ///
+    /// # Failure
+    /// Fails of this node does not contains an object.
+    #[inline]
+    pub fn set_mesh(&mut self, mesh: Rc<RefCell<Mesh>>) {
+        self.data_mut().set_mesh(mesh)
+    }
+
+    /// Replaces the mesh of the object contained by this node by a smoother one, with
+    /// `iterations` steps of Loop subdivision.
+    ///
+    /// # Failure
+    /// Fails of this node does not contains an object, or if the data of its mesh is not
+    /// available on the CPU.
+    #[inline]
+    pub fn subdivide(&mut self, iterations: u32) {
+        self.data_mut().subdivide(iterations)
+    }
+
+    /// Rotates the object in place such that its `z` axis is oriented toward `target`.
+    ///
+    /// The translation of the object is kept, e.g., to make a turret track a target.
+    #[inline]
+    pub fn look_at(&mut self, target: &Point3<f32>, up: &Vector3<f32>) {
+        self.data_mut().look_at(target, up)
+    }
+
     /// Appends a transformation to this node local transformation.
     #[inline]
     pub fn append_transformation(&mut self, t: &Isometry3<f32>) {
@@ -2,8 +2,10 @@
 
 pub use crate::text::font::Font;
 pub use crate::text::glyph::Glyph;
-pub use crate::text::renderer::TextRenderer;
+pub use crate::text::renderer::{TextAlign, TextRenderer};
+pub use crate::text::text_label::{TextLabel, TextLabelHandle};
 
 mod font;
 mod glyph;
 mod renderer;
+mod text_label;
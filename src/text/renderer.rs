@@ -2,7 +2,7 @@
 // available under the BSD-3 licence.
 // It has been modified to work with gl-rs, nalgebra, and rust-freetype
 
-use na::{Point2, Point3, Vector2};
+use na::{Matrix3, Point2, Point3, Vector2};
 use rusttype;
 use rusttype::gpu_cache::Cache;
 use std::rc::Rc;
@@ -12,12 +12,29 @@ use crate::resource::{AllocationType, BufferType, Effect, GPUVec, ShaderAttribut
 use crate::text::Font;
 use crate::verify;
 
+/// The horizontal alignment of a text relative to its anchor position.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TextAlign {
+    /// Each line starts at the anchor.
+    Left,
+    /// Each line is centered on the anchor.
+    Center,
+    /// Each line ends at the anchor.
+    Right,
+}
+
 struct TextRenderContext {
     len: usize,
     scale: f32,
     color: Point3<f32>,
     pos: Point2<f32>,
     font: Rc<Font>,
+    align: TextAlign,
+    max_width: Option<f32>,
+    // Maps the text coordinates, in pixels, to the screen.
+    transform: Option<Matrix3<f32>>,
+    // The normalized device depth of the text, if it is depth-tested.
+    depth: Option<f32>,
 }
 
 /// A ttf text renderer.
@@ -29,6 +46,7 @@ pub struct TextRenderer {
     invsz: ShaderUniform<Vector2<f32>>,
     tex: ShaderUniform<i32>,
     color: ShaderUniform<Point3<f32>>,
+    depth: ShaderUniform<f32>,
     pos: ShaderAttribute<Point2<f32>>,
     uvs: ShaderAttribute<Point2<f32>>,
     contexts: Vec<TextRenderContext>,
@@ -107,6 +125,7 @@ impl TextRenderer {
             invsz: shader.get_uniform("invsz").expect("Could not find invsz"),
             tex: shader.get_uniform("tex0").expect("Could not find tex0"),
             color: shader.get_uniform("color").expect("Could not find color"),
+            depth: shader.get_uniform("depth").expect("Could not find depth"),
             pos: shader.get_attrib("pos").expect("Could not find pos"),
             uvs: shader.get_attrib("uvs").expect("Could not find uvs"),
             shader,
@@ -125,6 +144,55 @@ impl TextRenderer {
         scale: f32,
         font: &Rc<Font>,
         color: &Point3<f32>,
+    ) {
+        self.draw_text_ex(text, pos, scale, font, color, TextAlign::Left, None)
+    }
+
+    /// Adds a piece of text to be drawn during the next frame, with the given alignment.
+    ///
+    /// If `max_width` is set, lines wider than `max_width` are wrapped at word boundaries. A
+    /// single word wider than `max_width` is not split. Like `draw_text`, this must be called
+    /// at each frame the text should be visible.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text_ex(
+        &mut self,
+        text: &str,
+        pos: &Point2<f32>,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+        align: TextAlign,
+        max_width: Option<f32>,
+    ) {
+        self.text.push_str(text);
+        self.contexts.push(TextRenderContext {
+            len: text.len(),
+            scale,
+            color: *color,
+            pos: *pos,
+            font: font.clone(),
+            align,
+            max_width,
+            transform: None,
+            depth: None,
+        })
+    }
+
+    /// Adds a piece of text to be drawn during the next frame, occluded by the parts of the 3D
+    /// scene closer to the camera.
+    ///
+    /// `depth` is the depth of the whole text in normalized device coordinates, from `-1.0` on
+    /// the near clipping plane to `1.0` on the far clipping plane, e.g., the `z` coordinate of
+    /// the projection of a 3D point. Like `draw_text`, this must be called at each frame the text
+    /// should be visible.
+    pub fn draw_text_with_depth(
+        &mut self,
+        text: &str,
+        pos: &Point2<f32>,
+        depth: f32,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
     ) {
         self.text.push_str(text);
         self.contexts.push(TextRenderContext {
@@ -133,15 +201,96 @@ impl TextRenderer {
             color: *color,
             pos: *pos,
             font: font.clone(),
+            align: TextAlign::Left,
+            max_width: None,
+            transform: None,
+            depth: Some(depth),
         })
     }
 
+    /// Adds a piece of text to be drawn during the next frame, with its top-left corner at the
+    /// origin of the text coordinates.
+    ///
+    /// `transform` maps the text coordinates, in pixels with the y axis pointing downward, to
+    /// the screen coordinates, in pixels. This allows the text to be rotated, scaled or skewed.
+    pub(crate) fn draw_text_transformed(
+        &mut self,
+        text: &str,
+        transform: &Matrix3<f32>,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+    ) {
+        self.text.push_str(text);
+        self.contexts.push(TextRenderContext {
+            len: text.len(),
+            scale,
+            color: *color,
+            pos: Point2::origin(),
+            font: font.clone(),
+            align: TextAlign::Left,
+            max_width: None,
+            transform: Some(*transform),
+            depth: None,
+        })
+    }
+
+    /// Rasterizes the glyphs of `chars` with the given font and scale into the glyph atlas.
+    ///
+    /// Glyphs are otherwise rasterized the first time they are drawn. Preloading the character
+    /// sets used by an application avoids rasterizing them during the first frames. Glyphs not
+    /// drawn recently may still be evicted when the atlas is full.
+    pub fn preload(&mut self, font: &Rc<Font>, scale: f32, chars: &str) {
+        let ctxt = Context::get();
+        let font_uid = Font::uid(font);
+        let scale = rusttype::Scale::uniform(scale);
+
+        for c in chars.chars() {
+            let glyph = font
+                .font()
+                .glyph(c)
+                .scaled(scale)
+                .positioned(rusttype::point(0.0, 0.0));
+            self.cache.queue_glyph(font_uid, glyph);
+        }
+
+        verify!(ctxt.active_texture(Context::TEXTURE0));
+        verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&self.texture)));
+
+        let _ = self.cache.cache_queued(|rect, data| {
+            verify!(ctxt.tex_sub_image2d(
+                Context::TEXTURE_2D,
+                0,
+                rect.min.x as i32,
+                rect.min.y as i32,
+                rect.width() as i32,
+                rect.height() as i32,
+                Context::RED,
+                Some(data)
+            ));
+        });
+    }
+
+    /// Removes all the glyphs from the glyph atlas.
+    ///
+    /// They are rasterized again the next time they are drawn.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
     /// Actually draws the text.
     pub fn render(&mut self, width: f32, height: f32) {
         if self.contexts.is_empty() {
             return;
         }
 
+        // Nothing can be drawn on an empty framebuffer, e.g., when the window is minimized.
+        if width <= 0.0 || height <= 0.0 {
+            self.text.clear();
+            self.contexts.clear();
+            return;
+        }
+
         let ctxt = Context::get();
         self.shader.use_program();
 
@@ -154,12 +303,15 @@ impl TextRenderer {
             Context::ONE_MINUS_SRC_ALPHA,
         ));
         verify!(ctxt.disable(Context::DEPTH_TEST));
+        // Depth-tested texts are occluded by the scene but must not occlude each other.
+        verify!(ctxt.depth_mask(false));
 
         self.pos.enable();
         self.uvs.enable();
         self.tex.upload(&0);
         self.invsz.upload(&Vector2::new(2.0 / width, -2.0 / height));
 
+        verify!(ctxt.active_texture(Context::TEXTURE0));
         verify!(ctxt.bind_texture(Context::TEXTURE_2D, Some(&self.texture)));
         verify!(ctxt.tex_parameteri(
             Context::TEXTURE_2D,
@@ -175,16 +327,43 @@ impl TextRenderer {
         let mut pos = 0;
 
         for context in self.contexts.iter() {
+            match context.depth {
+                Some(depth) => {
+                    verify!(ctxt.enable(Context::DEPTH_TEST));
+                    self.depth.upload(&depth);
+                }
+                None => {
+                    verify!(ctxt.disable(Context::DEPTH_TEST));
+                    self.depth.upload(&-1.0);
+                }
+            }
+
             let scale = rusttype::Scale::uniform(context.scale);
             let vmetrics = context.font.font().v_metrics(scale);
             let line_height = vmetrics.ascent - vmetrics.descent;
             let text = &self.text[pos..pos + context.len];
             let font_uid = Font::uid(&context.font);
             let mut vshift = 0.0;
+            let mut lines = Vec::new();
 
             for line in text.lines() {
+                match context.max_width {
+                    Some(max_width) => {
+                        wrap_line(context.font.font(), line, scale, max_width, &mut lines)
+                    }
+                    None => lines.push(line.to_string()),
+                }
+            }
+
+            for line in &lines {
+                let width = line_width(context.font.font(), line, scale);
+                let hshift = match context.align {
+                    TextAlign::Left => 0.0,
+                    TextAlign::Center => width / 2.0,
+                    TextAlign::Right => width,
+                };
                 let orig = rusttype::Point {
-                    x: context.pos.x,
+                    x: context.pos.x - hshift,
                     y: context.pos.y + vshift,
                 };
 
@@ -215,6 +394,11 @@ impl TextRenderer {
                 });
 
                 let layout = context.font.font().layout(line, scale, orig);
+                let transform = context.transform;
+                let vertex = |x: f32, y: f32| match transform {
+                    Some(t) => t.transform_point(&Point2::new(x, y)),
+                    None => Point2::new(x, y),
+                };
 
                 {
                     let coords = self.coords.data_mut().as_mut().unwrap();
@@ -225,22 +409,22 @@ impl TextRenderer {
                             let max_px = rect.max.x as f32;
                             let max_py = rect.max.y as f32 + vmetrics.ascent;
 
-                            coords.push(Point2::new(min_px, min_py));
+                            coords.push(vertex(min_px, min_py));
                             coords.push(Point2::new(tex.min.x, tex.min.y));
 
-                            coords.push(Point2::new(min_px, max_py));
+                            coords.push(vertex(min_px, max_py));
                             coords.push(Point2::new(tex.min.x, tex.max.y));
 
-                            coords.push(Point2::new(max_px, min_py));
+                            coords.push(vertex(max_px, min_py));
                             coords.push(Point2::new(tex.max.x, tex.min.y));
 
-                            coords.push(Point2::new(max_px, min_py));
+                            coords.push(vertex(max_px, min_py));
                             coords.push(Point2::new(tex.max.x, tex.min.y));
 
-                            coords.push(Point2::new(min_px, max_py));
+                            coords.push(vertex(min_px, max_py));
                             coords.push(Point2::new(tex.min.x, tex.max.y));
 
-                            coords.push(Point2::new(max_px, max_py));
+                            coords.push(vertex(max_px, max_py));
                             coords.push(Point2::new(tex.max.x, tex.max.y));
                         }
                     }
@@ -260,6 +444,7 @@ impl TextRenderer {
         self.pos.disable();
         self.uvs.enable();
 
+        verify!(ctxt.depth_mask(true));
         verify!(ctxt.enable(Context::DEPTH_TEST));
         verify!(ctxt.disable(Context::BLEND));
 
@@ -268,6 +453,42 @@ impl TextRenderer {
     }
 }
 
+/// The horizontal advance of `line` once laid out.
+fn line_width(font: &rusttype::Font<'static>, line: &str, scale: rusttype::Scale) -> f32 {
+    font.layout(line, scale, rusttype::point(0.0, 0.0))
+        .last()
+        .map(|g| g.position().x + g.unpositioned().h_metrics().advance_width)
+        .unwrap_or(0.0)
+}
+
+/// Greedily splits `line` at word boundaries so each piece fits in `max_width`.
+fn wrap_line(
+    font: &rusttype::Font<'static>,
+    line: &str,
+    scale: rusttype::Scale,
+    max_width: f32,
+    out: &mut Vec<String>,
+) {
+    let mut current = String::new();
+
+    for word in line.split_whitespace() {
+        if current.is_empty() {
+            current.push_str(word);
+            continue;
+        }
+
+        let candidate = format!("{} {}", current, word);
+
+        if line_width(font, &candidate, scale) > max_width {
+            out.push(std::mem::replace(&mut current, word.to_string()));
+        } else {
+            current = candidate;
+        }
+    }
+
+    out.push(current);
+}
+
 /// Vertex shader used by the material to display line.
 pub static TEXT_VERTEX_SRC: &str = A_VERY_LONG_STRING;
 /// Fragment shader used by the material to display line.
@@ -278,6 +499,7 @@ const A_VERY_LONG_STRING: &str = "
 
 uniform vec2 invsz;
 uniform vec3 color;
+uniform float depth;
 
 attribute vec2 pos;
 attribute vec2 uvs;
@@ -286,7 +508,7 @@ varying vec2 tex;
 varying vec3 Color;
 
 void main() {
-    gl_Position = vec4(pos.x * invsz.x - 1.0, pos.y * invsz.y + 1.0, -1.0, 1.0);
+    gl_Position = vec4(pos.x * invsz.x - 1.0, pos.y * invsz.y + 1.0, depth, 1.0);
     tex         = uvs;
     Color       = color;
 }
@@ -0,0 +1,42 @@
+use na::Point3;
+use std::rc::Rc;
+
+use crate::text::Font;
+
+/// A handle identifying a text label added to a window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextLabelHandle(pub(crate) usize);
+
+/// A piece of text anchored to a 3D position.
+///
+/// The label is re-projected on the screen at each frame and skipped when it is behind the camera.
+pub struct TextLabel {
+    /// The displayed text.
+    pub text: String,
+    /// The 3D position the top-left corner of the text is anchored to.
+    pub world_pos: Point3<f32>,
+    /// The scale of the text.
+    pub scale: f32,
+    /// The color of the text.
+    pub color: Point3<f32>,
+    /// The font used to render the text.
+    pub font: Rc<Font>,
+    pub(crate) depth_test: bool,
+}
+
+impl TextLabel {
+    /// Whether the label is hidden by the objects in front of its anchor.
+    #[inline]
+    pub fn depth_test(&self) -> bool {
+        self.depth_test
+    }
+
+    /// Sets whether the label is hidden by the objects in front of its anchor.
+    ///
+    /// Otherwise, the label is drawn on top of the 3D scene. Labels are never hidden when
+    /// post-processing effects are enabled. Defaults to `false`.
+    #[inline]
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        self.depth_test = enabled;
+    }
+}
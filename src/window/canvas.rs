@@ -1,10 +1,14 @@
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
-use crate::event::{Action, Key, MouseButton, WindowEvent};
+use crate::event::{Action, Key, Modifiers, MouseButton, WindowEvent};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::window::GLCanvas as CanvasImpl;
+#[cfg(feature = "gamepad")]
+use crate::window::GamepadListener;
 #[cfg(target_arch = "wasm32")]
 use crate::window::WebGLCanvas as CanvasImpl;
+use crate::window::{FullscreenMode, Monitor, WindowCreationError};
 use image::{GenericImage, Pixel};
 
 /// The possible number of samples for multisample anti-aliasing.
@@ -40,18 +44,52 @@ impl NumSamples {
     }
 }
 
+/// When the window renders a new frame.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RedrawMode {
+    /// A new frame is rendered as soon as possible.
+    Continuous,
+    /// A new frame is rendered only after an event occurred, or after
+    /// `Window::request_redraw` was called.
+    OnDemand,
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
 /// Canvas options.
+///
+/// Use `..CanvasSetup::default()` to only set some of the options.
 pub struct CanvasSetup {
     /// Is vsync enabled?
     pub vsync: bool,
     /// Number of AA sambles.
     pub samples: NumSamples,
+    /// Can the window be transparent? This is required by `Window::set_opacity`.
+    #[serde(default)]
+    pub transparent: bool,
+    /// The initial position of the top-left corner of the window, in physical pixels.
+    ///
+    /// Set to `None` to let the system choose. This is ignored on the web.
+    #[serde(default)]
+    pub position: Option<(i32, i32)>,
+}
+
+impl Default for CanvasSetup {
+    fn default() -> Self {
+        CanvasSetup {
+            vsync: true,
+            samples: NumSamples::Zero,
+            transparent: false,
+            position: None,
+        }
+    }
 }
 
 /// An abstract structure representing a window for native applications, and a canvas for web applications.
 pub struct Canvas {
     canvas: CanvasImpl,
+    scale_factor_override: Option<f64>,
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<GamepadListener>,
 }
 
 impl Canvas {
@@ -64,9 +102,35 @@ impl Canvas {
         canvas_setup: Option<CanvasSetup>,
         out_events: Sender<WindowEvent>,
     ) -> Self {
-        Canvas {
-            canvas: CanvasImpl::open(title, hide, width, height, canvas_setup, out_events),
-        }
+        Self::try_open(title, hide, width, height, canvas_setup, out_events)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Open a new window, returning an error instead of panicking if it cannot be created.
+    pub fn try_open(
+        title: &str,
+        hide: bool,
+        width: u32,
+        height: u32,
+        canvas_setup: Option<CanvasSetup>,
+        out_events: Sender<WindowEvent>,
+    ) -> Result<Self, WindowCreationError> {
+        #[cfg(feature = "gamepad")]
+        let gamepad = GamepadListener::new(out_events.clone());
+        let canvas = CanvasImpl::open(title, hide, width, height, canvas_setup, out_events)?;
+
+        Ok(Canvas {
+            canvas,
+            scale_factor_override: None,
+            #[cfg(feature = "gamepad")]
+            gamepad,
+        })
+    }
+
+    /// The platform-specific canvas: a `GLCanvas` on native platforms and a `WebGLCanvas` on
+    /// the web.
+    pub fn platform_canvas(&self) -> &CanvasImpl {
+        &self.canvas
     }
 
     /// Run the platform-specific render loop.
@@ -76,7 +140,36 @@ impl Canvas {
 
     /// Poll all events tha occurred since the last call to this method.
     pub fn poll_events(&mut self) {
-        self.canvas.poll_events()
+        self.canvas.poll_events();
+
+        #[cfg(feature = "gamepad")]
+        {
+            if let Some(gamepad) = &mut self.gamepad {
+                gamepad.poll_events()
+            }
+        }
+    }
+
+    /// Blocks until at least one event occurs, then polls all the events that occurred since the
+    /// last call to `poll_events` or `wait_events`.
+    ///
+    /// On the web, this does not block and is equivalent to `poll_events`.
+    pub fn wait_events(&mut self) {
+        self.canvas.wait_events();
+
+        #[cfg(feature = "gamepad")]
+        {
+            if let Some(gamepad) = &mut self.gamepad {
+                gamepad.poll_events()
+            }
+        }
+    }
+
+    /// Makes the OpenGL context of this canvas the current one.
+    ///
+    /// This is only needed when several canvases are open at the same time.
+    pub fn make_current(&mut self) {
+        self.canvas.make_current()
     }
 
     /// If double-buffering is supported, swap the buffers.
@@ -97,9 +190,24 @@ impl Canvas {
         self.canvas.cursor_pos()
     }
 
+    /// See `Window::is_focused` for details.
+    pub fn is_focused(&self) -> bool {
+        self.canvas.is_focused()
+    }
+
     /// The scale factor.
+    ///
+    /// This is the override set by `set_scale_factor_override`, if any.
     pub fn scale_factor(&self) -> f64 {
-        self.canvas.scale_factor()
+        self.scale_factor_override
+            .unwrap_or_else(|| self.canvas.scale_factor())
+    }
+
+    /// Forces the scale factor to the given value instead of the one reported by the platform.
+    ///
+    /// Set to `None` to use the platform scale factor again.
+    pub fn set_scale_factor_override(&mut self, factor: Option<f64>) {
+        self.scale_factor_override = factor
     }
 
     /// Set the window title.
@@ -112,6 +220,46 @@ impl Canvas {
         self.canvas.set_icon(icon)
     }
 
+    /// Set the size of the window. See `Window::set_size` for details.
+    pub fn set_size(&mut self, w: u32, h: u32) {
+        self.canvas.set_size(w, h)
+    }
+
+    /// Set the minimum size of the window. See `Window::set_min_size` for details.
+    pub fn set_min_size(&mut self, w: u32, h: u32) {
+        self.canvas.set_min_size(w, h)
+    }
+
+    /// Set the maximum size of the window. See `Window::set_max_size` for details.
+    pub fn set_max_size(&mut self, w: u32, h: u32) {
+        self.canvas.set_max_size(w, h)
+    }
+
+    /// Set the fullscreen mode of the window. See `Window::set_fullscreen` for details.
+    pub fn set_fullscreen(&mut self, mode: FullscreenMode) {
+        self.canvas.set_fullscreen(mode)
+    }
+
+    /// The monitors connected to the system. See `Window::available_monitors` for details.
+    pub fn available_monitors(&self) -> Vec<Monitor> {
+        self.canvas.available_monitors()
+    }
+
+    /// Set the position of the window. See `Window::set_position` for details.
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.canvas.set_position(x, y)
+    }
+
+    /// The position of the window. See `Window::position` for details.
+    pub fn position(&self) -> Option<(i32, i32)> {
+        self.canvas.position()
+    }
+
+    /// Center the window on its monitor. See `Window::center_on_screen` for details.
+    pub fn center_on_screen(&mut self) {
+        self.canvas.center_on_screen()
+    }
+
     /// Set the cursor grabbing behaviour.
     pub fn set_cursor_grab(&self, grab: bool) {
         self.canvas.set_cursor_grab(grab);
@@ -122,6 +270,17 @@ impl Canvas {
         self.canvas.set_cursor_position(x, y);
     }
 
+    /// Enable or disable the relative mouse mode. See `Window::set_relative_mouse_mode` for
+    /// details.
+    pub fn set_relative_mouse_mode(&mut self, enabled: bool) {
+        self.canvas.set_relative_mouse_mode(enabled);
+    }
+
+    /// Whether the relative mouse mode is enabled.
+    pub fn relative_mouse_mode(&self) -> bool {
+        self.canvas.relative_mouse_mode()
+    }
+
     /// Toggle the cursor visibility.
     pub fn hide_cursor(&self, hide: bool) {
         self.canvas.hide_cursor(hide);
@@ -137,6 +296,16 @@ impl Canvas {
         self.canvas.show()
     }
 
+    /// Set the maximum delay between two clicks for them to be counted as a double-click.
+    pub fn set_double_click_interval(&mut self, interval: Duration) {
+        self.canvas.set_double_click_interval(interval)
+    }
+
+    /// Enable or disable key auto-repeat events. See `Window::set_key_repeat` for details.
+    pub fn set_key_repeat(&mut self, enabled: bool) {
+        self.canvas.set_key_repeat(enabled)
+    }
+
     /// The state of a mouse button.
     pub fn get_mouse_button(&self, button: MouseButton) -> Action {
         self.canvas.get_mouse_button(button)
@@ -146,6 +315,11 @@ impl Canvas {
     pub fn get_key(&self, key: Key) -> Action {
         self.canvas.get_key(key)
     }
+
+    /// The modifier keys currently pressed.
+    pub fn modifiers(&self) -> Modifiers {
+        self.canvas.modifiers()
+    }
 }
 
 pub(crate) trait AbstractCanvas {
@@ -156,22 +330,40 @@ pub(crate) trait AbstractCanvas {
         height: u32,
         window_setup: Option<CanvasSetup>,
         out_events: Sender<WindowEvent>,
-    ) -> Self;
+    ) -> Result<Self, WindowCreationError>
+    where
+        Self: Sized;
     fn render_loop(data: impl FnMut(f64) -> bool + 'static);
     fn poll_events(&mut self);
+    fn wait_events(&mut self);
+    fn make_current(&mut self);
     fn swap_buffers(&mut self);
     fn size(&self) -> (u32, u32);
     fn cursor_pos(&self) -> Option<(f64, f64)>;
+    fn is_focused(&self) -> bool;
     fn scale_factor(&self) -> f64;
 
     fn set_title(&mut self, title: &str);
     fn set_icon(&mut self, icon: impl GenericImage<Pixel = impl Pixel<Subpixel = u8>>);
+    fn set_size(&mut self, w: u32, h: u32);
+    fn set_min_size(&mut self, w: u32, h: u32);
+    fn set_max_size(&mut self, w: u32, h: u32);
+    fn set_fullscreen(&mut self, mode: FullscreenMode);
+    fn available_monitors(&self) -> Vec<Monitor>;
+    fn set_position(&mut self, x: i32, y: i32);
+    fn position(&self) -> Option<(i32, i32)>;
+    fn center_on_screen(&mut self);
     fn set_cursor_grab(&self, grab: bool);
     fn set_cursor_position(&self, x: f64, y: f64);
     fn hide_cursor(&self, hide: bool);
+    fn set_relative_mouse_mode(&mut self, enabled: bool);
+    fn relative_mouse_mode(&self) -> bool;
     fn hide(&mut self);
     fn show(&mut self);
+    fn set_double_click_interval(&mut self, interval: Duration);
+    fn set_key_repeat(&mut self, enabled: bool);
 
     fn get_mouse_button(&self, button: MouseButton) -> Action;
     fn get_key(&self, key: Key) -> Action;
+    fn modifiers(&self) -> Modifiers;
 }
@@ -0,0 +1,53 @@
+use crate::event::MouseButton;
+use instant::Instant;
+use std::time::Duration;
+
+/// Maximum distance, in pixels, the cursor may travel between two clicks of the same sequence.
+const MAX_CLICK_DISTANCE: f64 = 4.0;
+
+/// Tracks consecutive clicks to detect double-clicks, triple-clicks, etc.
+pub(crate) struct ClickCounter {
+    interval: Duration,
+    last_click: Option<(MouseButton, Instant, (f64, f64))>,
+    count: u32,
+}
+
+impl ClickCounter {
+    /// Creates a new click counter with the default double-click interval of 500ms.
+    pub fn new() -> Self {
+        ClickCounter {
+            interval: Duration::from_millis(500),
+            last_click: None,
+            count: 0,
+        }
+    }
+
+    /// Sets the maximum delay between two clicks of the same sequence.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Registers a button press at the given cursor position and returns the resulting click count.
+    pub fn press(&mut self, button: MouseButton, pos: (f64, f64)) -> u32 {
+        let now = Instant::now();
+
+        let continues_sequence = match self.last_click {
+            Some((last_button, last_time, last_pos)) => {
+                let (dx, dy) = (pos.0 - last_pos.0, pos.1 - last_pos.1);
+                last_button == button
+                    && now.duration_since(last_time) <= self.interval
+                    && dx * dx + dy * dy <= MAX_CLICK_DISTANCE * MAX_CLICK_DISTANCE
+            }
+            None => false,
+        };
+
+        if continues_sequence {
+            self.count += 1;
+        } else {
+            self.count = 1;
+        }
+
+        self.last_click = Some((button, now, pos));
+        self.count
+    }
+}
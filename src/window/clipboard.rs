@@ -0,0 +1,157 @@
+//! Access to the system clipboard.
+
+#[cfg(target_arch = "wasm32")]
+use std::{cell::RefCell, rc::Rc};
+
+use crate::window::ClipboardError;
+
+/// The system clipboard.
+///
+/// The clipboard is opened on first use and kept open afterward: on X11, the copied content is
+/// lost as soon as the clipboard is closed unless a clipboard manager is running.
+///
+/// On the web, the asynchronous Clipboard API is used so the text read from the clipboard is only
+/// available after the browser answers.
+pub(crate) struct Clipboard {
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    clipboard: Option<arboard::Clipboard>,
+    // The last text copied or read from the clipboard.
+    #[cfg(target_arch = "wasm32")]
+    text: Rc<RefCell<Option<String>>>,
+}
+
+impl Clipboard {
+    /// Creates a clipboard that is not opened yet.
+    pub fn new() -> Clipboard {
+        Clipboard {
+            #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+            clipboard: None,
+            #[cfg(target_arch = "wasm32")]
+            text: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    fn with_clipboard<T>(
+        &mut self,
+        f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+    ) -> Result<T, ClipboardError> {
+        if self.clipboard.is_none() {
+            let clipboard = arboard::Clipboard::new()
+                .map_err(|e| ClipboardError::AccessFailed(e.to_string()))?;
+            self.clipboard = Some(clipboard);
+        }
+
+        f(self.clipboard.as_mut().unwrap()).map_err(|e| ClipboardError::AccessFailed(e.to_string()))
+    }
+
+    /// Copies an RGBA image to the clipboard.
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    pub fn set_image(
+        &mut self,
+        width: usize,
+        height: usize,
+        rgba: Vec<u8>,
+    ) -> Result<(), ClipboardError> {
+        let image = arboard::ImageData {
+            width,
+            height,
+            bytes: rgba.into(),
+        };
+
+        self.with_clipboard(|clipboard| clipboard.set_image(image))
+    }
+
+    /// Copies an RGBA image to the clipboard.
+    #[cfg(not(all(feature = "clipboard", not(target_arch = "wasm32"))))]
+    pub fn set_image(&mut self, _: usize, _: usize, _: Vec<u8>) -> Result<(), ClipboardError> {
+        Err(ClipboardError::Unsupported)
+    }
+
+    /// Copies text to the clipboard.
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    pub fn set_text(&mut self, text: &str) -> Result<(), ClipboardError> {
+        self.with_clipboard(|clipboard| clipboard.set_text(text.to_string()))
+    }
+
+    /// Copies text to the clipboard.
+    #[cfg(target_arch = "wasm32")]
+    pub fn set_text(&mut self, text: &str) -> Result<(), ClipboardError> {
+        let promise =
+            call_web_clipboard("writeText", Some(text)).ok_or(ClipboardError::Unsupported)?;
+        *self.text.borrow_mut() = Some(text.to_string());
+
+        wasm_bindgen_futures::spawn_local(async move {
+            // The browser may refuse to write without a user interaction.
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+        });
+
+        Ok(())
+    }
+
+    /// Copies text to the clipboard.
+    #[cfg(not(any(feature = "clipboard", target_arch = "wasm32")))]
+    pub fn set_text(&mut self, _: &str) -> Result<(), ClipboardError> {
+        Err(ClipboardError::Unsupported)
+    }
+
+    /// The text stored in the clipboard, if any.
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    pub fn text(&mut self) -> Option<String> {
+        self.with_clipboard(|clipboard| clipboard.get_text()).ok()
+    }
+
+    /// The text stored in the clipboard, if any.
+    ///
+    /// On the web, this starts reading the clipboard and returns the text read by the previous
+    /// call, or the last text copied with `set_text`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn text(&mut self) -> Option<String> {
+        if let Some(promise) = call_web_clipboard("readText", None) {
+            let text = self.text.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(read) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                    if let Some(read) = read.as_string() {
+                        *text.borrow_mut() = Some(read);
+                    }
+                }
+            });
+        }
+
+        self.text.borrow().clone()
+    }
+
+    /// The text stored in the clipboard, if any.
+    #[cfg(not(any(feature = "clipboard", target_arch = "wasm32")))]
+    pub fn text(&mut self) -> Option<String> {
+        None
+    }
+}
+
+/// Calls a method of `navigator.clipboard` with an optional text argument.
+///
+/// Returns `None` if the Clipboard API is not available, e.g., outside of secure contexts.
+#[cfg(target_arch = "wasm32")]
+fn call_web_clipboard(method: &str, arg: Option<&str>) -> Option<js_sys::Promise> {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    // `navigator.clipboard` is looked up dynamically because it may be undefined.
+    let navigator = web_sys::window()?.navigator();
+    let clipboard = js_sys::Reflect::get(&navigator, &JsValue::from_str("clipboard")).ok()?;
+
+    if clipboard.is_undefined() {
+        return None;
+    }
+
+    let function: js_sys::Function = js_sys::Reflect::get(&clipboard, &JsValue::from_str(method))
+        .ok()?
+        .dyn_into()
+        .ok()?;
+    let result = match arg {
+        Some(arg) => function.call1(&clipboard, &JsValue::from_str(arg)),
+        None => function.call0(&clipboard),
+    };
+
+    result.ok()?.dyn_into().ok()
+}
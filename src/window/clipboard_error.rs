@@ -0,0 +1,26 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when the system clipboard could not be accessed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// The clipboard is not supported on this platform (e.g. on the web).
+    Unsupported,
+    /// The clipboard could not be opened or written to.
+    ///
+    /// Contains the error message of the clipboard backend.
+    AccessFailed(String),
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClipboardError::Unsupported => {
+                write!(f, "the clipboard is not supported on this platform")
+            }
+            ClipboardError::AccessFailed(e) => write!(f, "failed to access the clipboard: {}", e),
+        }
+    }
+}
+
+impl Error for ClipboardError {}
@@ -0,0 +1,42 @@
+/// A video mode supported by a monitor, usable for exclusive fullscreen.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VideoMode {
+    /// The index of the monitor supporting this video mode, as given by `Monitor::id`.
+    pub monitor: usize,
+    /// The resolution of this video mode, in physical pixels.
+    pub size: (u32, u32),
+    /// The number of bits per pixel of this video mode.
+    pub bit_depth: u16,
+    /// The refresh rate of this video mode, in Hz.
+    pub refresh_rate: u16,
+}
+
+/// A monitor connected to the system.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Monitor {
+    /// The index of this monitor, used to select it with `FullscreenMode`.
+    pub id: usize,
+    /// The human-readable name of this monitor, if available.
+    pub name: Option<String>,
+    /// The position of the top-left corner of this monitor on the desktop, in physical pixels.
+    pub position: (i32, i32),
+    /// The current resolution of this monitor, in physical pixels.
+    pub size: (u32, u32),
+    /// The scale factor of this monitor.
+    pub scale_factor: f64,
+    /// The video modes supported by this monitor.
+    pub video_modes: Vec<VideoMode>,
+}
+
+/// The fullscreen state of a window.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FullscreenMode {
+    /// A regular window.
+    Windowed,
+    /// A borderless window covering the given monitor, or the current monitor if `None`.
+    ///
+    /// The monitor keeps its current video mode.
+    Borderless(Option<usize>),
+    /// An exclusive fullscreen window changing the video mode of its monitor.
+    Exclusive(VideoMode),
+}
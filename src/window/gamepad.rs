@@ -0,0 +1,83 @@
+use std::sync::mpsc::Sender;
+
+use crate::event::{Action, GamepadAxis, GamepadButton, WindowEvent};
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+/// Forwards the events of the connected gamepads to the window event channel.
+pub(crate) struct GamepadListener {
+    gilrs: Gilrs,
+    out_events: Sender<WindowEvent>,
+}
+
+impl GamepadListener {
+    /// Creates a new gamepad listener.
+    ///
+    /// Returns `None` if gamepads are not supported on this platform.
+    pub fn new(out_events: Sender<WindowEvent>) -> Option<Self> {
+        let gilrs = Gilrs::new().ok()?;
+        Some(GamepadListener { gilrs, out_events })
+    }
+
+    /// Sends the gamepad events that occurred since the last call to this method.
+    pub fn poll_events(&mut self) {
+        while let Some(event) = self.gilrs.next_event() {
+            let id: usize = event.id.into();
+            let event = match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    WindowEvent::GamepadButton(id, translate_button(button), Action::Press)
+                }
+                EventType::ButtonRepeated(button, _) => {
+                    WindowEvent::GamepadButton(id, translate_button(button), Action::Repeat)
+                }
+                EventType::ButtonReleased(button, _) => {
+                    WindowEvent::GamepadButton(id, translate_button(button), Action::Release)
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    WindowEvent::GamepadAxis(id, translate_axis(axis), value)
+                }
+                _ => continue,
+            };
+
+            let _ = self.out_events.send(event);
+        }
+    }
+}
+
+fn translate_button(button: Button) -> GamepadButton {
+    match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::C => GamepadButton::C,
+        Button::Z => GamepadButton::Z,
+        Button::LeftTrigger => GamepadButton::LeftTrigger,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger2,
+        Button::RightTrigger => GamepadButton::RightTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger2,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftThumb,
+        Button::RightThumb => GamepadButton::RightThumb,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        Button::Unknown => GamepadButton::Unknown,
+    }
+}
+
+fn translate_axis(axis: Axis) -> GamepadAxis {
+    match axis {
+        Axis::LeftStickX => GamepadAxis::LeftStickX,
+        Axis::LeftStickY => GamepadAxis::LeftStickY,
+        Axis::LeftZ => GamepadAxis::LeftZ,
+        Axis::RightStickX => GamepadAxis::RightStickX,
+        Axis::RightStickY => GamepadAxis::RightStickY,
+        Axis::RightZ => GamepadAxis::RightZ,
+        Axis::DPadX => GamepadAxis::DPadX,
+        Axis::DPadY => GamepadAxis::DPadY,
+        Axis::Unknown => GamepadAxis::Unknown,
+    }
+}
@@ -1,31 +1,282 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
 use crate::context::Context;
 use crate::event::{Action, Key, Modifiers, MouseButton, TouchAction, WindowEvent};
-use crate::window::canvas::{CanvasSetup, NumSamples};
-use crate::window::AbstractCanvas;
+use crate::window::canvas::CanvasSetup;
+use crate::window::{
+    AbstractCanvas, ClickCounter, FullscreenMode, Monitor, VideoMode, WindowCreationError,
+};
 use glutin::{
     self,
-    dpi::LogicalSize,
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
     event::TouchPhase,
     event_loop::{ControlFlow, EventLoop},
     platform::run_return::EventLoopExtRunReturn,
-    window::WindowBuilder,
-    ContextBuilder, GlRequest, PossiblyCurrent, WindowedContext,
+    window::{Fullscreen, WindowBuilder, WindowId},
+    ContextBuilder, GlRequest, PossiblyCurrent, RawContext,
 };
 use image::{GenericImage, Pixel};
 
+type SharedContext = Rc<RefCell<Option<RawContext<PossiblyCurrent>>>>;
+
+thread_local! {
+    // winit does not support creating more than one event loop, so it is shared by all the
+    // windows of the thread.
+    static EVENT_LOOP: RefCell<Option<EventLoop<()>>> = RefCell::new(None);
+    // The events received while another window was pumping the event loop.
+    static PENDING_EVENTS: RefCell<HashMap<WindowId, Vec<glutin::event::WindowEvent<'static>>>> =
+        RefCell::new(HashMap::new());
+    // The OpenGL contexts of the open windows. New contexts share their objects with them.
+    static CONTEXTS: RefCell<Vec<Weak<RefCell<Option<RawContext<PossiblyCurrent>>>>>> =
+        RefCell::new(Vec::new());
+}
+
+fn new_event_loop() -> EventLoop<()> {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use glutin::platform::unix::EventLoopExtUnix;
+        EventLoop::new_any_thread()
+    }
+    #[cfg(windows)]
+    {
+        use glutin::platform::windows::EventLoopExtWindows;
+        EventLoop::new_any_thread()
+    }
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        windows
+    )))]
+    {
+        EventLoop::new()
+    }
+}
+
 /// A canvas based on glutin and OpenGL.
+///
+/// All the canvases of a thread share the same event loop, and their OpenGL contexts share
+/// their objects (buffers, textures, shaders), so a mesh or texture can be used by several
+/// windows.
 pub struct GLCanvas {
-    window: WindowedContext<PossiblyCurrent>,
-    events: EventLoop<()>,
+    // The context must be dropped before its window.
+    context: SharedContext,
+    window: glutin::window::Window,
     cursor_pos: Option<(f64, f64)>,
+    focused: bool,
     key_states: [Action; Key::Unknown as usize + 1],
     button_states: [Action; MouseButton::Button8 as usize + 1],
+    modifiers: Modifiers,
+    click_counter: ClickCounter,
+    key_repeat: bool,
+    relative_mouse_mode: bool,
     out_events: Sender<WindowEvent>,
     // listeners: Vec<EventListenerHandle>,
 }
 
+impl Drop for GLCanvas {
+    fn drop(&mut self) {
+        let id = self.window.id();
+        let _ = PENDING_EVENTS.with(|pending| pending.borrow_mut().remove(&id));
+    }
+}
+
+impl GLCanvas {
+    /// The underlying glutin window.
+    ///
+    /// This implements `HasRawWindowHandle` and can be used to share this window with other
+    /// libraries.
+    pub fn window(&self) -> &glutin::window::Window {
+        &self.window
+    }
+
+    /// Processes the pending events.
+    ///
+    /// If `wait` is `true`, blocks until at least one window event occurs.
+    fn pump_events(&mut self, wait: bool) {
+        let id = self.window.id();
+        let pending = PENDING_EVENTS
+            .with(|pending| pending.borrow_mut().remove(&id))
+            .unwrap_or_default();
+        let mut received_event = !pending.is_empty();
+
+        for event in pending {
+            self.handle_window_event(event);
+        }
+
+        EVENT_LOOP.with(|events| {
+            let mut events = events.borrow_mut();
+            let events = events.as_mut().expect("The event loop is not initialized.");
+
+            events.run_return(|event, _, control_flow| {
+                use glutin::event::Event;
+
+                match event {
+                    Event::WindowEvent { window_id, event } => {
+                        received_event = true;
+
+                        if window_id == id {
+                            self.handle_window_event(event);
+                        } else if let Some(event) = event.to_static() {
+                            // Keep the event until the other window pumps its events.
+                            PENDING_EVENTS.with(|pending| {
+                                pending
+                                    .borrow_mut()
+                                    .entry(window_id)
+                                    .or_default()
+                                    .push(event)
+                            });
+                        }
+                    }
+                    Event::DeviceEvent {
+                        event: glutin::event::DeviceEvent::MouseMotion { delta },
+                        ..
+                    } if self.relative_mouse_mode => {
+                        received_event = true;
+                        let _ = self
+                            .out_events
+                            .send(WindowEvent::MouseMotion(delta.0, delta.1));
+                    }
+                    Event::RedrawEventsCleared => {
+                        if wait && !received_event {
+                            *control_flow = ControlFlow::Wait;
+                        } else {
+                            *control_flow = ControlFlow::Exit;
+                        }
+                    }
+                    _ => {}
+                };
+            })
+        })
+    }
+
+    #[allow(deprecated)] // todo: refactor to avoid using the deprecated `modifiers` field
+    fn handle_window_event(&mut self, event: glutin::event::WindowEvent) {
+        match event {
+            glutin::event::WindowEvent::CloseRequested => {
+                let _ = self.out_events.send(WindowEvent::Close);
+            }
+            glutin::event::WindowEvent::Resized(physical_size) => {
+                if let Some(context) = &*self.context.borrow() {
+                    context.resize(physical_size);
+                }
+                let fb_size: (u32, u32) = physical_size.into();
+                let _ = self
+                    .out_events
+                    .send(WindowEvent::FramebufferSize(fb_size.0, fb_size.1));
+            }
+            glutin::event::WindowEvent::Focused(focus) => {
+                self.focused = focus;
+                let _ = self.out_events.send(WindowEvent::Focus(focus));
+            }
+            glutin::event::WindowEvent::CursorMoved {
+                position,
+                modifiers,
+                ..
+            } => {
+                let modifiers = translate_modifiers(modifiers);
+                self.cursor_pos = Some(position.into());
+                let _ = self
+                    .out_events
+                    .send(WindowEvent::CursorPos(position.x, position.y, modifiers));
+            }
+            glutin::event::WindowEvent::MouseInput {
+                state,
+                button,
+                modifiers,
+                ..
+            } => {
+                let action = translate_action(state);
+                let button = translate_mouse_button(button);
+                let modifiers = translate_modifiers(modifiers);
+                self.button_states[button as usize] = action;
+                let _ = self
+                    .out_events
+                    .send(WindowEvent::MouseButton(button, action, modifiers));
+
+                if action == Action::Press {
+                    let (x, y) = self.cursor_pos.unwrap_or((0.0, 0.0));
+                    let count = self.click_counter.press(button, (x, y));
+                    let _ = self
+                        .out_events
+                        .send(WindowEvent::MouseClick(button, count, x, y, modifiers));
+                }
+            }
+            glutin::event::WindowEvent::Touch(touch) => {
+                let action = match touch.phase {
+                    TouchPhase::Started => TouchAction::Start,
+                    TouchPhase::Ended => TouchAction::End,
+                    TouchPhase::Moved => TouchAction::Move,
+                    TouchPhase::Cancelled => TouchAction::Cancel,
+                };
+
+                let _ = self.out_events.send(WindowEvent::Touch(
+                    touch.id,
+                    touch.location.x,
+                    touch.location.y,
+                    action,
+                    Modifiers::empty(),
+                ));
+            }
+            glutin::event::WindowEvent::MouseWheel {
+                delta, modifiers, ..
+            } => {
+                let (x, y) = match delta {
+                    glutin::event::MouseScrollDelta::LineDelta(dx, dy) => {
+                        (dx as f64 * 10.0, dy as f64 * 10.0)
+                    }
+                    glutin::event::MouseScrollDelta::PixelDelta(delta) => delta.into(),
+                };
+                let modifiers = translate_modifiers(modifiers);
+                let _ = self.out_events.send(WindowEvent::Scroll(x, y, modifiers));
+            }
+            glutin::event::WindowEvent::KeyboardInput { input, .. } => {
+                let mut action = translate_action(input.state);
+                let key = translate_key(input.virtual_keycode);
+                let modifiers = translate_modifiers(input.modifiers);
+                self.modifiers = modifiers;
+
+                // winit does not flag auto-repeat events: a press of a key
+                // that is already pressed is a repeat.
+                if self.key_repeat
+                    && action == Action::Press
+                    && key != Key::Unknown
+                    && self.key_states[key as usize] == Action::Press
+                {
+                    action = Action::Repeat;
+                } else {
+                    self.key_states[key as usize] = action;
+                }
+
+                let _ = self
+                    .out_events
+                    .send(WindowEvent::Key(key, action, modifiers));
+            }
+            glutin::event::WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = translate_modifiers(modifiers);
+            }
+            glutin::event::WindowEvent::ReceivedCharacter(c) => {
+                // NOTE: glutin does not report IME composition events yet so
+                // committed IME text only reaches us through this event.
+                let _ = self.out_events.send(WindowEvent::Char(c));
+            }
+            _ => {}
+        }
+    }
+}
+
 impl AbstractCanvas for GLCanvas {
     fn open(
         title: &str,
@@ -34,67 +285,108 @@ impl AbstractCanvas for GLCanvas {
         height: u32,
         canvas_setup: Option<CanvasSetup>,
         out_events: Sender<WindowEvent>,
-    ) -> Self {
-        #[cfg(any(
-            target_os = "linux",
-            target_os = "dragonfly",
-            target_os = "freebsd",
-            target_os = "netbsd",
-            target_os = "openbsd"
-        ))]
-        let events = {
-            use glutin::platform::unix::EventLoopExtUnix;
-            EventLoop::new_any_thread()
-        };
-        #[cfg(windows)]
-        let events = {
-            use glutin::platform::windows::EventLoopExtWindows;
-            EventLoop::new_any_thread()
-        };
-        #[cfg(not(any(
-            target_os = "linux",
-            target_os = "dragonfly",
-            target_os = "freebsd",
-            target_os = "netbsd",
-            target_os = "openbsd",
-            windows
-        )))]
-        let events = EventLoop::new();
-
+    ) -> Result<Self, WindowCreationError> {
+        let canvas_setup = canvas_setup.unwrap_or_default();
         let window = WindowBuilder::new()
             .with_title(title)
             .with_inner_size(LogicalSize::new(width as f64, height as f64))
-            .with_visible(!hide);
-        let canvas_setup = canvas_setup.unwrap_or(CanvasSetup {
-            vsync: true,
-            samples: NumSamples::Zero,
+            // The window is shown once it is moved to its initial position.
+            .with_visible(!hide && canvas_setup.position.is_none())
+            .with_transparent(canvas_setup.transparent);
+        // The requested configuration is tried first. If it fails, we fall back to any
+        // OpenGL/OpenGL ES version without multisampling, and finally to a context that
+        // is allowed to be software-rendered.
+        let configs = [
+            (
+                canvas_setup.samples as u16,
+                GlRequest::GlThenGles {
+                    opengl_version: (3, 2),
+                    opengles_version: (2, 0),
+                },
+                Some(true),
+            ),
+            (0, GlRequest::Latest, Some(true)),
+            (0, GlRequest::Latest, None),
+        ];
+        let mut last_error = None;
+        let mut context = None;
+        // The context of any other open window, to share the OpenGL objects with.
+        let shared_context = CONTEXTS.with(|contexts| {
+            let mut contexts = contexts.borrow_mut();
+            contexts.retain(|c| c.strong_count() > 0);
+            contexts.first().and_then(|c| c.upgrade())
         });
-        let window = ContextBuilder::new()
-            .with_vsync(canvas_setup.vsync)
-            .with_multisampling(canvas_setup.samples as u16)
-            .with_gl(GlRequest::GlThenGles {
-                opengl_version: (3, 2),
-                opengles_version: (2, 0),
-            })
-            .build_windowed(window, &events)
-            .unwrap();
-        let window = unsafe { window.make_current().unwrap() };
+        let shared = shared_context.as_ref().map(|c| c.borrow());
+
+        EVENT_LOOP.with(|events| {
+            let mut events = events.borrow_mut();
+            let events = events.get_or_insert_with(new_event_loop);
+
+            for (samples, request, hardware_acceleration) in configs.iter().cloned() {
+                // The off-screen depth buffers are DEPTH24_STENCIL8 too, so the depth of the
+                // screen can be copied into them.
+                let builder = ContextBuilder::new()
+                    .with_depth_buffer(24)
+                    .with_stencil_buffer(8)
+                    .with_vsync(canvas_setup.vsync)
+                    .with_multisampling(samples)
+                    .with_gl(request)
+                    .with_hardware_acceleration(hardware_acceleration);
+                let result = match shared.as_ref().and_then(|c| c.as_ref()) {
+                    Some(shared) => builder
+                        .with_shared_lists(&**shared)
+                        .build_windowed(window.clone(), events),
+                    None => builder.build_windowed(window.clone(), events),
+                };
+
+                match result {
+                    Ok(ctx) => {
+                        context = Some(ctx);
+                        break;
+                    }
+                    Err(e) => last_error = Some(e.to_string()),
+                }
+            }
+        });
+
+        drop(shared);
+        let window = context.ok_or_else(|| {
+            WindowCreationError::ContextCreationFailed(last_error.unwrap_or_default())
+        })?;
+        let window = unsafe { window.make_current() }
+            .map_err(|(_, e)| WindowCreationError::ContextActivationFailed(e.to_string()))?;
+        let (context, window) = unsafe { window.split() };
+
+        if let Some((x, y)) = canvas_setup.position {
+            window.set_outer_position(PhysicalPosition::new(x, y));
+            window.set_visible(!hide);
+        }
+
         Context::init(|| unsafe {
-            glow::Context::from_loader_function(|name| window.get_proc_address(name) as *const _)
+            glow::Context::from_loader_function(|name| context.get_proc_address(name) as *const _)
         });
 
         let ctxt = Context::get();
         let vao = ctxt.create_vertex_array();
         ctxt.bind_vertex_array(vao.as_ref());
 
-        GLCanvas {
+        let context = Rc::new(RefCell::new(Some(context)));
+        CONTEXTS.with(|contexts| contexts.borrow_mut().push(Rc::downgrade(&context)));
+
+        Ok(GLCanvas {
+            context,
             window,
-            events,
             cursor_pos: None,
+            // Assume a visible window is focused until told otherwise.
+            focused: !hide,
             key_states: [Action::Release; Key::Unknown as usize + 1],
             button_states: [Action::Release; MouseButton::Button8 as usize + 1],
+            modifiers: Modifiers::empty(),
+            click_counter: ClickCounter::new(),
+            key_repeat: false,
+            relative_mouse_mode: false,
             out_events,
-        }
+        })
     }
 
     fn render_loop(mut callback: impl FnMut(f64) -> bool + 'static) {
@@ -105,116 +397,50 @@ impl AbstractCanvas for GLCanvas {
         }
     }
 
-    #[allow(deprecated)] // todo: refactor to avoid using the deprecated `modifiers` field
     fn poll_events(&mut self) {
-        let out_events = &mut self.out_events;
-        let window = &mut self.window;
-        let button_states = &mut self.button_states;
-        let key_states = &mut self.key_states;
-        let cursor_pos = &mut self.cursor_pos;
-
-        self.events.run_return(|event, _, control_flow| {
-            use glutin::event::Event;
-
-            match event {
-                Event::WindowEvent { event, .. } => match event {
-                    glutin::event::WindowEvent::CloseRequested => {
-                        let _ = out_events.send(WindowEvent::Close);
-                    }
-                    glutin::event::WindowEvent::Resized(physical_size) => {
-                        window.resize(physical_size);
-                        let fb_size: (u32, u32) = physical_size.into();
-                        let _ = out_events.send(WindowEvent::FramebufferSize(fb_size.0, fb_size.1));
-                    }
-                    glutin::event::WindowEvent::CursorMoved {
-                        position,
-                        modifiers,
-                        ..
-                    } => {
-                        let modifiers = translate_modifiers(modifiers);
-                        *cursor_pos = Some(position.into());
-                        let _ = out_events
-                            .send(WindowEvent::CursorPos(position.x, position.y, modifiers));
-                    }
-                    glutin::event::WindowEvent::MouseInput {
-                        state,
-                        button,
-                        modifiers,
-                        ..
-                    } => {
-                        let action = translate_action(state);
-                        let button = translate_mouse_button(button);
-                        let modifiers = translate_modifiers(modifiers);
-                        button_states[button as usize] = action;
-                        let _ =
-                            out_events.send(WindowEvent::MouseButton(button, action, modifiers));
-                    }
-                    glutin::event::WindowEvent::Touch(touch) => {
-                        let action = match touch.phase {
-                            TouchPhase::Started => TouchAction::Start,
-                            TouchPhase::Ended => TouchAction::End,
-                            TouchPhase::Moved => TouchAction::Move,
-                            TouchPhase::Cancelled => TouchAction::Cancel,
-                        };
-
-                        let _ = out_events.send(WindowEvent::Touch(
-                            touch.id,
-                            touch.location.x,
-                            touch.location.y,
-                            action,
-                            Modifiers::empty(),
-                        ));
-                    }
-                    glutin::event::WindowEvent::MouseWheel {
-                        delta, modifiers, ..
-                    } => {
-                        let (x, y) = match delta {
-                            glutin::event::MouseScrollDelta::LineDelta(dx, dy) => {
-                                (dx as f64 * 10.0, dy as f64 * 10.0)
-                            }
-                            glutin::event::MouseScrollDelta::PixelDelta(delta) => delta.into(),
-                        };
-                        let modifiers = translate_modifiers(modifiers);
-                        let _ = out_events.send(WindowEvent::Scroll(x, y, modifiers));
-                    }
-                    glutin::event::WindowEvent::KeyboardInput { input, .. } => {
-                        let action = translate_action(input.state);
-                        let key = translate_key(input.virtual_keycode);
-                        let modifiers = translate_modifiers(input.modifiers);
-                        key_states[key as usize] = action;
-                        let _ = out_events.send(WindowEvent::Key(key, action, modifiers));
-                    }
-                    glutin::event::WindowEvent::ReceivedCharacter(c) => {
-                        let _ = out_events.send(WindowEvent::Char(c));
-                    }
-                    _ => {}
-                },
-                Event::RedrawEventsCleared => {
-                    *control_flow = ControlFlow::Exit;
-                }
-                _ => {}
-            };
-        })
+        self.pump_events(false)
+    }
+
+    fn wait_events(&mut self) {
+        self.pump_events(true)
+    }
+
+    fn make_current(&mut self) {
+        let mut context = self.context.borrow_mut();
+
+        if context.as_ref().map(|c| c.is_current()) == Some(false) {
+            let c = context.take().unwrap();
+            *context = Some(match unsafe { c.make_current() } {
+                Ok(c) => c,
+                Err((c, _)) => c,
+            });
+        }
     }
 
     fn swap_buffers(&mut self) {
-        let _ = self.window.swap_buffers();
+        if let Some(context) = &*self.context.borrow() {
+            let _ = context.swap_buffers();
+        }
     }
 
     fn size(&self) -> (u32, u32) {
-        self.window.window().inner_size().into()
+        self.window.inner_size().into()
     }
 
     fn cursor_pos(&self) -> Option<(f64, f64)> {
         self.cursor_pos
     }
 
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
     fn scale_factor(&self) -> f64 {
-        self.window.window().scale_factor() as f64
+        self.window.scale_factor() as f64
     }
 
     fn set_title(&mut self, title: &str) {
-        self.window.window().set_title(title)
+        self.window.set_title(title)
     }
 
     fn set_icon(&mut self, icon: impl GenericImage<Pixel = impl Pixel<Subpixel = u8>>) {
@@ -224,30 +450,156 @@ impl AbstractCanvas for GLCanvas {
             rgba.extend_from_slice(&pixel.to_rgba().0);
         }
         let icon = glutin::window::Icon::from_rgba(rgba, width, height).unwrap();
-        self.window.window().set_window_icon(Some(icon))
+        self.window.set_window_icon(Some(icon))
+    }
+
+    fn set_size(&mut self, w: u32, h: u32) {
+        let size = PhysicalSize::new(w, h);
+        self.window.set_inner_size(size);
+        // Apply the new size right away instead of waiting for the resize event.
+        if let Some(context) = &*self.context.borrow() {
+            context.resize(size);
+        }
+        let _ = self.out_events.send(WindowEvent::FramebufferSize(w, h));
+    }
+
+    fn set_min_size(&mut self, w: u32, h: u32) {
+        self.window
+            .set_min_inner_size(Some(LogicalSize::new(w as f64, h as f64)))
+    }
+
+    fn set_max_size(&mut self, w: u32, h: u32) {
+        self.window
+            .set_max_inner_size(Some(LogicalSize::new(w as f64, h as f64)))
+    }
+
+    fn set_fullscreen(&mut self, mode: FullscreenMode) {
+        let window = &self.window;
+        let fullscreen = match mode {
+            FullscreenMode::Windowed => None,
+            FullscreenMode::Borderless(monitor) => Some(Fullscreen::Borderless(
+                monitor.and_then(|i| window.available_monitors().nth(i)),
+            )),
+            FullscreenMode::Exclusive(mode) => {
+                let video_mode =
+                    window
+                        .available_monitors()
+                        .nth(mode.monitor)
+                        .and_then(|monitor| {
+                            monitor.video_modes().find(|m| {
+                                let size = m.size();
+                                (size.width, size.height) == mode.size
+                                    && m.bit_depth() == mode.bit_depth
+                                    && m.refresh_rate() == mode.refresh_rate
+                            })
+                        });
+
+                match video_mode {
+                    Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                    // Not a video mode of any connected monitor.
+                    None => return,
+                }
+            }
+        };
+
+        window.set_fullscreen(fullscreen)
+    }
+
+    fn available_monitors(&self) -> Vec<Monitor> {
+        self.window
+            .available_monitors()
+            .enumerate()
+            .map(|(id, monitor)| {
+                let size = monitor.size();
+                let video_modes = monitor
+                    .video_modes()
+                    .map(|m| VideoMode {
+                        monitor: id,
+                        size: (m.size().width, m.size().height),
+                        bit_depth: m.bit_depth(),
+                        refresh_rate: m.refresh_rate(),
+                    })
+                    .collect();
+
+                let position = monitor.position();
+
+                Monitor {
+                    id,
+                    name: monitor.name(),
+                    position: (position.x, position.y),
+                    size: (size.width, size.height),
+                    scale_factor: monitor.scale_factor(),
+                    video_modes,
+                }
+            })
+            .collect()
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.window.set_outer_position(PhysicalPosition::new(x, y))
+    }
+
+    fn position(&self) -> Option<(i32, i32)> {
+        let position = self.window.outer_position().ok()?;
+        Some((position.x, position.y))
+    }
+
+    fn center_on_screen(&mut self) {
+        let window = &self.window;
+        let monitor = match window
+            .current_monitor()
+            .or_else(|| window.available_monitors().next())
+        {
+            Some(monitor) => monitor,
+            None => return,
+        };
+
+        let (monitor_pos, monitor_size) = (monitor.position(), monitor.size());
+        let size = window.outer_size();
+        let x = monitor_pos.x + (monitor_size.width as i32 - size.width as i32) / 2;
+        let y = monitor_pos.y + (monitor_size.height as i32 - size.height as i32) / 2;
+
+        window.set_outer_position(PhysicalPosition::new(x, y))
     }
 
     fn set_cursor_grab(&self, grab: bool) {
-        let _ = self.window.window().set_cursor_grab(grab);
+        let _ = self.window.set_cursor_grab(grab);
     }
 
     fn set_cursor_position(&self, x: f64, y: f64) {
         self.window
-            .window()
             .set_cursor_position(glutin::dpi::PhysicalPosition::new(x, y))
             .unwrap();
     }
 
     fn hide_cursor(&self, hide: bool) {
-        self.window.window().set_cursor_visible(!hide)
+        self.window.set_cursor_visible(!hide)
+    }
+
+    fn set_relative_mouse_mode(&mut self, enabled: bool) {
+        self.relative_mouse_mode = enabled;
+        let _ = self.window.set_cursor_grab(enabled);
+        self.window.set_cursor_visible(!enabled);
+    }
+
+    fn relative_mouse_mode(&self) -> bool {
+        self.relative_mouse_mode
     }
 
     fn hide(&mut self) {
-        self.window.window().set_visible(false)
+        self.window.set_visible(false)
     }
 
     fn show(&mut self) {
-        self.window.window().set_visible(true)
+        self.window.set_visible(true)
+    }
+
+    fn set_double_click_interval(&mut self, interval: Duration) {
+        self.click_counter.set_interval(interval)
+    }
+
+    fn set_key_repeat(&mut self, enabled: bool) {
+        self.key_repeat = enabled
     }
 
     fn get_mouse_button(&self, button: MouseButton) -> Action {
@@ -256,6 +608,10 @@ impl AbstractCanvas for GLCanvas {
     fn get_key(&self, key: Key) -> Action {
         self.key_states[key as usize]
     }
+
+    fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
 }
 
 fn translate_action(action: glutin::event::ElementState) -> Action {
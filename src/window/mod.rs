@@ -1,20 +1,38 @@
 //! The window, and things to handle the rendering loop and events.
 
 mod canvas;
+mod click_counter;
+mod clipboard;
+mod clipboard_error;
+mod fullscreen;
+#[cfg(feature = "gamepad")]
+mod gamepad;
 #[cfg(not(target_arch = "wasm32"))]
 mod gl_canvas;
 mod state;
 #[cfg(target_arch = "wasm32")]
+mod texture_fetch_error;
+#[cfg(target_arch = "wasm32")]
 mod webgl_canvas;
 mod window;
 mod window_cache;
+mod window_creation_error;
 
 pub(crate) use canvas::AbstractCanvas;
-pub use canvas::{Canvas, CanvasSetup, NumSamples};
+pub use canvas::{Canvas, CanvasSetup, NumSamples, RedrawMode};
+pub(crate) use click_counter::ClickCounter;
+pub(crate) use clipboard::Clipboard;
+pub use clipboard_error::ClipboardError;
+pub use fullscreen::{FullscreenMode, Monitor, VideoMode};
+#[cfg(feature = "gamepad")]
+pub(crate) use gamepad::GamepadListener;
 #[cfg(not(target_arch = "wasm32"))]
 pub use gl_canvas::GLCanvas;
 pub use state::State;
 #[cfg(target_arch = "wasm32")]
+pub use texture_fetch_error::TextureFetchError;
+#[cfg(target_arch = "wasm32")]
 pub use webgl_canvas::WebGLCanvas;
 pub use window::Window;
 pub(crate) use window_cache::WINDOW_CACHE;
+pub use window_creation_error::WindowCreationError;
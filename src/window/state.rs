@@ -10,9 +10,22 @@ use crate::window::Window;
 /// render loop to update the application state, and customize the cameras and
 /// post-processing effects to be used by the renderer.
 pub trait State: 'static {
+    /// Method called once by `Window::render_loop`, and at each call to `Window::run_return`,
+    /// before the first call to `step`.
+    ///
+    /// This is typically used to build the scene of the state.
+    fn init(&mut self, _window: &mut Window) {}
+
     /// Method called at each render loop before a rendering.
     fn step(&mut self, window: &mut Window);
 
+    /// Method called at a fixed rate, set with `Window::set_fixed_timestep`, with the duration
+    /// of a fixed step in seconds.
+    ///
+    /// At each render loop, this is called zero or more times before `step`, depending on the
+    /// time elapsed since the previous frame. This is never called if fixed steps are disabled.
+    fn fixed_step(&mut self, _window: &mut Window, _dt: f32) {}
+
     /// Unless `cameras_and_effect_and_renderer` is implemented, this method called at each render loop to retrieve
     /// the cameras and post-processing effects to be used for the next render.
     #[deprecated(
@@ -0,0 +1,30 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when a texture could not be fetched with `Window::add_texture_from_url`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TextureFetchError {
+    /// The request failed or the server answered with an error status.
+    ///
+    /// Contains the URL and the error message.
+    RequestFailed(String, String),
+    /// The fetched data is not a valid image.
+    ///
+    /// Contains the URL and the error message of the image decoder.
+    InvalidImage(String, String),
+}
+
+impl fmt::Display for TextureFetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextureFetchError::RequestFailed(url, e) => {
+                write!(f, "failed to fetch the texture {}: {}", url, e)
+            }
+            TextureFetchError::InvalidImage(url, e) => {
+                write!(f, "failed to decode the texture {}: {}", url, e)
+            }
+        }
+    }
+}
+
+impl Error for TextureFetchError {}
@@ -4,27 +4,36 @@ use std::cell::RefCell;
 use std::ops::DerefMut;
 use std::rc::Rc;
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
 use crate::context::Context;
-use crate::event::{Action, Key, Modifiers, MouseButton, TouchAction, WindowEvent};
+use crate::event::{Action, ImeEvent, Key, Modifiers, MouseButton, TouchAction, WindowEvent};
 use crate::verify;
-use crate::window::{AbstractCanvas, CanvasSetup};
+use crate::window::{
+    AbstractCanvas, CanvasSetup, ClickCounter, FullscreenMode, Monitor, WindowCreationError,
+};
 use image::{GenericImage, Pixel};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{
-    EventTarget, HtmlCanvasElement, KeyboardEvent, MouseEvent, TouchEvent, UiEvent, WheelEvent,
+    CompositionEvent, EventTarget, FocusEvent, HtmlCanvasElement, KeyboardEvent, MouseEvent,
+    TouchEvent, UiEvent, WebGlContextAttributes, WheelEvent,
 };
 
 struct WebGLCanvasData {
     canvas: HtmlCanvasElement,
     cursor_pos: Option<(f64, f64)>,
+    focused: bool,
     key_states: [Action; Key::Unknown as usize + 1],
     button_states: [Action; MouseButton::Button8 as usize + 1],
+    modifiers: Modifiers,
+    click_counter: ClickCounter,
+    key_repeat: bool,
     pending_events: Vec<WindowEvent>,
     out_events: Sender<WindowEvent>,
     scale_factor: f64,
     mouse_capture_state: MouseCaptureState,
+    relative_mouse_mode: bool,
 }
 
 #[derive(PartialEq, Eq)]
@@ -40,6 +49,8 @@ enum EventListener {
     Touch(EventListenerHandle<dyn FnMut(TouchEvent)>),
     Wheel(EventListenerHandle<dyn FnMut(WheelEvent)>),
     Keyboard(EventListenerHandle<dyn FnMut(KeyboardEvent)>),
+    Composition(EventListenerHandle<dyn FnMut(CompositionEvent)>),
+    Focus(EventListenerHandle<dyn FnMut(FocusEvent)>),
 }
 
 struct EventListenerHandle<T: ?Sized> {
@@ -101,6 +112,13 @@ impl Drop for WebGLCanvas {
     }
 }
 
+impl WebGLCanvas {
+    /// The underlying HTML canvas element.
+    pub fn html_canvas(&self) -> HtmlCanvasElement {
+        self.data.borrow().canvas.clone()
+    }
+}
+
 impl AbstractCanvas for WebGLCanvas {
     fn open(
         _: &str,
@@ -109,7 +127,7 @@ impl AbstractCanvas for WebGLCanvas {
         _: u32,
         _setup: Option<CanvasSetup>,
         out_events: Sender<WindowEvent>,
-    ) -> Self {
+    ) -> Result<Self, WindowCreationError> {
         fn get_scale_factor() -> f64 {
             web_sys::window().unwrap().device_pixel_ratio()
         }
@@ -119,19 +137,19 @@ impl AbstractCanvas for WebGLCanvas {
         let initial_scale_factor = get_scale_factor();
         let canvas: HtmlCanvasElement = document
             .get_element_by_id("canvas")
-            .expect("No canvas found.")
-            .dyn_into::<HtmlCanvasElement>()
-            .expect("Canvas element is not an actual canvas.");
-
-        Context::init(|| {
-            let webgl_context = canvas
-                .get_context("webgl")
-                .unwrap()
-                .unwrap()
-                .dyn_into::<web_sys::WebGlRenderingContext>()
-                .unwrap();
-            glow::Context::from_webgl1_context(webgl_context)
-        });
+            .and_then(|e| e.dyn_into::<HtmlCanvasElement>().ok())
+            .ok_or(WindowCreationError::CanvasNotFound)?;
+
+        // Request a stencil buffer, used to draw object outlines.
+        let mut attributes = WebGlContextAttributes::new();
+        let _ = attributes.stencil(true);
+        let webgl_context = canvas
+            .get_context_with_context_options("webgl", &attributes)
+            .ok()
+            .flatten()
+            .and_then(|c| c.dyn_into::<web_sys::WebGlRenderingContext>().ok())
+            .ok_or(WindowCreationError::WebGlUnavailable)?;
+        Context::init(|| glow::Context::from_webgl1_context(webgl_context));
 
         let w = (canvas.offset_width() as f64 * initial_scale_factor) as u32;
         let h = (canvas.offset_height() as f64 * initial_scale_factor) as u32;
@@ -149,12 +167,17 @@ impl AbstractCanvas for WebGLCanvas {
         let data = Rc::new(RefCell::new(WebGLCanvasData {
             canvas,
             cursor_pos: None,
+            focused: false,
             key_states: [Action::Release; Key::Unknown as usize + 1],
             button_states: [Action::Release; MouseButton::Button8 as usize + 1],
+            modifiers: Modifiers::empty(),
+            click_counter: ClickCounter::new(),
+            key_repeat: false,
             pending_events: vec![WindowEvent::FramebufferSize(w, h)],
             out_events,
             scale_factor: initial_scale_factor,
             mouse_capture_state: MouseCaptureState::NotCaptured,
+            relative_mouse_mode: false,
         }));
 
         let mut event_listeners = Vec::new();
@@ -205,12 +228,22 @@ impl AbstractCanvas for WebGLCanvas {
             }
             edata.mouse_capture_state = MouseCaptureState::Captured;
             let button = translate_mouse_button(&e);
+            let modifiers = translate_mouse_modifiers(&e);
             let _ = edata.pending_events.push(WindowEvent::MouseButton(
                 button,
                 Action::Press,
-                translate_mouse_modifiers(&e),
+                modifiers,
             ));
             edata.button_states[button as usize] = Action::Press;
+
+            let scale_factor = edata.scale_factor;
+            let bounding_client_rect = edata.canvas.get_bounding_client_rect();
+            let x = (e.client_x() as f64 - bounding_client_rect.x()) * scale_factor;
+            let y = (e.client_y() as f64 - bounding_client_rect.y()) * scale_factor;
+            let count = edata.click_counter.press(button, (x, y));
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::MouseClick(button, count, x, y, modifiers));
         }) as Box<dyn FnMut(_)>);
         let listener = EventListenerHandle::new(&window, "mousedown", callback);
         event_listeners.push(EventListener::Mouse(listener));
@@ -255,6 +288,15 @@ impl AbstractCanvas for WebGLCanvas {
         let edata = data.clone();
         let callback = Closure::wrap(Box::new(move |e: MouseEvent| {
             let mut edata = edata.borrow_mut();
+            if edata.relative_mouse_mode {
+                let scale_factor = edata.scale_factor;
+                let _ = edata.pending_events.push(WindowEvent::MouseMotion(
+                    e.movement_x() as f64 * scale_factor,
+                    e.movement_y() as f64 * scale_factor,
+                ));
+                // The cursor does not move while the pointer is locked.
+                return;
+            }
             match edata.mouse_capture_state {
                 MouseCaptureState::NotCaptured => {
                     if e.target().map_or(false, |target| {
@@ -395,11 +437,17 @@ impl AbstractCanvas for WebGLCanvas {
         let callback = Closure::wrap(Box::new(move |e: KeyboardEvent| {
             let mut edata = edata.borrow_mut();
             let key = translate_key(&e);
-            let _ = edata.pending_events.push(WindowEvent::Key(
-                key,
-                Action::Press,
-                translate_key_modifiers(&e),
-            ));
+            let modifiers = translate_key_modifiers(&e);
+            edata.modifiers = modifiers;
+
+            let action = if e.repeat() && edata.key_repeat {
+                Action::Repeat
+            } else {
+                Action::Press
+            };
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::Key(key, action, modifiers));
             edata.key_states[key as usize] = Action::Press;
         }) as Box<dyn FnMut(_)>);
         let listener = EventListenerHandle::new(&data.borrow().canvas, "keydown", callback);
@@ -409,20 +457,72 @@ impl AbstractCanvas for WebGLCanvas {
         let callback = Closure::wrap(Box::new(move |e: KeyboardEvent| {
             let mut edata = edata.borrow_mut();
             let key = translate_key(&e);
-            let _ = edata.pending_events.push(WindowEvent::Key(
-                key,
-                Action::Release,
-                translate_key_modifiers(&e),
-            ));
+            let modifiers = translate_key_modifiers(&e);
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::Key(key, Action::Release, modifiers));
             edata.key_states[key as usize] = Action::Release;
+            edata.modifiers = modifiers;
         }) as Box<dyn FnMut(_)>);
         let listener = EventListenerHandle::new(&data.borrow().canvas, "keyup", callback);
         event_listeners.push(EventListener::Keyboard(listener));
 
-        WebGLCanvas {
+        let edata = data.clone();
+        let callback = Closure::wrap(Box::new(move |_: CompositionEvent| {
+            let mut edata = edata.borrow_mut();
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::Ime(ImeEvent::Enabled));
+        }) as Box<dyn FnMut(_)>);
+        let listener =
+            EventListenerHandle::new(&data.borrow().canvas, "compositionstart", callback);
+        event_listeners.push(EventListener::Composition(listener));
+
+        let edata = data.clone();
+        let callback = Closure::wrap(Box::new(move |e: CompositionEvent| {
+            let mut edata = edata.borrow_mut();
+            let text = e.data().unwrap_or_default();
+            let cursor = Some((text.len(), text.len()));
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::Ime(ImeEvent::Preedit(text, cursor)));
+        }) as Box<dyn FnMut(_)>);
+        let listener =
+            EventListenerHandle::new(&data.borrow().canvas, "compositionupdate", callback);
+        event_listeners.push(EventListener::Composition(listener));
+
+        let edata = data.clone();
+        let callback = Closure::wrap(Box::new(move |e: CompositionEvent| {
+            let mut edata = edata.borrow_mut();
+            let text = e.data().unwrap_or_default();
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::Ime(ImeEvent::Preedit(String::new(), None)));
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::Ime(ImeEvent::Commit(text)));
+            let _ = edata
+                .pending_events
+                .push(WindowEvent::Ime(ImeEvent::Disabled));
+        }) as Box<dyn FnMut(_)>);
+        let listener = EventListenerHandle::new(&data.borrow().canvas, "compositionend", callback);
+        event_listeners.push(EventListener::Composition(listener));
+
+        for &(event_type, focus) in &[("focus", true), ("blur", false)] {
+            let edata = data.clone();
+            let callback = Closure::wrap(Box::new(move |_: FocusEvent| {
+                let mut edata = edata.borrow_mut();
+                edata.focused = focus;
+                let _ = edata.pending_events.push(WindowEvent::Focus(focus));
+            }) as Box<dyn FnMut(_)>);
+            let listener = EventListenerHandle::new(&data.borrow().canvas, event_type, callback);
+            event_listeners.push(EventListener::Focus(listener));
+        }
+
+        Ok(WebGLCanvas {
             data,
             event_listeners,
-        }
+        })
     }
 
     fn render_loop(mut callback: impl FnMut(f64) -> bool + 'static) {
@@ -460,6 +560,15 @@ impl AbstractCanvas for WebGLCanvas {
         }
     }
 
+    fn wait_events(&mut self) {
+        // Not supported: the browser drives the render loop.
+        self.poll_events()
+    }
+
+    fn make_current(&mut self) {
+        // Nothing to do.
+    }
+
     fn swap_buffers(&mut self) {
         // Nothing to do.
     }
@@ -476,6 +585,10 @@ impl AbstractCanvas for WebGLCanvas {
         self.data.borrow().cursor_pos
     }
 
+    fn is_focused(&self) -> bool {
+        self.data.borrow().focused
+    }
+
     fn set_title(&mut self, _: &str) {
         // Not supported.
     }
@@ -484,6 +597,60 @@ impl AbstractCanvas for WebGLCanvas {
         // Not supported.
     }
 
+    fn set_size(&mut self, w: u32, h: u32) {
+        let scale_factor = self.scale_factor();
+        let mut data = self.data.borrow_mut();
+        let style = data.canvas.style();
+        let _ = style.set_property("width", &format!("{}px", w as f64 / scale_factor));
+        let _ = style.set_property("height", &format!("{}px", h as f64 / scale_factor));
+        data.canvas.set_width(w);
+        data.canvas.set_height(h);
+        data.pending_events.push(WindowEvent::FramebufferSize(w, h));
+        data.pending_events.push(WindowEvent::Size(w, h));
+    }
+
+    fn set_min_size(&mut self, _: u32, _: u32) {
+        // Not supported.
+    }
+
+    fn set_max_size(&mut self, _: u32, _: u32) {
+        // Not supported.
+    }
+
+    fn set_fullscreen(&mut self, mode: FullscreenMode) {
+        // Browsers only allow this from a user gesture, so failures are ignored.
+        match mode {
+            FullscreenMode::Windowed => {
+                if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                    if document.fullscreen_element().is_some() {
+                        document.exit_fullscreen();
+                    }
+                }
+            }
+            FullscreenMode::Borderless(_) | FullscreenMode::Exclusive(_) => {
+                let _ = self.data.borrow().canvas.request_fullscreen();
+            }
+        }
+    }
+
+    fn available_monitors(&self) -> Vec<Monitor> {
+        // Not supported.
+        Vec::new()
+    }
+
+    fn set_position(&mut self, _: i32, _: i32) {
+        // Not supported.
+    }
+
+    fn position(&self) -> Option<(i32, i32)> {
+        // Not supported.
+        None
+    }
+
+    fn center_on_screen(&mut self) {
+        // Not supported.
+    }
+
     fn set_cursor_grab(&self, _: bool) {
         // Not supported.
     }
@@ -496,6 +663,22 @@ impl AbstractCanvas for WebGLCanvas {
         // Not supported.
     }
 
+    fn set_relative_mouse_mode(&mut self, enabled: bool) {
+        // Browsers only lock the pointer from a user gesture, so failures are ignored.
+        let mut data = self.data.borrow_mut();
+        data.relative_mouse_mode = enabled;
+
+        if enabled {
+            data.canvas.request_pointer_lock();
+        } else if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            document.exit_pointer_lock();
+        }
+    }
+
+    fn relative_mouse_mode(&self) -> bool {
+        self.data.borrow().relative_mouse_mode
+    }
+
     fn hide(&mut self) {
         // Not supported.
     }
@@ -504,12 +687,24 @@ impl AbstractCanvas for WebGLCanvas {
         // Not supported.
     }
 
+    fn set_double_click_interval(&mut self, interval: Duration) {
+        self.data.borrow_mut().click_counter.set_interval(interval)
+    }
+
+    fn set_key_repeat(&mut self, enabled: bool) {
+        self.data.borrow_mut().key_repeat = enabled
+    }
+
     fn get_mouse_button(&self, button: MouseButton) -> Action {
         self.data.borrow().button_states[button as usize]
     }
     fn get_key(&self, key: Key) -> Action {
         self.data.borrow().key_states[key as usize]
     }
+
+    fn modifiers(&self) -> Modifiers {
+        self.data.borrow().modifiers
+    }
 }
 
 fn translate_mouse_modifiers(event: &MouseEvent) -> Modifiers {
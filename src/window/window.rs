@@ -4,43 +4,64 @@
  */
 use std::cell::RefCell;
 use std::iter::repeat;
+use std::ops::Range;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
 use std::time::Duration;
 
-use na::{Point2, Point3, Vector2, Vector3};
+use na::{Point2, Point3, Point4, Vector2, Vector3};
 
 use crate::camera::{ArcBall, Camera};
 use crate::context::Context;
-use crate::event::{Action, EventManager, Key, WindowEvent};
+use crate::event::{Action, EventManager, Key, Modifiers, WindowEvent};
 use crate::light::Light;
 use crate::planar_camera::{FixedView, PlanarCamera};
 use crate::planar_line_renderer::PlanarLineRenderer;
 use crate::post_processing::PostProcessingEffect;
+use crate::renderer::shadow_map::ShadowMapRenderer;
+use crate::renderer::transparency::OitRenderer;
 #[cfg(feature = "conrod")]
 use crate::renderer::ConrodRenderer;
-use crate::renderer::{LineRenderer, PointRenderer, Renderer};
+use crate::renderer::{
+    LineRenderer, PointRenderer, PointShape, RectangleRenderer, Renderer, SpriteBatch,
+    SpriteBatchRenderer, TransparencyMode,
+};
 use crate::resource::{
-    FramebufferManager, Mesh, PlanarMesh, RenderTarget, Texture, TextureManager,
+    FramebufferManager, Material, MaterialManager, Mesh, MeshManager, PlanarMesh, RenderTarget,
+    Texture, TextureManager,
 };
-use crate::scene::{PlanarSceneNode, SceneNode};
-use crate::text::{Font, TextRenderer};
+use crate::scene::{PlanarSceneNode, SceneNode, SceneStats};
+use crate::text::{Font, TextAlign, TextLabel, TextLabelHandle, TextRenderer};
 use crate::verify;
 use crate::window::canvas::CanvasSetup;
-use crate::window::{Canvas, State};
+#[cfg(target_arch = "wasm32")]
+use crate::window::TextureFetchError;
+use crate::window::{
+    Canvas, Clipboard, ClipboardError, FullscreenMode, Monitor, RedrawMode, State,
+    WindowCreationError,
+};
 use image::imageops;
 use image::{GenericImage, Pixel};
 use image::{ImageBuffer, Rgb};
+use ncollide3d::bounding_volume::AABB;
 use ncollide3d::procedural::TriMesh;
+use ncollide3d::query::Ray;
 
 #[cfg(feature = "conrod")]
 use std::collections::HashMap;
+use std::collections::VecDeque;
 
 use super::window_cache::WindowCache;
 
 static DEFAULT_WIDTH: u32 = 800u32;
 static DEFAULT_HEIGHT: u32 = 600u32;
+const FPS_SAMPLES: usize = 60;
+// The duration between two updates of the window title by `set_auto_fps_title`, in seconds.
+const FPS_TITLE_PERIOD: f32 = 0.25;
+// The maximum number of fixed steps run per frame, so slow frames do not snowball.
+const MAX_FIXED_STEPS_PER_FRAME: u32 = 10;
 
 #[cfg(feature = "conrod")]
 struct ConrodContext {
@@ -63,25 +84,64 @@ impl ConrodContext {
 /// Structure representing a window and a 3D scene.
 ///
 /// This is the main interface with the 3d engine.
+///
+/// Several windows can be open at the same time on native platforms, e.g., to show different
+/// views of the same model. They share their meshes, textures and materials, but each has its
+/// own scene, cameras and events, and must be rendered separately:
+///
+/// ```no_run
+/// # use kiss3d::window::Window;
+/// let mut front = Window::new("Front");
+/// let mut side = Window::new("Side");
+///
+/// while front.render() && side.render() {}
+/// ```
 pub struct Window {
     events: Rc<Receiver<WindowEvent>>,
     unhandled_events: Rc<RefCell<Vec<WindowEvent>>>,
     min_dur_per_frame: Option<Duration>,
+    fixed_timestep: Option<f32>,
+    fixed_step_accumulator: f32,
+    redraw_mode: RedrawMode,
+    redraw_requested: bool,
     scene: SceneNode,
     scene2: PlanarSceneNode,
+    // Scratch buffer used to sort the children of the planar scene nodes.
+    scene2_render_order: Vec<usize>,
     light_mode: Light, // FIXME: move that to the scene graph
+    transparency_mode: TransparencyMode,
+    oit_renderer: Option<OitRenderer>,
+    shadow_renderer: Option<ShadowMapRenderer>,
     background: Vector3<f32>,
+    opacity: f32,
     line_renderer: LineRenderer,
     planar_line_renderer: PlanarLineRenderer,
     point_renderer: PointRenderer,
     text_renderer: TextRenderer,
+    rectangle_renderer: RectangleRenderer,
+    sprite_batch_renderer: SpriteBatchRenderer,
+    sprite_batches: Vec<SpriteBatch>,
+    text_labels: Vec<(TextLabelHandle, TextLabel)>,
+    next_text_label_id: usize,
+    raw_context_callbacks: Vec<Box<dyn FnOnce(&Context)>>,
     framebuffer_manager: FramebufferManager,
     post_process_render_target: RenderTarget,
+    post_process_chain_target: RenderTarget,
     #[cfg(not(target_arch = "wasm32"))]
     curr_time: std::time::Instant,
+    prev_frame_time: instant::Instant,
+    show_fps: bool,
+    fps_title: Option<String>,
+    fps_title_elapsed: f32,
+    ui_scale: Option<f32>,
+    wireframe_mode: bool,
+    wireframe_toggle_key: Option<Key>,
+    close_key: Option<Key>,
+    frame_times: VecDeque<f32>,
     planar_camera: Rc<RefCell<FixedView>>,
     camera: Rc<RefCell<ArcBall>>,
     should_close: bool,
+    clipboard: RefCell<Clipboard>,
     #[cfg(feature = "conrod")]
     conrod_context: ConrodContext,
     canvas: Canvas,
@@ -89,6 +149,7 @@ pub struct Window {
 
 impl Drop for Window {
     fn drop(&mut self) {
+        self.canvas.make_current();
         WindowCache::clear();
     }
 }
@@ -119,6 +180,81 @@ impl Window {
         Vector2::new(w, h)
     }
 
+    /// Shows or hides a frame rate counter in the top-right corner of the window.
+    ///
+    /// The displayed frames per second and milliseconds per frame are averaged over the last
+    /// frames.
+    #[inline]
+    pub fn set_show_fps(&mut self, show: bool) {
+        self.show_fps = show;
+        self.frame_times.clear();
+    }
+
+    /// Sets whether the objects of the scene are drawn as wireframes.
+    ///
+    /// In wireframe mode, the surfaces of the objects drawn with the default material are
+    /// replaced by their edges, using their lines width and color if set. This is meant for
+    /// debugging.
+    #[inline]
+    pub fn set_wireframe_mode(&mut self, enabled: bool) {
+        self.wireframe_mode = enabled;
+    }
+
+    /// Whether the objects of the scene are drawn as wireframes.
+    #[inline]
+    pub fn wireframe_mode(&self) -> bool {
+        self.wireframe_mode
+    }
+
+    /// Switches the wireframe mode on or off.
+    #[inline]
+    pub fn toggle_wireframe(&mut self) {
+        self.wireframe_mode = !self.wireframe_mode;
+    }
+
+    /// Sets the key toggling the wireframe mode when released, or `None` to disable it.
+    ///
+    /// Defaults to `Key::F2`.
+    #[inline]
+    pub fn set_wireframe_toggle_key(&mut self, key: Option<Key>) {
+        self.wireframe_toggle_key = key;
+    }
+
+    /// Sets the key closing the window when released, or `None` to disable it.
+    ///
+    /// Defaults to `Key::Escape`.
+    #[inline]
+    pub fn set_close_key(&mut self, key: Option<Key>) {
+        self.close_key = key;
+    }
+
+    /// Sets the key resetting the default camera, or `None` to disable it.
+    ///
+    /// This only affects the default `ArcBall` camera: other cameras have their own bindings,
+    /// e.g., `ArcBall::rebind_reset_key`. Defaults to `Key::Return`.
+    #[inline]
+    pub fn set_reset_view_key(&mut self, key: Option<Key>) {
+        self.camera.borrow_mut().rebind_reset_key(key);
+    }
+
+    /// Enables or disables all the default keyboard controls of the window.
+    ///
+    /// Disabling them unbinds the keys closing the window, toggling the wireframe mode, and
+    /// resetting the default camera, so these keys can be used by the application. Enabling them
+    /// restores the default bindings. The keys can also be rebound individually with
+    /// `set_close_key`, `set_wireframe_toggle_key` and `set_reset_view_key`.
+    pub fn set_default_controls_enabled(&mut self, enabled: bool) {
+        if enabled {
+            self.set_close_key(Some(Key::Escape));
+            self.set_wireframe_toggle_key(Some(Key::F2));
+            self.set_reset_view_key(Some(Key::Return));
+        } else {
+            self.set_close_key(None);
+            self.set_wireframe_toggle_key(None);
+            self.set_reset_view_key(None);
+        }
+    }
+
     /// Sets the maximum number of frames per second. Cannot be 0. `None` means there is no limit.
     #[inline]
     pub fn set_framerate_limit(&mut self, fps: Option<u64>) {
@@ -128,11 +264,79 @@ impl Window {
         })
     }
 
+    /// Sets the duration, in seconds, of the fixed steps of the render loop. Cannot be 0. `None`
+    /// disables fixed steps, which is the default.
+    ///
+    /// When set, `State::fixed_step` is called after each frame as many times as needed to
+    /// catch up with the elapsed time, independently from the framerate. This is typically used
+    /// for physics simulations. At most 10 fixed steps are run per frame: the remaining time is
+    /// dropped so a slow simulation does not stall the rendering.
+    pub fn set_fixed_timestep(&mut self, dt: Option<f32>) {
+        if let Some(dt) = dt {
+            assert!(dt > 0.0, "The fixed timestep must be positive.");
+        }
+
+        self.fixed_timestep = dt;
+        self.fixed_step_accumulator = 0.0;
+    }
+
+    /// The duration, in seconds, of the fixed steps of the render loop, if enabled.
+    pub fn fixed_timestep(&self) -> Option<f32> {
+        self.fixed_timestep
+    }
+
+    /// The fraction of a fixed step elapsed since the last call to `State::fixed_step`.
+    ///
+    /// This is in `[0.0, 1.0)` and can be used in `State::step` to interpolate between the two
+    /// last simulation states. This is `0.0` if fixed steps are disabled.
+    pub fn fixed_step_alpha(&self) -> f32 {
+        match self.fixed_timestep {
+            Some(step) => self.fixed_step_accumulator / step,
+            None => 0.0,
+        }
+    }
+
+    /// Sets when new frames are rendered. Defaults to `RedrawMode::Continuous`.
+    ///
+    /// With `RedrawMode::OnDemand`, the rendering methods block until an event occurs or
+    /// `request_redraw` is called, which avoids wasting CPU and GPU time on static scenes. This is
+    /// not supported on the web, where the browser drives the render loop.
+    pub fn set_redraw_mode(&mut self, mode: RedrawMode) {
+        self.redraw_mode = mode;
+    }
+
+    /// When new frames are rendered.
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.redraw_mode
+    }
+
+    /// Ensures the next frame is rendered without waiting for an event.
+    ///
+    /// This is only useful with `RedrawMode::OnDemand`, e.g., after modifying the scene or
+    /// during an animation.
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
     /// Set window title
+    ///
+    /// This stops the title updates enabled by `set_auto_fps_title`.
     pub fn set_title(&mut self, title: &str) {
+        self.fps_title = None;
         self.canvas.set_title(title)
     }
 
+    /// Sets the window title to `base` followed by the current frame rate.
+    ///
+    /// The frame rate is averaged over the last frames, and the title is updated a few times per
+    /// second. Call `set_title` to stop the updates.
+    pub fn set_auto_fps_title(&mut self, base: &str) {
+        self.fps_title = Some(base.to_string());
+        // Update the title at the next frame.
+        self.fps_title_elapsed = FPS_TITLE_PERIOD;
+        self.canvas.set_title(base)
+    }
+
     /// Set the window icon. On wasm this does nothing.
     ///
     /// ```rust,should_panic
@@ -164,12 +368,127 @@ impl Window {
         self.canvas.set_cursor_position(x, y);
     }
 
+    /// Enables or disables the relative mouse mode, e.g., for first-person mouselook.
+    ///
+    /// While enabled, the cursor is hidden and locked inside of the window, and raw mouse
+    /// displacements are reported as `WindowEvent::MouseMotion` events, in addition to the
+    /// usual `WindowEvent::CursorPos` events. The `FirstPerson` camera rotates with these
+    /// displacements without requiring a mouse button to be pressed. On the web, this uses the
+    /// browser Pointer Lock API, which only succeeds in response to a user interaction.
+    pub fn set_relative_mouse_mode(&mut self, enabled: bool) {
+        self.canvas.set_relative_mouse_mode(enabled);
+    }
+
     #[inline]
     /// Toggle the cursor visibility.
     pub fn hide_cursor(&self, hide: bool) {
         self.canvas.hide_cursor(hide);
     }
 
+    /// Sets the maximum delay between two clicks for them to be reported as a double-click.
+    ///
+    /// Consecutive clicks are reported by `WindowEvent::MouseClick`. The default is 500ms.
+    pub fn set_double_click_interval(&mut self, interval: Duration) {
+        self.canvas.set_double_click_interval(interval);
+    }
+
+    /// Enables or disables the reporting of key auto-repeat events with `Action::Repeat`.
+    ///
+    /// When enabled, holding a key down generates `WindowEvent::Key` events with the
+    /// `Action::Repeat` action after the initial `Action::Press`. When disabled (the default),
+    /// these auto-repeat events are reported with `Action::Press`.
+    pub fn set_key_repeat(&mut self, enabled: bool) {
+        self.canvas.set_key_repeat(enabled);
+    }
+
+    /// The canvas this window renders to.
+    ///
+    /// Use `Canvas::platform_canvas` to access the underlying glutin window or HTML canvas.
+    #[inline]
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Makes the OpenGL context of this window the current one.
+    ///
+    /// This is done automatically before rendering, so this is only needed to issue OpenGL calls
+    /// directly while several windows are open.
+    #[inline]
+    pub fn make_current(&mut self) {
+        self.canvas.make_current()
+    }
+
+    /// The OpenGL/WebGL context shared by every window.
+    ///
+    /// This can be used to render with another glow-based library into the same context.
+    #[inline]
+    pub fn gl_context(&self) -> Arc<glow::Context> {
+        Context::get().ctxt.context.clone()
+    }
+
+    /// Sets the minimum size of the window, in logical pixels.
+    ///
+    /// This has no effect on the web.
+    pub fn set_min_size(&mut self, w: u32, h: u32) {
+        self.canvas.set_min_size(w, h);
+    }
+
+    /// Sets the size of the window, in physical pixels, as returned by `Window::size`.
+    ///
+    /// The framebuffer and the cameras are resized as if the user resized the window. The
+    /// window manager may choose a different size, which is then applied once the window is
+    /// actually resized. On the web, this sets the size of the canvas.
+    pub fn set_size(&mut self, w: u32, h: u32) {
+        self.canvas.set_size(w, h);
+    }
+
+    /// Sets the maximum size of the window, in logical pixels.
+    ///
+    /// This has no effect on the web.
+    pub fn set_max_size(&mut self, w: u32, h: u32) {
+        self.canvas.set_max_size(w, h);
+    }
+
+    /// Sets the fullscreen mode of the window.
+    ///
+    /// Monitors and video modes are identified by the entries returned by
+    /// `Window::available_monitors`. Requesting a video mode that no connected monitor supports
+    /// leaves the window unchanged. On the web, this uses the browser Fullscreen API, which only
+    /// succeeds in response to a user interaction, and the monitor or video mode is ignored.
+    pub fn set_fullscreen(&mut self, mode: FullscreenMode) {
+        self.canvas.set_fullscreen(mode);
+    }
+
+    /// The monitors connected to the system, and their supported video modes.
+    ///
+    /// This is always empty on the web.
+    pub fn available_monitors(&self) -> Vec<Monitor> {
+        self.canvas.available_monitors()
+    }
+
+    /// Moves the top-left corner of the window to the given desktop position, in physical pixels.
+    ///
+    /// Use `CanvasSetup::position` to choose the position of the window when it is created. This
+    /// has no effect on the web.
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.canvas.set_position(x, y)
+    }
+
+    /// The desktop position of the top-left corner of the window, in physical pixels.
+    ///
+    /// Returns `None` if the platform does not support it, e.g., on the web or on Wayland.
+    pub fn position(&self) -> Option<(i32, i32)> {
+        self.canvas.position()
+    }
+
+    /// Centers the window on the monitor it is currently on, or on the first monitor returned by
+    /// `Window::available_monitors` if it cannot be determined.
+    ///
+    /// This has no effect on the web.
+    pub fn center_on_screen(&mut self) {
+        self.canvas.center_on_screen()
+    }
+
     /// Closes the window.
     #[inline]
     pub fn close(&mut self) {
@@ -188,6 +507,24 @@ impl Window {
         self.canvas.show()
     }
 
+    /// Sets the opacity of the window, between `0.0` (fully transparent) and `1.0` (opaque).
+    ///
+    /// On native platforms, this only has an effect if the window was created with
+    /// `CanvasSetup::transparent` set to `true`, and if the platform supports transparent windows.
+    /// The colors of the whole frame are multiplied by `opacity` and its alpha channel is set to
+    /// `opacity`, so its blending with what is behind the window is up to the window system. On
+    /// the web, the page behind the canvas shows through.
+    #[inline]
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.max(0.0).min(1.0);
+    }
+
+    /// The opacity of the window.
+    #[inline]
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
     /// Sets the background color.
     #[inline]
     pub fn set_background_color(&mut self, r: f32, g: f32, b: f32) {
@@ -204,6 +541,15 @@ impl Window {
         self.point_renderer.set_point_size(pt_size);
     }
 
+    /// Sets the shape of all points that will be rendered.
+    ///
+    /// Round points are discs with antialiased edges, which look nicer for point clouds than the
+    /// default squares.
+    #[inline]
+    pub fn set_point_shape(&mut self, shape: PointShape) {
+        self.point_renderer.set_point_shape(shape);
+    }
+
     /// Set the width of all lines that will be rendered.
     #[inline]
     pub fn set_line_width(&mut self, line_width: f32) {
@@ -220,6 +566,24 @@ impl Window {
         self.line_renderer.draw_line(*a, *b, *color);
     }
 
+    /// Adds the 12 edges of an axis-aligned bounding box to be drawn during the next render.
+    ///
+    /// The box is being drawn only during the next frame after this call.
+    /// Therefore, this call must be executed at as many frames as you want it to remain visible.
+    pub fn draw_aabb(&mut self, aabb: &AABB<f32>, color: &Point3<f32>) {
+        let extents = aabb.extents();
+        let center = aabb.center();
+        let (corners, edges) = crate::procedural::wire_cube(extents.x, extents.y, extents.z);
+
+        for edge in edges {
+            self.line_renderer.draw_line(
+                corners[edge.x as usize] + center.coords,
+                corners[edge.y as usize] + center.coords,
+                *color,
+            );
+        }
+    }
+
     /// Draws a 2D line to be drawn during the next render.
     ///
     /// The line is being drawn only during the next frame after this call.
@@ -235,6 +599,19 @@ impl Window {
         self.point_renderer.draw_point(*pt, *color);
     }
 
+    /// Adds a filled screen-space rectangle to be drawn during the next frame.
+    ///
+    /// The corners are given in pixels, the origin being the top-left corner of the screen. The
+    /// rectangle is drawn on top of the scenes, but below the text. It is alpha-blended using the
+    /// fourth component of `color`.
+    ///
+    /// The rectangle is being drawn only during the next frame after this call.
+    /// Therefore, this call must be executed at as many frames as you want it to remain visible.
+    #[inline]
+    pub fn draw_rectangle(&mut self, min: &Point2<f32>, max: &Point2<f32>, color: &Point4<f32>) {
+        self.rectangle_renderer.draw_rectangle(*min, *max, *color);
+    }
+
     /// Adds a string to be drawn during the next frame.
     #[inline]
     pub fn draw_text(
@@ -245,9 +622,139 @@ impl Window {
         font: &Rc<Font>,
         color: &Point3<f32>,
     ) {
+        let scale = scale * self.ui_scale();
         self.text_renderer.draw_text(text, pos, scale, font, color);
     }
 
+    /// Adds a string to be drawn during the next frame, hidden by the objects of the 3D scene
+    /// closer to the camera.
+    ///
+    /// See `TextRenderer::draw_text_with_depth` for details on `depth`. The text is not
+    /// occluded if post-processing effects are enabled.
+    #[inline]
+    pub fn draw_text_with_depth(
+        &mut self,
+        text: &str,
+        pos: &Point2<f32>,
+        depth: f32,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+    ) {
+        let scale = scale * self.ui_scale();
+        self.text_renderer
+            .draw_text_with_depth(text, pos, depth, scale, font, color);
+    }
+
+    /// Rasterizes the glyphs of `chars` with the given font and scale ahead of their first use.
+    ///
+    /// See `TextRenderer::preload` for details.
+    #[inline]
+    pub fn preload_text(&mut self, font: &Rc<Font>, scale: f32, chars: &str) {
+        self.text_renderer.preload(font, scale, chars);
+    }
+
+    /// Removes all the glyphs rasterized for text rendering.
+    #[inline]
+    pub fn clear_text_cache(&mut self) {
+        self.text_renderer.clear_cache();
+    }
+
+    /// Adds a string to be drawn during the next frame, with the given alignment and wrapping.
+    ///
+    /// Each line is left-aligned, centered, or right-aligned on `pos` depending on `align`. If
+    /// `max_width` is set, lines wider than `max_width` are wrapped at word boundaries.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn draw_text_ex(
+        &mut self,
+        text: &str,
+        pos: &Point2<f32>,
+        scale: f32,
+        font: &Rc<Font>,
+        color: &Point3<f32>,
+        align: TextAlign,
+        max_width: Option<f32>,
+    ) {
+        let scale = scale * self.ui_scale();
+        self.text_renderer
+            .draw_text_ex(text, pos, scale, font, color, align, max_width);
+    }
+
+    /// Runs `f` with the rendering context during the next frame.
+    ///
+    /// This allows issuing raw draw calls interleaved with the rendering of kiss3d. `f` is
+    /// called once, after the 3D and 2D scenes and the post-processing effects are rendered to
+    /// the screen framebuffer, and before the rectangles and texts are drawn. Like `draw_text`,
+    /// this must be called at each frame the custom rendering should happen.
+    ///
+    /// `f` must restore any state it modifies (bound program, buffers, textures, framebuffer,
+    /// blending, depth testing, etc.) so the rest of the frame renders correctly.
+    pub fn with_raw_context<F: FnOnce(&Context) + 'static>(&mut self, f: F) {
+        self.raw_context_callbacks.push(Box::new(f));
+    }
+
+    /// Adds a persistent text label anchored to a 3D position.
+    ///
+    /// The label is projected on the screen at each frame using the current 3D camera, and is
+    /// not drawn when it is behind the camera.
+    pub fn add_text_label(
+        &mut self,
+        text: String,
+        world_pos: Point3<f32>,
+        scale: f32,
+        color: Point3<f32>,
+    ) -> TextLabelHandle {
+        let handle = TextLabelHandle(self.next_text_label_id);
+        self.next_text_label_id += 1;
+
+        let label = TextLabel {
+            text,
+            world_pos,
+            scale,
+            color,
+            font: Font::default(),
+            depth_test: false,
+        };
+        self.text_labels.push((handle, label));
+
+        handle
+    }
+
+    /// Removes a text label previously added with `add_text_label`.
+    ///
+    /// Returns `false` if this label does not exist.
+    pub fn remove_text_label(&mut self, handle: TextLabelHandle) -> bool {
+        if let Some(i) = self.text_labels.iter().position(|l| l.0 == handle) {
+            let _ = self.text_labels.remove(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mutable reference to a text label previously added with `add_text_label`.
+    pub fn text_label_mut(&mut self, handle: TextLabelHandle) -> Option<&mut TextLabel> {
+        self.text_labels
+            .iter_mut()
+            .find(|l| l.0 == handle)
+            .map(|l| &mut l.1)
+    }
+
+    /// Adds a batch of 2D sprites sharing the same texture.
+    ///
+    /// All the sprites of the batch are drawn with a single draw call, on top of the 2D scene.
+    pub fn add_sprite_batch(&mut self, texture: Rc<Texture>) -> SpriteBatch {
+        let batch = SpriteBatch::new(texture);
+        self.sprite_batches.push(batch.clone());
+        batch
+    }
+
+    /// Removes a sprite batch previously added with `add_sprite_batch`.
+    pub fn remove_sprite_batch(&mut self, batch: &SpriteBatch) {
+        self.sprite_batches.retain(|b| !b.is_same_as(batch))
+    }
+
     /// Removes an object from the scene.
     #[deprecated(note = "Use `remove_node` instead.")]
     pub fn remove(&mut self, sn: &mut SceneNode) {
@@ -287,11 +794,63 @@ impl Window {
         self.scene.add_obj(path, mtl_dir, scale)
     }
 
+    /// Adds an obj model to the scene from the content of its obj file and, optionally, of its
+    /// mtl file.
+    ///
+    /// See `SceneNode::add_obj_from_memory` for details.
+    ///
+    /// # Arguments
+    /// * `obj_data` - the content of the obj file.
+    /// * `mtl_data` - the content of the mtl file, if any.
+    /// * `scale` - scale to apply to the model.
+    pub fn add_obj_from_memory(
+        &mut self,
+        obj_data: &str,
+        mtl_data: Option<&str>,
+        scale: Vector3<f32>,
+    ) -> SceneNode {
+        self.scene.add_obj_from_memory(obj_data, mtl_data, scale)
+    }
+
+    /// Adds a PLY mesh or point cloud to the scene.
+    ///
+    /// See `SceneNode::add_ply` for details.
+    ///
+    /// # Arguments
+    /// * `path`  - relative path to the PLY file.
+    /// * `scale` - scale to apply to the model.
+    pub fn add_ply(&mut self, path: &Path, scale: Vector3<f32>) -> SceneNode {
+        self.scene.add_ply(path, scale)
+    }
+
     /// Adds an unnamed mesh to the scene.
     pub fn add_mesh(&mut self, mesh: Rc<RefCell<Mesh>>, scale: Vector3<f32>) -> SceneNode {
         self.scene.add_mesh(mesh, scale)
     }
 
+    /// Adds an unnamed mesh to the scene, drawn with a custom material.
+    pub fn add_mesh_with_material(
+        &mut self,
+        mesh: Rc<RefCell<Mesh>>,
+        scale: Vector3<f32>,
+        material: Rc<RefCell<Box<dyn Material + 'static>>>,
+    ) -> SceneNode {
+        self.scene.add_mesh_with_material(mesh, scale, material)
+    }
+
+    /// Adds a point cloud to the scene.
+    ///
+    /// Unlike `draw_point`, the points are uploaded to the GPU once and persist across frames,
+    /// which makes this suitable for large clouds. Use `SceneNode::modify_points` to update them
+    /// and `SceneNode::set_points_size` to change their size.
+    ///
+    /// # Arguments
+    /// * `points` - the position of each point.
+    /// * `colors` - the color of each point. Must have the same length as `points`.
+    pub fn add_point_cloud(&mut self, points: &[Point3<f32>], colors: &[Point3<f32>]) -> SceneNode {
+        self.scene.add_point_cloud(points, colors)
+    }
+
     /// Adds an unnamed planar mesh to the scene.
     pub fn add_planar_mesh(
         &mut self,
@@ -307,6 +866,14 @@ impl Window {
         self.scene.add_trimesh(descr, scale)
     }
 
+    /// Registers a mesh under the given name so it can be instantiated with `add_geom_with_name`.
+    ///
+    /// Every object created from this name shares the same GPU buffers. A mesh previously
+    /// registered with the same name is replaced.
+    pub fn register_mesh(&mut self, mesh: Rc<RefCell<Mesh>>, name: &str) {
+        MeshManager::get_global_manager(|mm| mm.add(mesh.clone(), name));
+    }
+
     /// Creates and adds a new object using the geometry registered as `geometry_name`.
     pub fn add_geom_with_name(
         &mut self,
@@ -334,6 +901,26 @@ impl Window {
         self.scene.add_sphere(r)
     }
 
+    /// Adds the edges of a box to the scene, e.g., to display a bounding box. The box is
+    /// initially axis-aligned and centered at (0, 0, 0).
+    ///
+    /// # Arguments
+    /// * `wx` - the box extent along the x axis
+    /// * `wy` - the box extent along the y axis
+    /// * `wz` - the box extent along the z axis
+    pub fn add_wire_cube(&mut self, wx: f32, wy: f32, wz: f32) -> SceneNode {
+        self.scene.add_wire_cube(wx, wy, wz)
+    }
+
+    /// Adds the meridians and parallels of a sphere to the scene. The sphere is initially
+    /// centered at (0, 0, 0).
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    pub fn add_wire_sphere(&mut self, r: f32) -> SceneNode {
+        self.scene.add_wire_sphere(r)
+    }
+
     /// Adds a cone to the scene. The cone is initially centered at (0, 0, 0) and points toward the
     /// positive `y` axis.
     ///
@@ -364,6 +951,63 @@ impl Window {
         self.scene.add_capsule(r, h)
     }
 
+    /// Adds a torus to the scene. The torus is initially centered at (0, 0, 0) and lies on the
+    /// `xz` plane.
+    ///
+    /// # Arguments
+    /// * `major_r` - the distance from the torus center to the center of its tube
+    /// * `minor_r` - the radius of the tube
+    pub fn add_torus(&mut self, major_r: f32, minor_r: f32) -> SceneNode {
+        self.scene.add_torus(major_r, minor_r)
+    }
+
+    /// Adds a prism to the scene, obtained by extruding a closed 2D outline along the `y` axis.
+    /// The bottom of the prism lies on the `xz` plane.
+    ///
+    /// # Arguments
+    /// * `outline` - the closed polyline to extrude
+    /// * `height` - the extrusion length along the `y` axis
+    pub fn add_extrusion(&mut self, outline: &[Point2<f32>], height: f32) -> SceneNode {
+        self.scene.add_extrusion(outline, height)
+    }
+
+    /// Adds a double-sided flat disk to the scene. The disk is initially centered at (0, 0, 0)
+    /// and lies on the `xz` plane.
+    ///
+    /// # Arguments
+    /// * `r` - the disk radius
+    /// * `segments` - the number of subdivisions of the disk boundary
+    pub fn add_disk(&mut self, r: f32, segments: u32) -> SceneNode {
+        self.scene.add_disk(r, segments)
+    }
+
+    /// Adds a geodesic sphere to the scene. The sphere is initially centered at (0, 0, 0).
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    /// * `subdivisions` - the number of subdivisions of the initial icosahedron
+    pub fn add_icosphere(&mut self, r: f32, subdivisions: u32) -> SceneNode {
+        self.scene.add_icosphere(r, subdivisions)
+    }
+
+    /// Adds a double-sided part of a sphere to the scene, e.g., a dome. The sphere is initially
+    /// centered at (0, 0, 0).
+    ///
+    /// See `procedural::sphere_sector` for details about the angles.
+    ///
+    /// # Arguments
+    /// * `r` - the sphere radius
+    /// * `phi_range` - the range of polar angles from the `+y` axis, in radians
+    /// * `theta_range` - the range of azimuthal angles around the `y` axis, in radians
+    pub fn add_sphere_sector(
+        &mut self,
+        r: f32,
+        phi_range: Range<f32>,
+        theta_range: Range<f32>,
+    ) -> SceneNode {
+        self.scene.add_sphere_sector(r, phi_range, theta_range)
+    }
+
     /// Adds a 2D capsule to the scene. The capsule is initially centered at (0, 0) and has its
     /// principal axis aligned with the `y` axis.
     ///
@@ -406,6 +1050,67 @@ impl Window {
         TextureManager::get_global_manager(|tm| tm.add(path, name))
     }
 
+    /// The width and height, in pixels, of the texture registered as `name`.
+    ///
+    /// Returns `None` if no texture is registered with this name.
+    pub fn texture_size(&self, name: &str) -> Option<(u32, u32)> {
+        TextureManager::get_global_manager(|tm| tm.get_with_size(name)).map(|(_, size)| size)
+    }
+
+    /// Fetches an image over HTTP, registers it as a texture, and returns a reference to it.
+    ///
+    /// The texture is registered in the `TextureManager` under `name` once the returned future
+    /// completes. If a texture with the same name already exists, it is returned instead. Returns
+    /// an error if the request fails or if the fetched data is not a valid image.
+    #[cfg(target_arch = "wasm32")]
+    pub fn add_texture_from_url(
+        &mut self,
+        url: &str,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Rc<Texture>, TextureFetchError>> {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+
+        let url = url.to_string();
+        let name = name.to_string();
+
+        async move {
+            if let Some(tex) = TextureManager::get_global_manager(|tm| tm.get(&name)) {
+                return Ok(tex);
+            }
+
+            let request_failed = |e: wasm_bindgen::JsValue| {
+                TextureFetchError::RequestFailed(url.clone(), format!("{:?}", e))
+            };
+            let window = web_sys::window().expect("No global window found.");
+            let response = JsFuture::from(window.fetch_with_str(&url))
+                .await
+                .map_err(request_failed)?
+                .dyn_into::<web_sys::Response>()
+                .map_err(request_failed)?;
+
+            if !response.ok() {
+                return Err(TextureFetchError::RequestFailed(
+                    url.clone(),
+                    format!("HTTP status {}", response.status()),
+                ));
+            }
+
+            let buffer = JsFuture::from(response.array_buffer().map_err(request_failed)?)
+                .await
+                .map_err(request_failed)?;
+            let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+            let mut image = Some(
+                image::load_from_memory(&bytes)
+                    .map_err(|e| TextureFetchError::InvalidImage(url.clone(), e.to_string()))?,
+            );
+
+            Ok(TextureManager::get_global_manager(|tm| {
+                tm.add_image(image.take().unwrap(), &name)
+            }))
+        }
+    }
+
     /// Adds a rectangle to the scene. The rectangle is initially axis-aligned and centered at (0, 0, 0).
     ///
     /// # Arguments
@@ -415,6 +1120,22 @@ impl Window {
         self.scene2.add_rectangle(wx, wy)
     }
 
+    /// Adds a rectangle with rounded corners to the scene. The rectangle is initially
+    /// axis-aligned and centered at (0, 0).
+    ///
+    /// # Arguments
+    /// * `wx` - the rectangle extent along the x axis
+    /// * `wy` - the rectangle extent along the y axis
+    /// * `corner_radius` - the radius of the corners, clamped to half the smallest extent
+    pub fn add_rounded_rectangle(
+        &mut self,
+        wx: f32,
+        wy: f32,
+        corner_radius: f32,
+    ) -> PlanarSceneNode {
+        self.scene2.add_rounded_rectangle(wx, wy, corner_radius)
+    }
+
     /// Adds a circle to the scene. The circle is initially centered at (0, 0, 0).
     ///
     /// # Arguments
@@ -445,11 +1166,102 @@ impl Window {
         self.canvas.scale_factor()
     }
 
+    /// Forces the scale factor used for rendering, ignoring the one reported by the platform.
+    ///
+    /// This is useful to get the same rendering on every screen, e.g., for screenshot tests.
+    /// Set to `None` to use the platform scale factor again.
+    pub fn set_scale_factor_override(&mut self, factor: Option<f64>) {
+        self.canvas.set_scale_factor_override(factor)
+    }
+
+    /// The scale of the user interface, i.e., the number of pixels per UI point.
+    ///
+    /// This is the value set by `set_ui_scale` if any, or the scale factor of the screen
+    /// otherwise. The size of the text drawn with `draw_text`, `draw_text_ex` and of the text
+    /// labels is multiplied by this scale.
+    pub fn ui_scale(&self) -> f32 {
+        self.ui_scale
+            .unwrap_or_else(|| self.canvas.scale_factor() as f32)
+    }
+
+    /// Sets the scale of the user interface, independently from the scale factor of the screen.
+    ///
+    /// This sets the number of pixels per point of the conrod UI and of the FPS counter. The
+    /// size of the text drawn with `draw_text`, `draw_text_ex` and of the text labels is
+    /// multiplied by `scale` too. Unlike `set_scale_factor_override`, this does not affect the
+    /// rendering of the scenes.
+    pub fn set_ui_scale(&mut self, scale: f32) {
+        assert!(scale > 0.0, "The UI scale must be positive.");
+        self.ui_scale = Some(scale);
+    }
+
+    /// Uses the scale factor of the screen as the scale of the user interface again.
+    pub fn reset_ui_scale(&mut self) {
+        self.ui_scale = None;
+    }
+
     /// Sets the light mode. Only one light is supported.
     pub fn set_light(&mut self, pos: Light) {
         self.light_mode = pos;
     }
 
+    /// Sets how the transparent objects of the scene are rendered.
+    ///
+    /// Defaults to `TransparencyMode::Sorted`. The off-screen buffers needed by
+    /// `TransparencyMode::WeightedBlended` are allocated the first time it is enabled. If the
+    /// context cannot render to half-float buffers, `TransparencyMode::Sorted` is used instead.
+    pub fn set_transparency_mode(&mut self, mode: TransparencyMode) {
+        let mode = if mode == TransparencyMode::WeightedBlended
+            && !Context::get().supports_half_float_render_targets()
+        {
+            TransparencyMode::Sorted
+        } else {
+            mode
+        };
+
+        if mode == TransparencyMode::WeightedBlended && self.oit_renderer.is_none() {
+            let (w, h) = self.canvas.size();
+            self.oit_renderer = Some(OitRenderer::new(w as f32, h as f32));
+        }
+
+        self.transparency_mode = mode;
+    }
+
+    /// The technique used to render the transparent objects of the scene.
+    pub fn transparency_mode(&self) -> TransparencyMode {
+        self.transparency_mode
+    }
+
+    /// Enables or disables the shadows cast by the objects of the scene. Disabled by default.
+    ///
+    /// Shadows are only cast by a `Light::Absolute` light, from the objects located within 90
+    /// degrees of the direction from the light to the origin. Only objects drawn with the
+    /// default material receive shadows.
+    pub fn set_shadows(&mut self, enabled: bool) {
+        if !enabled {
+            self.shadow_renderer = None;
+            ShadowMapRenderer::disable();
+        } else if self.shadow_renderer.is_none() {
+            self.shadow_renderer = Some(ShadowMapRenderer::new());
+        }
+    }
+
+    /// Whether the objects of the scene cast shadows.
+    pub fn shadows(&self) -> bool {
+        self.shadow_renderer.is_some()
+    }
+
+    /// Sets the color of the ambient light.
+    ///
+    /// The ambient light is added to the lighting of every object drawn with the default
+    /// material, regardless of its orientation relative to the light. Defaults to
+    /// `(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0)`.
+    pub fn set_ambient_light(&mut self, color: Point3<f32>) {
+        MaterialManager::get_global_manager(|mm| {
+            mm.get_default().borrow_mut().set_ambient_light(&color)
+        });
+    }
+
     /// Retrieve a mutable reference to the UI based on Conrod.
     #[cfg(feature = "conrod")]
     pub fn conrod_ui_mut(&mut self) -> &mut conrod::Ui {
@@ -512,6 +1324,18 @@ impl Window {
         Window::do_new(title, false, DEFAULT_WIDTH, DEFAULT_HEIGHT, None)
     }
 
+    /// Opens a window, returning an error instead of panicking if it cannot be created.
+    ///
+    /// If the default OpenGL configuration is not supported, fallback configurations are
+    /// tried before giving up: any OpenGL version without multisampling, then a
+    /// software-rendered context.
+    ///
+    /// # Arguments
+    /// * `title` - the window title
+    pub fn try_new(title: &str) -> Result<Window, WindowCreationError> {
+        Window::do_try_new(title, false, DEFAULT_WIDTH, DEFAULT_HEIGHT, None)
+    }
+
     /// Opens a window with a custom size then calls a user-defined procedure.
     ///
     /// # Arguments
@@ -535,26 +1359,52 @@ impl Window {
         height: u32,
         setup: Option<CanvasSetup>,
     ) -> Window {
+        Window::do_try_new(title, hide, width, height, setup).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn do_try_new(
+        title: &str,
+        hide: bool,
+        width: u32,
+        height: u32,
+        setup: Option<CanvasSetup>,
+    ) -> Result<Window, WindowCreationError> {
         let (event_send, event_receive) = mpsc::channel();
-        let canvas = Canvas::open(title, hide, width, height, setup, event_send);
+        let canvas = Canvas::try_open(title, hide, width, height, setup, event_send)?;
 
         init_gl();
         WindowCache::populate();
 
         let mut usr_window = Window {
             should_close: false,
+            clipboard: RefCell::new(Clipboard::new()),
             min_dur_per_frame: None,
+            fixed_timestep: None,
+            fixed_step_accumulator: 0.0,
+            redraw_mode: RedrawMode::Continuous,
+            redraw_requested: true,
             canvas,
             events: Rc::new(event_receive),
             unhandled_events: Rc::new(RefCell::new(Vec::new())),
             scene: SceneNode::new_empty(),
             scene2: PlanarSceneNode::new_empty(),
+            scene2_render_order: Vec::new(),
             light_mode: Light::Absolute(Point3::new(0.0, 10.0, 0.0)),
+            transparency_mode: TransparencyMode::Sorted,
+            oit_renderer: None,
+            shadow_renderer: None,
             background: Vector3::new(0.0, 0.0, 0.0),
+            opacity: 1.0,
             line_renderer: LineRenderer::new(),
             planar_line_renderer: PlanarLineRenderer::new(),
             point_renderer: PointRenderer::new(),
             text_renderer: TextRenderer::new(),
+            rectangle_renderer: RectangleRenderer::new(),
+            sprite_batch_renderer: SpriteBatchRenderer::new(),
+            sprite_batches: Vec::new(),
+            text_labels: Vec::new(),
+            next_text_label_id: 0,
+            raw_context_callbacks: Vec::new(),
             #[cfg(feature = "conrod")]
             conrod_context: ConrodContext::new(width as f64, height as f64),
             post_process_render_target: FramebufferManager::new_render_target(
@@ -562,9 +1412,23 @@ impl Window {
                 height as usize,
                 true,
             ),
+            post_process_chain_target: FramebufferManager::new_render_target(
+                width as usize,
+                height as usize,
+                true,
+            ),
             framebuffer_manager: FramebufferManager::new(),
             #[cfg(not(target_arch = "wasm32"))]
             curr_time: std::time::Instant::now(),
+            prev_frame_time: instant::Instant::now(),
+            show_fps: false,
+            fps_title: None,
+            fps_title_elapsed: 0.0,
+            ui_scale: None,
+            wireframe_mode: false,
+            wireframe_toggle_key: Some(Key::F2),
+            close_key: Some(Key::Escape),
+            frame_times: VecDeque::with_capacity(FPS_SAMPLES),
             planar_camera: Rc::new(RefCell::new(FixedView::new())),
             camera: Rc::new(RefCell::new(ArcBall::new(
                 Point3::new(0.0f32, 0.0, -1.0),
@@ -580,7 +1444,7 @@ impl Window {
         let light = usr_window.light_mode.clone();
         usr_window.set_light(light);
 
-        usr_window
+        Ok(usr_window)
     }
 
     /// Reference to the scene associated with this window.
@@ -595,6 +1459,14 @@ impl Window {
         &mut self.scene
     }
 
+    /// Statistics about the 3D scene of this window, e.g., to keep track of its rendering cost.
+    ///
+    /// The root node of the scene is counted, as well as the hidden nodes and objects.
+    #[inline]
+    pub fn scene_stats(&self) -> SceneStats {
+        self.scene.stats()
+    }
+
     // FIXME: give more options for the snap size and offset.
     /// Read the pixels currently displayed to the screen.
     ///
@@ -623,6 +1495,20 @@ impl Window {
         // FIXME: this is _not_ the fastest way of doing this.
         let ctxt = Context::get();
         ctxt.pixel_storei(Context::PACK_ALIGNMENT, 1);
+
+        // Pixels cannot be read directly from a multisampled framebuffer. This is not an issue on
+        // WebGL where the browser resolves the canvas itself.
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut sample_buffers = [0];
+            ctxt.get_parameter_i32_slice(Context::SAMPLE_BUFFERS, &mut sample_buffers);
+
+            if sample_buffers[0] > 0 {
+                Self::read_resolved_pixels(out, x as i32, y as i32, width as i32, height as i32);
+                return;
+            }
+        }
+
         ctxt.read_pixels(
             x as i32,
             y as i32,
@@ -633,6 +1519,42 @@ impl Window {
         );
     }
 
+    /// Reads pixels from the multisampled screen framebuffer by resolving them into a
+    /// single-sampled framebuffer first.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_resolved_pixels(out: &mut [u8], x: i32, y: i32, width: i32, height: i32) {
+        let ctxt = Context::get();
+        let renderbuffer = ctxt.create_renderbuffer();
+        let framebuffer = ctxt.create_framebuffer();
+
+        verify!(ctxt.bind_renderbuffer(renderbuffer.as_ref()));
+        verify!(ctxt.renderbuffer_storage(Context::RGBA8, width, height));
+        verify!(ctxt.bind_framebuffer(Context::DRAW_FRAMEBUFFER, framebuffer.as_ref()));
+        verify!(ctxt.framebuffer_renderbuffer(Context::COLOR_ATTACHMENT0, renderbuffer.as_ref()));
+
+        // The source and destination rectangles of a multisample resolve must have the same size.
+        verify!(ctxt.blit_framebuffer(
+            x,
+            y,
+            x + width,
+            y + height,
+            0,
+            0,
+            width,
+            height,
+            Context::COLOR_BUFFER_BIT,
+            Context::NEAREST
+        ));
+
+        verify!(ctxt.bind_framebuffer(Context::READ_FRAMEBUFFER, framebuffer.as_ref()));
+        ctxt.read_pixels(0, 0, width, height, Context::RGB, Some(out));
+
+        verify!(ctxt.bind_framebuffer(Context::FRAMEBUFFER, None));
+        verify!(ctxt.bind_renderbuffer(None));
+        verify!(ctxt.delete_framebuffer(framebuffer.as_ref()));
+        verify!(ctxt.delete_renderbuffer(renderbuffer.as_ref()));
+    }
+
     /// Get the current screen as an image
     pub fn snap_image(&self) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
         let (width, height) = self.canvas.size();
@@ -643,16 +1565,85 @@ impl Window {
         imageops::flip_vertical(&img)
     }
 
+    /// Copies the current content of the window to the system clipboard, as an image.
+    ///
+    /// The image is captured with `snap_image`. This requires the `clipboard` feature and
+    /// returns `ClipboardError::Unsupported` without it, or on the web.
+    pub fn copy_frame_to_clipboard(&self) -> Result<(), ClipboardError> {
+        let image = image::DynamicImage::ImageRgb8(self.snap_image()).to_rgba8();
+        let (width, height) = image.dimensions();
+
+        self.clipboard
+            .borrow_mut()
+            .set_image(width as usize, height as usize, image.into_raw())
+    }
+
+    /// Copies the given text to the system clipboard.
+    ///
+    /// This can be used to implement copy and cut in user interfaces. The conrod text widgets do
+    /// not expose their selection, so copying from them must be done by the application with
+    /// this method. On native platforms, this requires the `clipboard` feature and returns
+    /// `ClipboardError::Unsupported` without it. On the web, the text is written asynchronously
+    /// and the browser may refuse it outside of a user interaction.
+    pub fn set_clipboard_text(&self, text: &str) -> Result<(), ClipboardError> {
+        self.clipboard.borrow_mut().set_text(text)
+    }
+
+    /// The text currently stored in the system clipboard.
+    ///
+    /// This can be used to implement paste in user interfaces. Pressing Ctrl+V (or Cmd+V) while
+    /// a conrod widget captures the keyboard pastes this text into it. Returns `None` if the
+    /// clipboard does not contain text or could not be accessed, and always on native platforms
+    /// without the `clipboard` feature.
+    ///
+    /// On the web, the clipboard can only be read asynchronously: this starts reading it and
+    /// returns the text read by the previous call, or the last text copied with
+    /// `set_clipboard_text`. The browser may ask the user for the permission first.
+    pub fn clipboard_text(&self) -> Option<String> {
+        self.clipboard.borrow_mut().text()
+    }
+
     /// Gets the events manager that gives access to an event iterator.
+    ///
+    /// Events marked with `Event::inhibit` while iterating are not handled by the window and
+    /// the cameras during the next frame.
     pub fn events(&self) -> EventManager {
         EventManager::new(self.events.clone(), self.unhandled_events.clone())
     }
 
+    /// Polls the window events without rendering a frame, and returns them.
+    ///
+    /// This keeps the window responsive during long computations, or lets input be processed
+    /// several times per rendered frame. The returned events are also handled by the window and
+    /// the default cameras, as during rendering, so they will not be reported again by `events`.
+    pub fn pump_events(&mut self) -> Vec<WindowEvent> {
+        // Events already seen through `events` are handled, but not reported again.
+        let unhandled_events: Vec<_> = self.unhandled_events.borrow_mut().drain(..).collect();
+
+        for event in &unhandled_events {
+            self.handle_event(&mut None, &mut None, event)
+        }
+
+        self.canvas.poll_events();
+        let events: Vec<_> = self.events.try_iter().collect();
+
+        for event in &events {
+            self.handle_event(&mut None, &mut None, event)
+        }
+
+        events
+    }
+
     /// Gets the status of a key.
     pub fn get_key(&self, key: Key) -> Action {
         self.canvas.get_key(key)
     }
 
+    /// Gets the modifier keys currently pressed.
+    pub fn modifiers(&self) -> Modifiers {
+        self.canvas.modifiers()
+    }
+
     /// Gets the last known position of the mouse.
     ///
     /// The position of the mouse is automatically updated when the mouse moves over the canvas.
@@ -660,6 +1651,39 @@ impl Window {
         self.canvas.cursor_pos()
     }
 
+    /// Whether this window currently has the keyboard focus.
+    ///
+    /// This is updated from the focus events, which are also reported as `WindowEvent::Focus`.
+    /// On the web, this is the focus of the canvas element, which is needed to receive keyboard
+    /// events.
+    pub fn is_focused(&self) -> bool {
+        self.canvas.is_focused()
+    }
+
+    /// The world-space ray passing through the last known position of the mouse.
+    ///
+    /// The ray is computed from the default camera of this window. Use
+    /// `cursor_ray_with_camera` if you render with your own camera. Returns `None` if the
+    /// position of the mouse is not known.
+    pub fn cursor_ray(&self) -> Option<Ray<f32>> {
+        self.cursor_ray_with_camera(&*self.camera.borrow())
+    }
+
+    /// The world-space ray passing through the last known position of the mouse, as seen by
+    /// the given camera.
+    ///
+    /// Returns `None` if the position of the mouse is not known.
+    pub fn cursor_ray_with_camera(&self, camera: &dyn Camera) -> Option<Ray<f32>> {
+        let (x, y) = self.cursor_pos()?;
+        let size = self.size();
+        let (origin, dir) = camera.unproject(
+            &Point2::new(x as f32, y as f32),
+            &Vector2::new(size.x as f32, size.y as f32),
+        );
+
+        Some(Ray::new(origin, dir))
+    }
+
     #[inline]
     fn handle_events(
         &mut self,
@@ -668,13 +1692,26 @@ impl Window {
     ) {
         let unhandled_events = self.unhandled_events.clone(); // FIXME: could we avoid the clone?
         let events = self.events.clone(); // FIXME: could we avoid the clone?
+        let mut pending_events: Vec<_> = events.try_iter().collect();
+
+        if self.redraw_mode == RedrawMode::OnDemand
+            && !self.redraw_requested
+            && unhandled_events.borrow().is_empty()
+            && pending_events.is_empty()
+        {
+            // Nothing changed since the last frame: wait for something to happen.
+            self.canvas.wait_events();
+            pending_events.extend(events.try_iter());
+        }
+
+        self.redraw_requested = false;
 
         for event in unhandled_events.borrow().iter() {
             self.handle_event(camera, planar_camera, event)
         }
 
-        for event in events.try_iter() {
-            self.handle_event(camera, planar_camera, &event)
+        for event in pending_events.iter() {
+            self.handle_event(camera, planar_camera, event)
         }
 
         unhandled_events.borrow_mut().clear();
@@ -688,9 +1725,15 @@ impl Window {
         event: &WindowEvent,
     ) {
         match *event {
-            WindowEvent::Key(Key::Escape, Action::Release, _) | WindowEvent::Close => {
+            WindowEvent::Key(key, Action::Release, _) if Some(key) == self.close_key => {
+                self.close();
+            }
+            WindowEvent::Close => {
                 self.close();
             }
+            WindowEvent::Key(key, Action::Release, _) if Some(key) == self.wireframe_toggle_key => {
+                self.toggle_wireframe();
+            }
             WindowEvent::FramebufferSize(w, h) => {
                 self.update_viewport(w as f32, h as f32);
             }
@@ -699,10 +1742,11 @@ impl Window {
 
         #[cfg(feature = "conrod")]
         fn window_event_to_conrod_input(
-            event: WindowEvent,
+            event: &WindowEvent,
             size: Vector2<u32>,
             scale: f64,
         ) -> Option<conrod::event::Input> {
+            use crate::event::ImeEvent;
             use conrod::event::Input;
             use conrod::input::{Button, Key as CKey, Motion, MouseButton};
 
@@ -713,7 +1757,7 @@ impl Window {
                 )
             };
 
-            match event {
+            match *event {
                 WindowEvent::FramebufferSize(w, h) => {
                     Some(Input::Resize(w as f64 / scale, h as f64 / scale))
                 }
@@ -738,6 +1782,7 @@ impl Window {
                     match action {
                         Action::Press => Some(Input::Press(Button::Mouse(button))),
                         Action::Release => Some(Input::Release(Button::Mouse(button))),
+                        Action::Repeat => None,
                     }
                 }
                 WindowEvent::Key(key, action, _) => {
@@ -865,7 +1910,7 @@ impl Window {
                     };
 
                     match action {
-                        Action::Press => Some(Input::Press(Button::Keyboard(key))),
+                        Action::Press | Action::Repeat => Some(Input::Press(Button::Keyboard(key))),
                         Action::Release => Some(Input::Release(Button::Keyboard(key))),
                     }
                 }
@@ -877,22 +1922,40 @@ impl Window {
                         '\u{1b}' | // Escape
                         '\u{8}'  | // Backspace
                         '\r' | '\n' | '\t' => "".to_string(),
+                        // E.g., Ctrl+V, which pastes the clipboard instead.
+                        c if c.is_control() => "".to_string(),
                         _ => ch.to_string()
                     };
                     Some(Input::Text(string))
                 }
+                WindowEvent::Ime(ImeEvent::Commit(ref text)) => Some(Input::Text(text.clone())),
                 _ => None,
             }
         }
 
         #[cfg(feature = "conrod")]
         {
-            let (size, scale) = (self.size(), self.scale_factor());
+            // Conrod does not access the clipboard itself, so the text is pasted as text input.
+            let pasted = match *event {
+                WindowEvent::Key(Key::V, Action::Press, modifiers)
+                    if modifiers.intersects(Modifiers::Control | Modifiers::Super)
+                        && self.is_conrod_ui_capturing_keyboard() =>
+                {
+                    self.clipboard_text()
+                }
+                _ => None,
+            };
+
+            let (size, scale) = (self.size(), self.ui_scale() as f64);
             let conrod_ui = self.conrod_ui_mut();
-            if let Some(input) = window_event_to_conrod_input(*event, size, scale) {
+            if let Some(input) = window_event_to_conrod_input(event, size, scale) {
                 conrod_ui.handle_event(input);
             }
 
+            if let Some(text) = pasted {
+                conrod_ui.handle_event(conrod::event::Input::Text(text));
+            }
+
             let state = &conrod_ui.global_input().current;
             let window_id = Some(conrod_ui.window);
 
@@ -923,6 +1986,8 @@ impl Window {
     }
 
     /// Runs the render and event loop until the window is closed.
+    ///
+    /// `State::init` is called once before the first frame, then `State::step` at each frame.
     pub fn render_loop<S: State>(self, state: S) {
         // We have to be really careful here about drop order.
         //
@@ -944,9 +2009,55 @@ impl Window {
             window: self,
         };
 
+        dc.state.init(&mut dc.window);
+
         Canvas::render_loop(move |_| dc.window.do_render_with_state(&mut dc.state));
     }
 
+    /// Runs the render and event loop, calling `f` after each frame, until the window is closed
+    /// or `f` returns `false`.
+    ///
+    /// This is a closure-based alternative to `render_loop` and the `State` trait. The scene is
+    /// rendered with the default cameras and without post-processing. Like `render_loop`, this
+    /// also works on the web where the frames are driven by the browser.
+    pub fn run<F: FnMut(&mut Window) -> bool + 'static>(self, f: F) {
+        // The closure must be dropped before the window, see `render_loop`.
+        struct DropControl<F> {
+            f: F,
+            window: Window,
+        }
+
+        let mut dc = DropControl { f, window: self };
+
+        Canvas::render_loop(move |_| {
+            let window = &mut dc.window;
+            window.should_close = !window.do_render_with(None, None, None, &mut []);
+            !window.should_close && (dc.f)(window)
+        });
+    }
+
+    /// Runs the render and event loop until the window is closed, then returns.
+    ///
+    /// Unlike `render_loop`, this does not consume the window, and can be called again once it
+    /// returns, e.g., to keep showing the same window in a test harness. The window is hidden when
+    /// this returns, and shown again by the next call. `State::init` is called before the first
+    /// frame of each call. Once the window is dropped, all the resources shared
+    /// by the windows are released when no other window is open, so a new window can be opened
+    /// afterward in the same process.
+    ///
+    /// This is not available on the web where the frames are driven by the browser.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_return<S: State>(&mut self, state: &mut S) {
+        self.should_close = false;
+        self.canvas.show();
+        state.init(self);
+
+        while self.do_render_with_state(state) {}
+
+        // The window is not dropped, so it would otherwise stay on screen unresponsive.
+        self.canvas.hide();
+    }
+
     /// Render one frame using the specified state.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn render_with_state<S: State>(&mut self, state: &mut S) -> bool {
@@ -956,7 +2067,15 @@ impl Window {
     fn do_render_with_state<S: State>(&mut self, state: &mut S) -> bool {
         {
             let (camera, planar_camera, renderer, effect) = state.cameras_and_effect_and_renderer();
-            self.should_close = !self.do_render_with(camera, planar_camera, renderer, effect);
+            let mut effects: Vec<_> = effect.into_iter().collect();
+            self.should_close = !self.do_render_with(camera, planar_camera, renderer, &mut effects);
+        }
+
+        if let Some(step) = self.fixed_timestep {
+            while !self.should_close && self.fixed_step_accumulator >= step {
+                self.fixed_step_accumulator -= step;
+                state.fixed_step(self, step);
+            }
         }
 
         if !self.should_close {
@@ -982,6 +2101,16 @@ impl Window {
         self.render_with(None, None, Some(effect))
     }
 
+    /// Render using several post processing effects applied in sequence.
+    ///
+    /// Each effect reads the output of the previous one. The last effect is drawn on the screen.
+    ///
+    /// Returns `false` if the window should be closed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_with_effects(&mut self, effects: &mut [&mut dyn PostProcessingEffect]) -> bool {
+        self.do_render_with(None, None, None, effects)
+    }
+
     /// Render using a specific camera.
     ///
     /// Returns `false` if the window should be closed.
@@ -1038,7 +2167,51 @@ impl Window {
         post_processing: Option<&mut dyn PostProcessingEffect>,
     ) -> bool {
         // FIXME: for backward-compatibility, we don't accept any custom renderer here.
-        self.do_render_with(camera, planar_camera, None, post_processing)
+        let mut effects: Vec<_> = post_processing.into_iter().collect();
+        self.do_render_with(camera, planar_camera, None, &mut effects)
+    }
+
+    /// Renders the 3D scene seen by `camera` into `target` instead of the window.
+    ///
+    /// This can be called any number of times per frame, e.g., for mirrors, minimaps, or portals.
+    /// The result can then be used as the texture of objects, see `RenderTarget::texture`. Render
+    /// targets are created with `FramebufferManager::new_render_target`.
+    ///
+    /// The camera is notified of the size of the target so its aspect ratio matches. The lines,
+    /// points, planar scene, and text drawn for the current frame are not rendered into the
+    /// target, and transparent objects are always rendered with `TransparencyMode::Sorted`.
+    pub fn render_to_texture(&mut self, camera: &mut dyn Camera, target: &RenderTarget) {
+        self.canvas.make_current();
+        let (width, height) = self.canvas.size();
+        let (w, h) = target
+            .size()
+            .map(|(w, h)| (w as u32, h as u32))
+            .unwrap_or((width, height));
+
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
+
+        self.framebuffer_manager.select(target);
+
+        let ctxt = Context::get();
+        verify!(ctxt.viewport(0, 0, w as i32, h as i32));
+        verify!(ctxt.scissor(0, 0, w as i32, h as i32));
+
+        for pass in 0usize..camera.num_passes() {
+            camera.start_pass(pass, &self.canvas);
+            self.clear_scene();
+            self.scene.data_mut().render(pass, camera, &self.light_mode);
+        }
+
+        camera.render_complete(&self.canvas);
+
+        self.framebuffer_manager
+            .select(&FramebufferManager::screen());
+        verify!(ctxt.viewport(0, 0, width as i32, height as i32));
+        verify!(ctxt.scissor(0, 0, width as i32, height as i32));
     }
 
     fn do_render_with(
@@ -1046,10 +2219,11 @@ impl Window {
         camera: Option<&mut dyn Camera>,
         planar_camera: Option<&mut dyn PlanarCamera>,
         renderer: Option<&mut dyn Renderer>,
-        post_processing: Option<&mut dyn PostProcessingEffect>,
+        post_processing: &mut [&mut dyn PostProcessingEffect],
     ) -> bool {
         let mut camera = camera;
         let mut planar_camera = planar_camera;
+        self.canvas.make_current();
         self.handle_events(&mut camera, &mut planar_camera);
 
         let self_cam2 = self.planar_camera.clone(); // FIXME: this is ugly.
@@ -1060,16 +2234,16 @@ impl Window {
 
         match (camera, planar_camera) {
             (Some(cam), Some(cam2)) => {
-                self.render_single_frame(cam, cam2, renderer, post_processing)
+                self.render_single_frame(&mut [cam], cam2, renderer, post_processing)
             }
             (None, Some(cam2)) => {
-                self.render_single_frame(&mut *bself_cam, cam2, renderer, post_processing)
+                self.render_single_frame(&mut [&mut *bself_cam], cam2, renderer, post_processing)
             }
             (Some(cam), None) => {
-                self.render_single_frame(cam, &mut *bself_cam2, renderer, post_processing)
+                self.render_single_frame(&mut [cam], &mut *bself_cam2, renderer, post_processing)
             }
             (None, None) => self.render_single_frame(
-                &mut *bself_cam,
+                &mut [&mut *bself_cam],
                 &mut *bself_cam2,
                 renderer,
                 post_processing,
@@ -1077,27 +2251,108 @@ impl Window {
         }
     }
 
+    /// Renders the scene once per camera, each camera drawing into its own viewport.
+    ///
+    /// This is typically used for split-screen or picture-in-picture rendering: see
+    /// `ArcBall::set_viewport` for example. Cameras without viewport render into the whole
+    /// window, so they should come first. The text labels and post-processing effects use the
+    /// first camera, which is also the only one receiving the window events.
+    ///
+    /// Returns `false` if the window should be closed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_with_multiple_cameras(&mut self, cameras: &mut [&mut dyn Camera]) -> bool {
+        assert!(!cameras.is_empty(), "At least one camera must be provided.");
+        self.canvas.make_current();
+        self.handle_events(&mut Some(&mut *cameras[0]), &mut None);
+
+        let self_cam2 = self.planar_camera.clone(); // FIXME: this is ugly.
+        let mut bself_cam2 = self_cam2.borrow_mut();
+
+        self.render_single_frame(cameras, &mut *bself_cam2, None, &mut [])
+    }
+
     fn render_single_frame(
         &mut self,
-        camera: &mut dyn Camera,
+        cameras: &mut [&mut dyn Camera],
         planar_camera: &mut dyn PlanarCamera,
         mut renderer: Option<&mut dyn Renderer>,
-        mut post_processing: Option<&mut dyn PostProcessingEffect>,
+        post_processing: &mut [&mut dyn PostProcessingEffect],
     ) -> bool {
         // XXX: too bad we have to do this at each frame…
         let w = self.width();
         let h = self.height();
 
-        planar_camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
-        camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
+        let now = instant::Instant::now();
+        let dt = now.duration_since(self.prev_frame_time).as_secs_f32();
+        self.prev_frame_time = now;
+
+        if let Some(step) = self.fixed_timestep {
+            self.fixed_step_accumulator =
+                (self.fixed_step_accumulator + dt).min(step * MAX_FIXED_STEPS_PER_FRAME as f32);
+        }
+
+        if self.show_fps || self.fps_title.is_some() {
+            if self.frame_times.len() == FPS_SAMPLES {
+                let _ = self.frame_times.pop_front();
+            }
+
+            self.frame_times.push_back(dt);
+        }
+
+        if let Some(base) = &self.fps_title {
+            self.fps_title_elapsed += dt;
+
+            if self.fps_title_elapsed >= FPS_TITLE_PERIOD {
+                self.fps_title_elapsed = 0.0;
+
+                if let Some(frame_time) = self.average_frame_time() {
+                    let title = format!("{} - {:.0} FPS", base, 1.0 / frame_time);
+                    self.canvas.set_title(&title);
+                }
+            }
+        }
+
+        // The framebuffer is empty when the window is minimized.
+        if w != 0 && h != 0 {
+            planar_camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(w, h));
+
+            for camera in cameras.iter_mut() {
+                let (vw, vh) = match camera.viewport() {
+                    Some((_, _, vw, vh)) => (vw.max(0) as u32, vh.max(0) as u32),
+                    None => (w, h),
+                };
+
+                if vw != 0 && vh != 0 {
+                    camera.handle_event(&self.canvas, &WindowEvent::FramebufferSize(vw, vh));
+                }
+            }
+        }
         planar_camera.update(&self.canvas);
-        camera.update(&self.canvas);
+
+        for camera in cameras.iter_mut() {
+            camera.update(&self.canvas);
+        }
 
         if let Light::StickToCamera = self.light_mode {
             self.set_light(Light::StickToCamera)
         }
 
-        if post_processing.is_some() {
+        match (&mut self.shadow_renderer, &self.light_mode) {
+            (Some(shadow_renderer), Light::Absolute(light_position)) => {
+                shadow_renderer.render(
+                    &mut self.framebuffer_manager,
+                    &mut self.scene.data_mut(),
+                    light_position,
+                );
+
+                let ctxt = Context::get();
+                verify!(ctxt.viewport(0, 0, w as i32, h as i32));
+                verify!(ctxt.scissor(0, 0, w as i32, h as i32));
+            }
+            _ => ShadowMapRenderer::disable(),
+        }
+
+        if !post_processing.is_empty() {
             // if we need post-processing, render to our own frame buffer
             self.framebuffer_manager
                 .select(&self.post_process_render_target);
@@ -1106,19 +2361,40 @@ impl Window {
                 .select(&FramebufferManager::screen());
         }
 
-        for pass in 0usize..camera.num_passes() {
-            camera.start_pass(pass, &self.canvas);
-            self.render_scene(camera, pass);
+        let ctxt = Context::get();
 
-            if let Some(ref mut renderer) = renderer {
-                renderer.render(pass, camera)
+        for camera in cameras.iter_mut() {
+            let viewport = camera.viewport();
+
+            if let Some((x, y, vw, vh)) = viewport {
+                if vw <= 0 || vh <= 0 {
+                    continue;
+                }
+
+                verify!(ctxt.viewport(x, y, vw, vh));
+                verify!(ctxt.scissor(x, y, vw, vh));
             }
-        }
 
-        camera.render_complete(&self.canvas);
+            for pass in 0usize..camera.num_passes() {
+                camera.start_pass(pass, &self.canvas);
+                self.render_scene(&mut **camera, pass, !post_processing.is_empty());
+
+                if let Some(ref mut renderer) = renderer {
+                    renderer.render(pass, &mut **camera)
+                }
+            }
 
-        self.render_planar_scene(planar_camera);
+            camera.render_complete(&self.canvas);
 
+            if viewport.is_some() {
+                verify!(ctxt.viewport(0, 0, w as i32, h as i32));
+                verify!(ctxt.scissor(0, 0, w as i32, h as i32));
+            }
+        }
+
+        self.render_planar_scene(planar_camera, w as f32, h as f32);
+
+        let camera = &mut *cameras[0];
         let (znear, zfar) = camera.clip_planes();
 
         // FIXME: remove this completely?
@@ -1127,25 +2403,68 @@ impl Window {
         //     verify!(gl::PolygonMode(Context::FRONT_AND_BACK, Context::FILL));
         // }
 
-        if let Some(ref mut p) = post_processing {
-            // switch back to the screen framebuffer …
-            self.framebuffer_manager
-                .select(&FramebufferManager::screen());
-            // … and execute the post-process
-            // FIXME: use the real time value instead of 0.016!
-            p.update(0.016, w as f32, h as f32, znear, zfar);
-            p.draw(&self.post_process_render_target);
+        let num_effects = post_processing.len();
+
+        for (i, p) in post_processing.iter_mut().enumerate() {
+            // Ping-pong between our two render targets, the last effect
+            // being drawn on the screen framebuffer.
+            let (source, dest) = if i % 2 == 0 {
+                (
+                    &self.post_process_render_target,
+                    &self.post_process_chain_target,
+                )
+            } else {
+                (
+                    &self.post_process_chain_target,
+                    &self.post_process_render_target,
+                )
+            };
+
+            if i + 1 == num_effects {
+                self.framebuffer_manager
+                    .select(&FramebufferManager::screen());
+            } else {
+                self.framebuffer_manager.select(dest);
+            }
+
+            p.update(dt, w as f32, h as f32, znear, zfar);
+            p.draw(source);
         }
 
+        for f in self.raw_context_callbacks.drain(..) {
+            f(&ctxt)
+        }
+
+        self.rectangle_renderer.render(w as f32, h as f32);
+        self.queue_text_labels(camera, w as f32, h as f32);
+        if self.show_fps {
+            self.queue_fps_counter(w as f32);
+        }
         self.text_renderer.render(w as f32, h as f32);
         #[cfg(feature = "conrod")]
         self.conrod_context.renderer.render(
             w as f32,
             h as f32,
-            self.canvas.scale_factor() as f32,
+            self.ui_scale(),
             &self.conrod_context.textures,
         );
 
+        if self.opacity < 1.0 {
+            // Compositors expect premultiplied colors: scale the colors of the whole frame by the
+            // opacity by blending a black rectangle over it, then overwrite its alpha channel.
+            self.rectangle_renderer.draw_rectangle(
+                Point2::origin(),
+                Point2::new(w as f32, h as f32),
+                Point4::new(0.0, 0.0, 0.0, 1.0 - self.opacity),
+            );
+            verify!(ctxt.color_mask(true, true, true, false));
+            self.rectangle_renderer.render(w as f32, h as f32);
+            verify!(ctxt.color_mask(false, false, false, true));
+            verify!(ctxt.clear_color(0.0, 0.0, 0.0, self.opacity));
+            verify!(ctxt.clear(Context::COLOR_BUFFER_BIT));
+            verify!(ctxt.color_mask(true, true, true, true));
+        }
+
         // We are done: swap buffers
         self.canvas.swap_buffers();
 
@@ -1168,7 +2487,79 @@ impl Window {
         !self.should_close()
     }
 
-    fn render_scene(&mut self, camera: &mut dyn Camera, pass: usize) {
+    fn queue_text_labels(&mut self, camera: &mut dyn Camera, w: f32, h: f32) {
+        let transformation = camera.transformation();
+        let text_scale = self.ui_scale();
+
+        for (_, label) in &self.text_labels {
+            let h_pos = transformation * label.world_pos.to_homogeneous();
+
+            // Skip labels behind the camera.
+            if h_pos.w <= 0.0 {
+                continue;
+            }
+
+            let pos = Point2::new(
+                (1.0 + h_pos.x / h_pos.w) * w / 2.0,
+                (1.0 - h_pos.y / h_pos.w) * h / 2.0,
+            );
+
+            if label.depth_test {
+                self.text_renderer.draw_text_with_depth(
+                    &label.text,
+                    &pos,
+                    h_pos.z / h_pos.w,
+                    label.scale * text_scale,
+                    &label.font,
+                    &label.color,
+                );
+            } else {
+                self.text_renderer.draw_text(
+                    &label.text,
+                    &pos,
+                    label.scale * text_scale,
+                    &label.font,
+                    &label.color,
+                );
+            }
+        }
+    }
+
+    // The frame duration averaged over the last frames, if known.
+    fn average_frame_time(&self) -> Option<f32> {
+        let total: f32 = self.frame_times.iter().sum();
+
+        if total <= 0.0 {
+            None
+        } else {
+            Some(total / self.frame_times.len() as f32)
+        }
+    }
+
+    fn queue_fps_counter(&mut self, w: f32) {
+        let frame_time = match self.average_frame_time() {
+            Some(frame_time) => frame_time,
+            None => return,
+        };
+        let text = format!(
+            "{:.0} FPS ({:.2} ms)",
+            1.0 / frame_time,
+            frame_time * 1000.0
+        );
+        let scale = self.ui_scale();
+
+        self.text_renderer.draw_text_ex(
+            &text,
+            &Point2::new(w - 10.0 * scale, 10.0 * scale),
+            30.0 * scale,
+            &Font::default(),
+            &Point3::new(1.0, 1.0, 1.0),
+            TextAlign::Right,
+            None,
+        );
+    }
+
+    fn clear_scene(&self) {
         let ctxt = Context::get();
         // Activate the default texture
         verify!(ctxt.active_texture(Context::TEXTURE0));
@@ -1176,13 +2567,42 @@ impl Window {
         verify!(ctxt.clear_color(self.background.x, self.background.y, self.background.z, 1.0));
         verify!(ctxt.clear(Context::COLOR_BUFFER_BIT));
         verify!(ctxt.clear(Context::DEPTH_BUFFER_BIT));
+        verify!(ctxt.clear(Context::STENCIL_BUFFER_BIT));
+    }
+
+    fn render_scene(&mut self, camera: &mut dyn Camera, pass: usize, post_processing: bool) {
+        self.clear_scene();
+        crate::builtin::set_wireframe_mode(self.wireframe_mode);
 
         self.line_renderer.render(pass, camera);
         self.point_renderer.render(pass, camera);
-        self.scene.data_mut().render(pass, camera, &self.light_mode);
+
+        match (self.transparency_mode, &mut self.oit_renderer) {
+            (TransparencyMode::WeightedBlended, Some(oit_renderer)) => {
+                let mut scene = self.scene.data_mut();
+                scene.render_opaque(pass, camera, &self.light_mode);
+
+                let screen = FramebufferManager::screen();
+                let target = if post_processing {
+                    &self.post_process_render_target
+                } else {
+                    &screen
+                };
+
+                oit_renderer.render(
+                    &mut self.framebuffer_manager,
+                    target,
+                    &mut scene,
+                    pass,
+                    camera,
+                    &self.light_mode,
+                );
+            }
+            _ => self.scene.data_mut().render(pass, camera, &self.light_mode),
+        }
     }
 
-    fn render_planar_scene(&mut self, camera: &mut dyn PlanarCamera) {
+    fn render_planar_scene(&mut self, camera: &mut dyn PlanarCamera, width: f32, height: f32) {
         let ctxt = Context::get();
         // Activate the default texture
         verify!(ctxt.active_texture(Context::TEXTURE0));
@@ -1196,14 +2616,28 @@ impl Window {
         //     self.point_renderer2.render(camera);
         // }
 
-        self.scene2.data_mut().render(camera);
+        let mut scene2 = self.scene2.data_mut();
+        scene2.render_with_scratch(camera, &mut self.scene2_render_order);
+        scene2.queue_texts(&mut self.text_renderer, camera, width, height);
+        self.sprite_batch_renderer
+            .render(camera, &self.sprite_batches);
     }
 
     fn update_viewport(&mut self, w: f32, h: f32) {
+        // The framebuffer is empty when the window is minimized: keep the previous viewport.
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+
         // Update the viewport
         verify!(Context::get().scissor(0, 0, w as i32, h as i32));
         FramebufferManager::screen().resize(w, h);
         self.post_process_render_target.resize(w, h);
+        self.post_process_chain_target.resize(w, h);
+
+        if let Some(oit_renderer) = &mut self.oit_renderer {
+            oit_renderer.resize(w, h);
+        }
     }
 }
 
@@ -1,6 +1,9 @@
 use std::{cell::RefCell, mem::take};
 
-use crate::resource::{MaterialManager, MeshManager, TextureManager};
+use crate::renderer::shadow_map::ShadowMapRenderer;
+use crate::resource::{
+    MaterialManager, MeshManager, PlanarMaterialManager, PlanarMeshManager, TextureManager,
+};
 
 #[derive(Default)]
 /// Globally accessible cache of objects
@@ -8,23 +11,47 @@ pub(crate) struct WindowCache {
     pub(crate) mesh_manager: Option<MeshManager>,
     pub(crate) texture_manager: Option<TextureManager>,
     pub(crate) material_manager: Option<MaterialManager>,
+    window_count: usize,
 }
 
 thread_local!(pub(crate) static WINDOW_CACHE: RefCell<WindowCache>  = RefCell::new(WindowCache::default()));
 
 impl WindowCache {
-    /// Initialize resource managers
+    /// Initialize resource managers, unless they are already shared by another window
     pub fn populate() {
         WINDOW_CACHE.with(|cache| {
-            cache.borrow_mut().mesh_manager = Some(MeshManager::new());
-            cache.borrow_mut().texture_manager = Some(TextureManager::new());
-            cache.borrow_mut().material_manager = Some(MaterialManager::new());
+            let mut cache = cache.borrow_mut();
+
+            if cache.window_count == 0 {
+                cache.mesh_manager = Some(MeshManager::new());
+                cache.texture_manager = Some(TextureManager::new());
+                cache.material_manager = Some(MaterialManager::new());
+            }
+
+            cache.window_count += 1;
         });
     }
 
-    /// Clear the cache dropping all resources
-    #[allow(unused_results)]
+    /// Clear the cache dropping all resources, once the last window using it is closed
     pub fn clear() {
-        WINDOW_CACHE.with(|cache| take(&mut *cache.borrow_mut()));
+        let resources = WINDOW_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.window_count = cache.window_count.saturating_sub(1);
+
+            if cache.window_count == 0 {
+                Some(take(&mut *cache))
+            } else {
+                None
+            }
+        });
+
+        // The resources are dropped after the cache is released.
+        if let Some(resources) = resources {
+            drop(resources);
+            PlanarMeshManager::clear_global_manager();
+            PlanarMaterialManager::clear_global_manager();
+            // The shadow map texture belongs to the context of the last window.
+            ShadowMapRenderer::disable();
+        }
     }
 }
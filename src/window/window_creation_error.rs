@@ -0,0 +1,38 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when a window and its rendering context could not be created.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WindowCreationError {
+    /// No OpenGL context could be created, even after trying all the fallback configurations.
+    ///
+    /// Contains the error message of the last attempt.
+    ContextCreationFailed(String),
+    /// The OpenGL context was created but could not be made current.
+    ContextActivationFailed(String),
+    /// No HTML canvas with the id `canvas` was found in the document (web only).
+    CanvasNotFound,
+    /// The HTML canvas does not support WebGL (web only).
+    WebGlUnavailable,
+}
+
+impl fmt::Display for WindowCreationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WindowCreationError::ContextCreationFailed(e) => {
+                write!(f, "failed to create an OpenGL context: {}", e)
+            }
+            WindowCreationError::ContextActivationFailed(e) => {
+                write!(f, "failed to make the OpenGL context current: {}", e)
+            }
+            WindowCreationError::CanvasNotFound => {
+                write!(f, "no canvas element with the id `canvas` was found")
+            }
+            WindowCreationError::WebGlUnavailable => {
+                write!(f, "WebGL is not available on this canvas")
+            }
+        }
+    }
+}
+
+impl Error for WindowCreationError {}